@@ -0,0 +1,40 @@
+//! Compares fanning a large buffer out to many children by deep cloning and by [`SharedValue`].
+//!
+//! Run with `cargo bench --bench fanout`.
+
+use async_dag::Graph;
+use async_dag::IntoAny;
+use async_dag::SharedValue;
+use futures::executor::block_on;
+use std::time::Duration;
+use std::time::Instant;
+
+const BUFFER_SIZE: usize = 8 << 20;
+const CHILDREN: usize = 32;
+const ITERATIONS: u32 = 10;
+
+fn fanout<T: IntoAny + Clone>(make: fn() -> T, len: fn(&T) -> usize) -> Duration {
+    let mut total = Duration::ZERO;
+    for _ in 0..ITERATIONS {
+        let mut graph = Graph::new();
+        let buffer = graph.add_task(move || async move { make() });
+        for _ in 0..CHILDREN {
+            let _ = graph
+                .add_child_task(buffer, move |v: T| async move { len(&v) }, 0)
+                .unwrap();
+        }
+        let start = Instant::now();
+        block_on(graph.run());
+        total += start.elapsed();
+    }
+    total / ITERATIONS
+}
+
+fn main() {
+    let cloned = fanout(|| vec![0u8; BUFFER_SIZE], Vec::len);
+    let shared = fanout(|| SharedValue::new(vec![0u8; BUFFER_SIZE]), |v| v.len());
+    println!(
+        "fanning {} bytes out to {} children: Vec {:?}, SharedValue {:?}",
+        BUFFER_SIZE, CHILDREN, cloned, shared
+    );
+}
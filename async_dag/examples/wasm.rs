@@ -0,0 +1,32 @@
+//! Runs a graph in the browser.
+//!
+//! Build with `cargo build --example wasm --target wasm32-unknown-unknown`
+//! and load the output with `wasm-bindgen`.
+
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    use async_dag::LocalGraph;
+    use std::rc::Rc;
+
+    async fn sum(lhs: i32, rhs: i32) -> i32 {
+        lhs + rhs
+    }
+
+    wasm_bindgen_futures::spawn_local(async {
+        let mut graph = LocalGraph::new();
+        let lhs = graph.add_task(|| async { 1 });
+        // Futures on the browser's event loop needn't be `Send`.
+        let rhs = graph.add_local_task(|| async { *Rc::new(2) });
+        let root = graph.add_task(sum);
+        graph.update_dependency(lhs, root, 0).unwrap();
+        graph.update_dependency(rhs, root, 1).unwrap();
+        graph.run_local().await;
+        let result = graph.get_value::<i32>(root).unwrap();
+        web_sys::console::log_1(&format!("Result: {}", result).into());
+    });
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    eprintln!("This example only runs on wasm32.");
+}
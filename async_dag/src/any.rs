@@ -5,27 +5,24 @@ use std::{
 };
 
 /// Conversion to [`Any`] to workaround [#65991](https://github.com/rust-lang/rust/issues/65991).
-/// Implemented for anything that's `'static` and [`Clone`].
-pub trait IntoAny: DynClone + Any {
+/// Implemented for anything that's `'static`, [`Clone`] and [`Send`].
+pub trait IntoAny: DynClone + Any + Send {
     /// The conversion.
     fn into_any(self: Box<Self>) -> Box<dyn Any>;
 }
 
 dyn_clone::clone_trait_object!(IntoAny);
 
-impl<T: 'static + Clone> IntoAny for T {
+impl<T: 'static + Clone + Send> IntoAny for T {
     fn into_any(self: Box<Self>) -> Box<dyn Any> {
-        Box::new(*self)
+        // Reuses the allocation.
+        self
     }
 }
 
-pub fn downcast<T: 'static>(value: Box<dyn IntoAny>) -> Result<T, Box<dyn IntoAny>> {
-    if (*value).type_id() != TypeId::of::<T>() {
-        return Err(value);
-    }
-    let value = value.into_any();
-    // We've checked the type id.
-    Ok(*Box::<dyn Any + 'static>::downcast::<T>(value).unwrap())
+/// Takes the `T` out of `value`, or gives `value` back if it holds another type.
+pub fn downcast<T: 'static>(value: DynAny) -> Result<T, DynAny> {
+    value.downcast()
 }
 
 /// A [`TypeId`] and the type's name.
@@ -72,7 +69,7 @@ impl Eq for TypeInfo {}
 
 impl PartialOrd for TypeInfo {
     fn partial_cmp(&self, other: &TypeInfo) -> Option<std::cmp::Ordering> {
-        self.id.partial_cmp(&other.id)
+        Some(self.cmp(other))
     }
 }
 
@@ -82,11 +79,187 @@ impl Ord for TypeInfo {
     }
 }
 
-/// A [`Box`]ed [`IntoAny`].
-pub type DynAny = Box<dyn IntoAny>;
+/// A value of any [`IntoAny`] type, see [`SmallAny`].
+pub type DynAny = SmallAny;
+
+/// A value of any [`IntoAny`] type.
+///
+/// Small primitives, e.g. `i32` or `f64`, are stored inline, anything else is [`Box`]ed.
+#[derive(Clone)]
+pub struct SmallAny(Repr);
+
+macro_rules! small_any {
+    ($($variant:ident($ty:ty),)*) => {
+        #[derive(Clone)]
+        enum Repr {
+            $($variant($ty),)*
+            Boxed(Box<dyn IntoAny>),
+        }
+
+        impl SmallAny {
+            /// Stores `value`, inline if it's a small primitive.
+            ///
+            /// The stored type is always `T`, even if `T` is itself a [`SmallAny`].
+            pub fn new<T: IntoAny>(value: T) -> Self {
+                let mut value = Some(value);
+                let any: &mut dyn Any = &mut value;
+                $(
+                    if let Some(value) = any.downcast_mut::<Option<$ty>>() {
+                        return Self(Repr::$variant(value.take().unwrap()));
+                    }
+                )*
+                Self(Repr::Boxed(Box::new(value.unwrap())))
+            }
+
+            /// Stores an already type erased `value`, moving small primitives inline
+            /// and unwrapping a boxed [`SmallAny`] rather than nesting it.
+            pub(crate) fn from_erased(value: Box<dyn IntoAny>) -> Self {
+                let id = (*value).type_id();
+                $(
+                    if id == TypeId::of::<$ty>() {
+                        // We've checked the type id.
+                        return Self(Repr::$variant(*value.into_any().downcast::<$ty>().unwrap()));
+                    }
+                )*
+                if id == TypeId::of::<SmallAny>() {
+                    return *value.into_any().downcast::<SmallAny>().unwrap();
+                }
+                Self(Repr::Boxed(value))
+            }
+
+            /// The [`TypeId`] of the stored value.
+            pub fn type_id(&self) -> TypeId {
+                match &self.0 {
+                    $(Repr::$variant(_) => TypeId::of::<$ty>(),)*
+                    Repr::Boxed(value) => (**value).type_id(),
+                }
+            }
+
+            /// Takes the `T` out, or gives `self` back if it holds another type.
+            pub fn downcast<T: 'static>(self) -> Result<T, Self> {
+                match self.0 {
+                    $(Repr::$variant(value) => {
+                        let mut value = Some(value);
+                        let any: &mut dyn Any = &mut value;
+                        match any.downcast_mut::<Option<T>>() {
+                            Some(value) => Ok(value.take().unwrap()),
+                            None => Err(Self(Repr::$variant(value.unwrap()))),
+                        }
+                    })*
+                    Repr::Boxed(value) => {
+                        if (*value).type_id() != TypeId::of::<T>() {
+                            return Err(Self(Repr::Boxed(value)));
+                        }
+                        // We've checked the type id.
+                        Ok(*value.into_any().downcast::<T>().unwrap())
+                    }
+                }
+            }
 
-impl std::fmt::Debug for DynAny {
+            /// Borrows the stored value if it's a `T`.
+            pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+                let any: &dyn Any = match &self.0 {
+                    $(Repr::$variant(value) => value,)*
+                    Repr::Boxed(value) => &**value,
+                };
+                any.downcast_ref()
+            }
+
+            /// Boxes the stored value.
+            pub fn into_any(self) -> Box<dyn Any> {
+                match self.0 {
+                    $(Repr::$variant(value) => Box::new(value),)*
+                    Repr::Boxed(value) => value.into_any(),
+                }
+            }
+        }
+    };
+}
+
+small_any! {
+    Unit(()),
+    Bool(bool),
+    Char(char),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    Isize(isize),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    Usize(usize),
+    F32(f32),
+    F64(f64),
+}
+
+impl SmallAny {
+    /// Returns `true` if the value is stored in a [`Box`], so cloning it allocates.
+    pub fn is_boxed(&self) -> bool {
+        matches!(self.0, Repr::Boxed(_))
+    }
+}
+
+impl From<Box<dyn IntoAny>> for SmallAny {
+    fn from(value: Box<dyn IntoAny>) -> Self {
+        Self::from_erased(value)
+    }
+}
+
+impl std::fmt::Debug for SmallAny {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("NamedAny").finish_non_exhaustive()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::DynAny;
+    use super::IntoAny;
+    use super::SmallAny;
+    use crate::Graph;
+    use futures::executor::block_on;
+    use std::any::TypeId;
+
+    #[test]
+    fn test_small_any() {
+        let value = SmallAny::new(1i32);
+        assert!(!value.is_boxed());
+        assert_eq!(value.type_id(), TypeId::of::<i32>());
+        assert_eq!(value.downcast_ref::<i32>(), Some(&1));
+        let value = value.downcast::<u32>().unwrap_err();
+        assert_eq!(value.downcast::<i32>().unwrap(), 1);
+
+        let value = SmallAny::new("one".to_owned());
+        assert!(value.is_boxed());
+        assert_eq!(value.type_id(), TypeId::of::<String>());
+        let value = value.downcast::<i32>().unwrap_err();
+        assert_eq!(value.downcast::<String>().unwrap(), "one");
+    }
+
+    #[test]
+    fn test_small_any_from_box() {
+        let boxed: Box<dyn IntoAny> = Box::new(2u8);
+        let value = SmallAny::from(boxed);
+        assert!(!value.is_boxed());
+        assert_eq!(value.downcast::<u8>().unwrap(), 2);
+        let boxed: Box<dyn IntoAny> = Box::new(SmallAny::new(1.0f64));
+        assert_eq!(SmallAny::from(boxed).type_id(), TypeId::of::<f64>());
+    }
+
+    #[test]
+    fn test_dyn_any_output() {
+        let mut graph = Graph::new();
+        let value = graph.add_task(|| async { DynAny::new(1.0f64) });
+        let child = graph.add_task(|value: DynAny| async move {
+            assert_eq!(value.type_id(), TypeId::of::<f64>());
+            value.downcast::<f64>().unwrap() + 1.0
+        });
+        graph.update_dependency(value, child, 0).unwrap();
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<f64>(child).unwrap(), 2.0);
+        let value = graph.get_value::<DynAny>(value).unwrap();
+        assert_eq!(value.downcast::<f64>().unwrap(), 1.0);
+    }
+}
@@ -11,6 +11,7 @@ use futures::future::BoxFuture;
 use futures::FutureExt;
 use futures::TryFutureExt;
 
+/// The future of a running task, outputting a type erased value.
 pub type TaskFuture<'a, Err> = BoxFuture<'a, Result<DynAny, Err>>;
 
 /// [`Curry`] describes the process of currying and finally calling.
@@ -34,26 +35,62 @@ pub trait Curry<'a, Err> {
 
     /// Consumes the inner task and inputs and returns a future of the output value.
     fn call(self: Box<Self>) -> Result<TaskFuture<'a, Err>, TakeError>;
+
+    /// Clones the inner task and inputs, [`None`] if the task can't be cloned.
+    fn clone_curry(&self) -> Option<Box<dyn Curry<'a, Err> + Send + 'a>> {
+        None
+    }
 }
 
 /// [`CurriedTask`] holds a task and its inputs and tracks if all inputs are ready.
 pub struct CurriedTask<'a, Err, T: TryTask<'a, Err = Err>> {
     task: T,
     inputs: <T::Inputs as Tuple>::Option,
+    clone: Option<CloneFn<'a, Err, T>>,
 }
 
+type CloneFn<'a, Err, T> = fn(&CurriedTask<'a, Err, T>) -> Box<dyn Curry<'a, Err> + Send + 'a>;
+
 impl<'a, Err, T: TryTask<'a, Err = Err>> CurriedTask<'a, Err, T> {
     /// Creates a [CurriedTask] from a task and no inputs.
     pub fn new(task: T) -> Self {
         CurriedTask {
             task,
             inputs: Default::default(),
+            clone: None,
         }
     }
+
+    /// Creates a [CurriedTask] from a task and no inputs, which can be cloned by [`Curry::clone_curry`].
+    pub fn new_cloneable(task: T) -> Self
+    where
+        Err: 'a,
+        T: Clone + 'a,
+        <T::Inputs as Tuple>::Option: Clone,
+    {
+        CurriedTask {
+            task,
+            inputs: Default::default(),
+            clone: Some(Self::clone_boxed),
+        }
+    }
+
+    fn clone_boxed(&self) -> Box<dyn Curry<'a, Err> + Send + 'a>
+    where
+        Err: 'a,
+        T: Clone + 'a,
+        <T::Inputs as Tuple>::Option: Clone,
+    {
+        Box::new(CurriedTask {
+            task: self.task.clone(),
+            inputs: self.inputs.clone(),
+            clone: self.clone,
+        })
+    }
 }
 
 fn make_any<T: IntoAny>(t: T) -> DynAny {
-    Box::new(t)
+    DynAny::new(t)
 }
 
 impl<'a, Err, T: TryTask<'a, Err = Err>> Curry<'a, Err> for CurriedTask<'a, Err, T> {
@@ -78,10 +115,16 @@ impl<'a, Err, T: TryTask<'a, Err = Err>> Curry<'a, Err> for CurriedTask<'a, Err,
     }
 
     fn call(self: Box<Self>) -> Result<TaskFuture<'a, Err>, TakeError> {
-        let CurriedTask { task, mut inputs } = *self;
+        let CurriedTask {
+            task, mut inputs, ..
+        } = *self;
         let inputs = inputs.take()?;
         let future = task.run(inputs);
         let future = future.map_ok(make_any);
         Ok(future.boxed())
     }
+
+    fn clone_curry(&self) -> Option<Box<dyn Curry<'a, Err> + Send + 'a>> {
+        self.clone.map(|clone| clone(self))
+    }
 }
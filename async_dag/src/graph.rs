@@ -1,5 +1,69 @@
+mod adapter;
+mod builder;
+mod bulk;
+mod cache;
+mod checkpoint;
+mod clock;
+mod clone;
+mod collect;
+mod context;
+mod data;
+mod dot;
+mod dot_import;
+#[cfg(feature = "serde")]
+mod dynamic;
+mod edge;
 pub mod error;
+mod expr;
+#[cfg(test)]
+mod fixtures;
+mod gather;
+mod group;
+mod handle;
+mod index;
+mod input;
+#[cfg(feature = "inspector")]
+mod inspect;
+mod intern;
+mod lane;
+mod layer;
+mod ledger;
+mod lint;
+mod local;
+#[cfg(feature = "log")]
+mod log;
+mod memory;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod options;
+#[cfg(feature = "opentelemetry")]
+mod otel;
+mod output;
+mod params;
+mod pipeline;
+#[cfg(feature = "serde")]
+mod registry;
+mod render;
+mod replay;
+mod report;
+mod resource;
 mod runner;
+mod schedule;
+mod scope;
+mod simulate;
+#[cfg(feature = "tracing")]
+mod spans;
+mod spawn;
+mod spec;
+#[cfg(any(feature = "inspector", feature = "tui"))]
+mod states;
+mod task;
+mod topology;
+#[cfg(feature = "tui")]
+mod tui;
+mod unrun;
+mod watch;
+mod watchdog;
 
 use crate::any::downcast;
 use crate::any::DynAny;
@@ -7,19 +71,36 @@ use crate::any::IntoAny;
 use crate::any::TypeInfo;
 use crate::curry::CurriedTask;
 use crate::curry::Curry;
+use crate::curry::TaskFuture;
+use crate::task::IntoSyncTryTask;
 use crate::task::IntoTryTask;
 use crate::task::TryTask;
+use crate::tuple::In;
+use crate::tuple::InputIndex;
 use crate::tuple::Tuple;
 use crate::tuple::TupleIndex;
+use adapter::Adapters;
+use daggy::petgraph::graph::IndexType;
 use daggy::EdgeIndex;
 use error::Error;
 use error::ErrorWithTask;
+use error::ExecuteError;
+use error::GetValueError;
+use error::NodeLabel;
+use error::RunError;
+use layer::Layers;
 use runner::Runner;
+use schedule::Schedule;
 use std::any::type_name;
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::future::Future;
 
 /// A [`Box`]ed [`Curry`].
-type DynCurry<'a, Err> = Box<dyn Curry<'a, Err> + 'a>;
+type DynCurry<'a, Err> = Box<dyn Curry<'a, Err> + Send + 'a>;
+
+// The panic message of runs not checked like `TryGraph::try_run_checked`.
+const UNCHECKED: &str = "Expecting values to pass between nodes";
 
 impl<'a, Err> std::fmt::Debug for DynCurry<'a, Err> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -31,8 +112,9 @@ impl<'a, Err> std::fmt::Debug for DynCurry<'a, Err> {
 /// Node type.
 ///
 /// A node is either a [`Curry`], running (with a certain output type),
-/// or the [`Curry`]'s awaited successful calling output.
+/// the [`Curry`]'s awaited successful calling output, failed, or skipped.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Node<'a, Err> {
     /// A [`Curry`].
     Curry(DynCurry<'a, Err>),
@@ -47,9 +129,38 @@ pub enum Node<'a, Err> {
         /// The output type.
         type_info: TypeInfo,
     },
+    /// A completed node whose value was dropped after all its children received it,
+    /// see [`RunOptions::drop_consumed_values`].
+    Dropped(TypeInfo),
+    /// A node whose task failed, kept by [`RunOptions::continue_on_error`].
+    Failed {
+        /// The task's error.
+        error: Err,
+        /// The output type.
+        type_info: TypeInfo,
+    },
+    /// A node that will never run, e.g. cancelled by [`RunHandle::cancel_subtree`].
+    Skipped {
+        /// The output type.
+        type_info: TypeInfo,
+        /// The failed or cancelled node that caused the skip, which is the node itself if it was cancelled directly.
+        cause: NodeIndex,
+    },
 }
 
 impl<'a, Err> Node<'a, Err> {
+    /// Gets the output type.
+    pub(super) fn output_type_info(&self) -> TypeInfo {
+        match self {
+            Node::Curry(curry) => curry.output_type_info(),
+            Node::Running(type_info) => *type_info,
+            Node::Value { type_info, .. } => *type_info,
+            Node::Dropped(type_info) => *type_info,
+            Node::Failed { type_info, .. } => *type_info,
+            Node::Skipped { type_info, .. } => *type_info,
+        }
+    }
+
     /// Converts this [`Node`] into a concrete type.
     ///
     /// Returns `self` on failure.
@@ -65,8 +176,41 @@ impl<'a, Err> Node<'a, Err> {
     }
 }
 
+/// A node of the [`daggy::Dag`] underlying a [`TryGraph`], see [`TryGraph::as_dag`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct NodeWeight<'a, Err, D> {
+    /// The node's state.
+    pub node: Node<'a, Err>,
+    /// The input types of the node's task, kept after the task is called.
+    pub inputs: Vec<TypeInfo>,
+    /// The data attached by [`TryGraph::set_node_data`], if any.
+    pub data: Option<D>,
+}
+
+impl<'a, Err, D> NodeWeight<'a, Err, D> {
+    fn new(curry: DynCurry<'a, Err>) -> Self {
+        let inputs = (0..curry.num_inputs())
+            .map(|index| curry.input_type_info(index).unwrap())
+            .collect();
+        Self {
+            node: Node::Curry(curry),
+            inputs,
+            data: None,
+        }
+    }
+}
+
+/// The integer type of [`NodeIndex`]es, bounding how many nodes and dependencies a graph can have.
+///
+/// `u32` by default. The `small-index` feature shrinks it to `u16` for many small graphs,
+/// and the `large-index` feature grows it to `usize` for huge graphs. If both are enabled, `large-index` wins.
+///
+/// Adding a dependency beyond what the index type can address fails with [`Error::IndexOverflow`].
+pub type Index = index::Index;
+
 /// Node identifier.
-pub type NodeIndex = daggy::NodeIndex;
+pub type NodeIndex = daggy::NodeIndex<Index>;
 
 /// Edge type.
 ///
@@ -75,21 +219,71 @@ pub type NodeIndex = daggy::NodeIndex;
 pub type Edge = TupleIndex;
 
 /// An async task DAG.
-#[derive(Debug, Default)]
-pub struct TryGraph<'a, Err: 'a> {
-    dag: daggy::Dag<Node<'a, Err>, Edge>,
-    dependencies: HashMap<(NodeIndex, Edge), EdgeIndex>,
+///
+/// `D` is user data which can be attached to nodes, see [`TryGraph::set_node_data`].
+#[derive(Debug)]
+pub struct TryGraph<'a, Err: 'a, D = ()> {
+    dag: daggy::Dag<NodeWeight<'a, Err, D>, Edge, Index>,
+    dependencies: HashMap<(NodeIndex, Edge), EdgeIndex<Index>>,
+    adapters: Adapters<'a>,
+    input_names: HashMap<NodeIndex, Vec<String>>,
+    context: Option<NodeIndex>,
+    names: HashMap<NodeIndex, String>,
+    // Kinds of nodes added from a registry.
+    kinds: HashMap<NodeIndex, String>,
+    schedule: Schedule,
+    layers: Layers<'a, Err>,
+    edge_metadata: HashMap<(NodeIndex, Edge), EdgeMetadata>,
+    interned: HashMap<(String, Vec<NodeIndex>), NodeIndex>,
+    caches: HashMap<NodeIndex, cache::CacheSpec>,
+    idempotency_keys: HashMap<NodeIndex, String>,
+    params: HashMap<NodeIndex, NodeParams>,
+    // Set by error and result dependencies.
+    catch_error: adapter::CatchError<Err>,
+    templates: HashMap<NodeIndex, DynCurry<'a, Err>>,
+    // Inputs given by `set_input` rather than by parents.
+    given_inputs: HashSet<(NodeIndex, Edge)>,
+    // Senders of output futures, fed when their nodes complete.
+    outputs: output::Outputs<'a>,
+    journal: checkpoint::Journal<'a, Err, D>,
 }
 
-impl<'a, Err: 'a> TryGraph<'a, Err> {
-    /// Creates an empty [`TryGraph`].
-    pub fn new() -> Self {
+impl<'a, Err: 'a, D> Default for TryGraph<'a, Err, D> {
+    fn default() -> Self {
         Self {
             dag: Default::default(),
             dependencies: Default::default(),
+            adapters: Default::default(),
+            input_names: Default::default(),
+            context: None,
+            names: Default::default(),
+            kinds: Default::default(),
+            schedule: Default::default(),
+            layers: Default::default(),
+            edge_metadata: Default::default(),
+            interned: Default::default(),
+            caches: Default::default(),
+            idempotency_keys: Default::default(),
+            params: Default::default(),
+            catch_error: Default::default(),
+            templates: Default::default(),
+            given_inputs: Default::default(),
+            outputs: Default::default(),
+            journal: Default::default(),
         }
     }
+}
+
+impl<'a, Err: 'a> TryGraph<'a, Err> {
+    /// Creates an empty [`TryGraph`].
+    ///
+    /// Use [`Default::default`] for a graph with node data.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
 
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
     /// Converts `self` into an iterator of [`Node`]s.
     ///
     /// Client can use this method and previous returned [`NodeIndex`]s to retrive the graph running result.
@@ -99,21 +293,92 @@ impl<'a, Err: 'a> TryGraph<'a, Err> {
             .into_nodes_edges()
             .0
             .into_iter()
-            .map(|node| node.weight)
+            .map(|node| node.weight.node)
+    }
+
+    /// Converts `self` into the output values of all completed nodes.
+    pub fn into_values(self) -> HashMap<NodeIndex, DynAny> {
+        self.into_nodes()
+            .enumerate()
+            .filter_map(|(index, node)| match node {
+                Node::Value { value, .. } => Some((NodeIndex::new(index), value)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Converts `self` into the output values of all completed nodes whose output type is `T`.
+    ///
+    /// Values of other types are dropped.
+    pub fn into_typed_values<T: 'static>(self) -> HashMap<NodeIndex, T> {
+        self.into_values()
+            .into_iter()
+            .filter_map(|(index, value)| downcast(value).ok().map(|value| (index, value)))
+            .collect()
+    }
+
+    /// Gets the underlying [`daggy::Dag`].
+    ///
+    /// Useful for running [`petgraph`](daggy::petgraph) algorithms on the task graph.
+    pub fn as_dag(&self) -> &daggy::Dag<NodeWeight<'a, Err, D>, Edge, Index> {
+        &self.dag
     }
 
     /// Gets the output value of `node`.
     ///
     /// Returns [`None`] if the `node`'s task hasn't done running or the type does not match.
+    /// Use [`TryGraph::try_get_value`] to tell the two cases apart.
     ///
     /// **Panics** if `node` does not exist within the graph.
     pub fn get_value<T: 'static>(&self, node: NodeIndex) -> Option<T> {
-        match self.dag.node_weight(node).unwrap() {
+        match &self.dag[node].node {
             Node::Value { value, .. } => downcast(value.clone()).ok(),
             _ => None,
         }
     }
 
+    /// Gets the output value of `node`, reporting why it's not available on failure.
+    pub fn try_get_value<T: 'static>(&self, node: NodeIndex) -> Result<T, GetValueError> {
+        match self.dag.node_weight(node).map(|weight| &weight.node) {
+            Some(Node::Value { value, type_info }) => {
+                downcast(value.clone()).map_err(|_| GetValueError::TypeMismatch {
+                    stored: *type_info,
+                    requested: TypeInfo::of::<T>(),
+                })
+            }
+            Some(_) => Err(GetValueError::NotCompleted),
+            None => Err(GetValueError::NoSuchNode),
+        }
+    }
+
+    /// Gets the failed nodes and their errors, kept by [`RunOptions::continue_on_error`].
+    pub fn failures(&self) -> Vec<(NodeIndex, &Err)> {
+        self.dag
+            .graph()
+            .node_indices()
+            .filter_map(|index| match &self.dag[index].node {
+                Node::Failed { error, .. } => Some((index, error)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Sets the name of `node`, replacing the previous one if any.
+    ///
+    /// Names are only used for diagnostics.
+    ///
+    /// **Panics** if `node` does not exist within the graph.
+    pub fn set_name(&mut self, node: NodeIndex, name: impl Into<String>) {
+        assert!(self.dag.node_weight(node).is_some());
+        let previous = self.names.insert(node, name.into());
+        self.record(move |graph| checkpoint::restore(&mut graph.names, node, previous));
+    }
+
+    /// Gets the name of `node` if it has one.
+    pub fn name(&self, node: NodeIndex) -> Option<&str> {
+        self.names.get(&node).map(String::as_str)
+    }
+
     /// Adds a task without specifying its dependencies.
     ///
     /// Returns the [`NodeIndex`] representing this task.
@@ -126,6 +391,36 @@ impl<'a, Err: 'a> TryGraph<'a, Err> {
         self.add_task_impl(task.into_task())
     }
 
+    /// Adds a synchronous task, i.e. a function returning a [`Result`] instead of a future.
+    ///
+    /// The function is called on the thread driving the graph, so it should be cheap,
+    /// or the node should be marked by [`TryGraph::mark_blocking`].
+    ///
+    /// See [`TryGraph::add_try_task`].
+    pub fn add_sync_try_task<Args, Ok, T: IntoSyncTryTask<'a, Args, Ok, Err>>(
+        &mut self,
+        task: T,
+    ) -> NodeIndex {
+        self.add_task_impl(task.into_task())
+    }
+
+    /// Adds an existing `future` as a node without dependencies, e.g. an operation started elsewhere.
+    ///
+    /// The same as [`TryGraph::add_try_task`] with a closure returning `future`.
+    pub fn add_try_future<Ok: IntoAny>(
+        &mut self,
+        future: impl Future<Output = Result<Ok, Err>> + Send + 'a,
+    ) -> NodeIndex {
+        self.add_try_task(move || future)
+    }
+
+    /// Adds a custom [`Curry`], e.g. one binding arguments in a bespoke way, memoizing or proxying.
+    ///
+    /// Its [`Curry::input_type_info`] and [`Curry::output_type_info`] are respected by type checking.
+    pub fn add_curry(&mut self, curry: Box<dyn Curry<'a, Err> + Send + 'a>) -> NodeIndex {
+        self.dag.add_node(NodeWeight::new(curry))
+    }
+
     fn add_task_impl<T: TryTask<'a, Err = Err> + 'a>(&mut self, task: T) -> NodeIndex {
         self.dag.add_node(Self::make_node(task))
     }
@@ -138,8 +433,6 @@ impl<'a, Err: 'a> TryGraph<'a, Err> {
     ///
     /// This is more efficient than [`TryGraph::add_task`] then [`TryGraph::update_dependency`].
     ///
-    /// **Panics** if the graph is at the maximum number of nodes for its index type.
-    ///
     /// **Panics** if `child` does not exist within the graph.
     pub fn add_parent_try_task<Args, Ok: IntoAny, T: IntoTryTask<'a, Args, Ok, Err>>(
         &mut self,
@@ -150,13 +443,33 @@ impl<'a, Err: 'a> TryGraph<'a, Err> {
         self.add_parent_task_impl::<Ok, _>(task.into_task(), child, index)
     }
 
+    /// The same as [`TryGraph::add_parent_try_task`] with an index checked at compile time.
+    ///
+    /// `child`'s arity is only known at runtime, so an out of range `index` is still an error.
+    pub fn add_parent_try_task_at<
+        Args,
+        Ok: IntoAny,
+        T: IntoTryTask<'a, Args, Ok, Err>,
+        const I: TupleIndex,
+    >(
+        &mut self,
+        task: T,
+        child: NodeIndex,
+        index: In<I>,
+    ) -> Result<NodeIndex, ErrorWithTask<T::Task>> {
+        self.add_parent_task_impl::<Ok, _>(task.into_task(), child, index.into())
+    }
+
     fn add_parent_task_impl<Ok: 'static, T: TryTask<'a, Err = Err> + 'a>(
         &mut self,
         task: T,
         child: NodeIndex,
         index: Edge,
     ) -> Result<NodeIndex, ErrorWithTask<T>> {
-        if let Err(error) = self.type_check(child, index, TypeInfo::of::<Ok>()) {
+        let check = self
+            .type_check(None, child, index, TypeInfo::of::<Ok>())
+            .and_then(|()| self.check_capacity(1, 1));
+        if let Err(error) = check {
             return Err(ErrorWithTask { error, task });
         }
         #[allow(unused_results)]
@@ -165,6 +478,7 @@ impl<'a, Err: 'a> TryGraph<'a, Err> {
         }
         let (edge, node) = self.dag.add_parent(child, index, Self::make_node(task));
         assert!(self.dependencies.insert((child, index), edge).is_none());
+        self.record_dependency(child, index);
         Ok(node)
     }
 
@@ -174,8 +488,6 @@ impl<'a, Err: 'a> TryGraph<'a, Err> {
     ///
     /// This is more efficient than [`TryGraph::add_task`] then [`TryGraph::update_dependency`].
     ///
-    /// **Panics** if the graph is at the maximum number of nodes for its index type.
-    ///
     /// **Panics** if `parent` does not exist within the graph.
     pub fn add_child_try_task<Args, Ok: IntoAny, T: IntoTryTask<'a, Args, Ok, Err>>(
         &mut self,
@@ -183,10 +495,26 @@ impl<'a, Err: 'a> TryGraph<'a, Err> {
         task: T,
         index: Edge,
     ) -> Result<NodeIndex, ErrorWithTask<T::Task>> {
-        self.add_child_task_impl::<Ok, _>(parent, task.into_task(), index)
+        self.add_child_task_impl(parent, task.into_task(), index)
+    }
+
+    /// The same as [`TryGraph::add_child_try_task`] with an index checked at compile time.
+    ///
+    /// Fails to compile if `index` is out of range of the task's inputs, so the only possible error is a type mismatch.
+    pub fn add_child_try_task_at<Args, Ok, T, const I: TupleIndex>(
+        &mut self,
+        parent: NodeIndex,
+        task: T,
+        _index: In<I>,
+    ) -> Result<NodeIndex, ErrorWithTask<T::Task>>
+    where
+        T: IntoTryTask<'a, Args, Ok, Err>,
+        In<I>: InputIndex<<T::Task as TryTask<'a>>::Inputs>,
+    {
+        self.add_child_task_impl(parent, task.into_task(), <In<I> as InputIndex<_>>::INDEX)
     }
 
-    fn add_child_task_impl<Ok: 'static, T: TryTask<'a, Err = Err> + 'a>(
+    fn add_child_task_impl<T: TryTask<'a, Err = Err> + 'a>(
         &mut self,
         parent: NodeIndex,
         task: T,
@@ -202,11 +530,15 @@ impl<'a, Err: 'a> TryGraph<'a, Err> {
             }
         };
         let output_type_info = self.output_type_info(parent);
-        if let Err(error) = check_type_equality(input_type_info, output_type_info) {
+        let check = self
+            .check_type_equality(Some(parent), None, index, input_type_info, output_type_info)
+            .and_then(|()| self.check_capacity(1, 1));
+        if let Err(error) = check {
             return Err(ErrorWithTask { error, task });
         }
         let (edge, node) = self.dag.add_child(parent, index, Self::make_node(task));
         assert!(self.dependencies.insert((node, index), edge).is_none());
+        self.record_dependency(node, index);
         Ok(node)
     }
 
@@ -215,15 +547,60 @@ impl<'a, Err: 'a> TryGraph<'a, Err> {
     /// If child already has a dependency at `index`, it will be removed. But the depended node won't.
     ///
     /// **Panics** if either `parent` or `child` does not exist within the graph.
-    ///
-    /// **Panics** if the graph is at the maximum number of edges for its index type.
     pub fn update_dependency(
         &mut self,
         parent: NodeIndex,
         child: NodeIndex,
         index: Edge,
     ) -> Result<(), Error> {
-        self.type_check(child, index, self.output_type_info(parent))?;
+        self.update_dependency_impl(parent, child, index, self.output_type_info(parent), None)
+    }
+
+    /// Sets `parent` as `child`'s dependency at the only input whose type is `parent`'s output type.
+    ///
+    /// Returns the input index, or [`Error::Ambiguous`] if there's no such input or more than one.
+    ///
+    /// Otherwise the same as [`TryGraph::update_dependency`].
+    pub fn wire_by_type(&mut self, parent: NodeIndex, child: NodeIndex) -> Result<Edge, Error> {
+        let output = self.output_type_info(parent);
+        let curry = match &self.dag[child].node {
+            Node::Curry(curry) => curry,
+            _ => return Err(Error::HasStarted(child)),
+        };
+        let candidates = (0..curry.num_inputs())
+            .filter(|index| curry.input_type_info(*index) == Some(output))
+            .collect::<Vec<_>>();
+        match candidates[..] {
+            [index] => {
+                self.update_dependency(parent, child, index)?;
+                Ok(index)
+            }
+            _ => Err(Error::Ambiguous { output, candidates }),
+        }
+    }
+
+    /// Fails with [`Error::IndexOverflow`] if `nodes` more nodes or `edges` more dependencies can't be addressed by [`Index`].
+    fn check_capacity(&self, nodes: usize, edges: usize) -> Result<(), Error> {
+        let max = <Index as IndexType>::max().index();
+        if nodes > max - self.dag.node_count() || edges > max - self.dag.edge_count() {
+            return Err(Error::IndexOverflow);
+        }
+        Ok(())
+    }
+
+    /// `output_type_info` is what's fed into `child`, after `adapter` if any.
+    fn update_dependency_impl(
+        &mut self,
+        parent: NodeIndex,
+        child: NodeIndex,
+        index: Edge,
+        output_type_info: TypeInfo,
+        adapter: Option<adapter::Adapter<'a>>,
+    ) -> Result<(), Error> {
+        self.type_check(Some(parent), child, index, output_type_info)?;
+        if !self.dependencies.contains_key(&(child, index)) {
+            self.check_capacity(0, 1)?;
+        }
         #[allow(unused_results)]
         {
             self.remove_dependency(child, index);
@@ -231,8 +608,19 @@ impl<'a, Err: 'a> TryGraph<'a, Err> {
         let edge = self
             .dag
             .add_edge(parent, child, index)
-            .map_err(|_| Error::WouldCycle)?;
+            .map_err(|_| Error::WouldCycle {
+                path: self
+                    .path(child, parent)
+                    .unwrap()
+                    .into_iter()
+                    .map(|node| self.label(node))
+                    .collect(),
+            })?;
         assert!(self.dependencies.insert((child, index), edge).is_none());
+        if let Some(adapter) = adapter {
+            let _ = self.adapters.0.insert((child, index), adapter);
+        }
+        self.record_dependency(child, index);
         Ok(())
     }
 
@@ -241,8 +629,26 @@ impl<'a, Err: 'a> TryGraph<'a, Err> {
     /// Returns `true` if `child` has a dependency at `index` before removing.
     pub fn remove_dependency(&mut self, child: NodeIndex, index: Edge) -> bool {
         let edge = self.dependencies.remove(&(child, index));
+        let adapter = self.adapters.0.remove(&(child, index));
+        let catch = self.adapters.1.remove(&(child, index));
+        let metadata = self.edge_metadata.remove(&(child, index));
         if let Some(edge) = edge {
+            let (parent, _) = self.dag.edge_endpoints(edge).unwrap();
             assert!(self.dag.remove_edge(edge).is_some());
+            // The last edge takes the removed edge's index.
+            if let Some((_, moved)) = self.dag.edge_endpoints(edge) {
+                let _ = self.dependencies.insert((moved, self.dag[edge]), edge);
+            }
+            self.record(move |graph| {
+                graph.restore(bulk::Replaced {
+                    parent,
+                    child,
+                    index,
+                    adapter,
+                    catch,
+                    metadata,
+                })
+            });
             true
         } else {
             false
@@ -253,18 +659,218 @@ impl<'a, Err: 'a> TryGraph<'a, Err> {
     ///
     /// If the returned future is dropped before completion, or an error occurs, some tasks will be cancelled and forever lost.
     /// Corresponding [`Node`] will be set to [`Node::Running`].
+    ///
+    /// **Panics** if a value can't be passed between nodes, see [`TryGraph::try_run_checked`].
     pub async fn try_run(&mut self) -> Result<(), Err> {
-        let mut runner = Runner::new(&mut self.dag);
+        self.try_run_wrapped(|_, future| future)
+            .await
+            .map_err(|error| error.into_task().expect(UNCHECKED))
+    }
+
+    /// Runs with the default options, wrapping task futures by `outer` after instrumentation.
+    async fn try_run_wrapped(
+        &mut self,
+        outer: impl Fn(NodeIndex, TaskFuture<'a, Err>) -> TaskFuture<'a, Err> + Sync,
+    ) -> Result<(), RunError<Err>> {
+        let options = RunOptions::default();
+        let mut admission = self.schedule.admission(&options);
+        let instrumentation = Instrumentation::new(&self.layers, &self.names, &self.dag);
+        let wrap = |index, future| outer(index, instrumentation.wrap(index, future));
+        let catch_error = &self.catch_error;
+        let mut runner = Runner::new(
+            &mut self.dag,
+            &self.adapters,
+            &wrap,
+            &mut admission,
+            &*options.clock,
+            options.handle.as_ref(),
+            options.policy,
+        );
+        runner.catch_error(catch_error);
+        runner.notify_outputs(&mut self.outputs);
         runner.run().await
     }
 
+    /// Marks `node` as blocking, so its task is run by [`RunOptions::blocking_spawner`].
+    ///
+    /// **Panics** if `node` does not exist within the graph.
+    pub fn mark_blocking(&mut self, node: NodeIndex) {
+        assert!(self.dag.node_weight(node).is_some());
+        if self.schedule.blocking.insert(node) {
+            self.record(move |graph| {
+                let _ = graph.schedule.blocking.remove(&node);
+            });
+        }
+    }
+
+    /// [`TryGraph::try_run`] with `options`.
+    ///
+    /// **Panics** if a value can't be passed between nodes, see [`TryGraph::try_run_checked`].
+    pub async fn try_run_with(&mut self, options: &RunOptions<'a>) -> Result<(), Err>
+    where
+        Err: Send,
+    {
+        self.try_run_checked(options)
+            .await
+            .map_err(|error| error.into_task().expect(UNCHECKED))
+    }
+
+    /// [`TryGraph::try_run_with`], but returns [`RunError::Dependency`] if a value can't be passed between nodes
+    /// instead of panicking, e.g. for graphs of custom [`Curry`]s or built from dynamic specs.
+    ///
+    /// Nodes already running are dropped when a dependency error happens, as on task errors.
+    pub async fn try_run_checked(&mut self, options: &RunOptions<'a>) -> Result<(), RunError<Err>>
+    where
+        Err: Send,
+    {
+        #[cfg(feature = "inspector")]
+        let inspection = options
+            .inspector
+            .as_ref()
+            .map(|inspector| (inspector, inspector.publish(self)));
+        #[cfg(feature = "tui")]
+        if let Some(progress) = &options.terminal_progress {
+            progress.start(self);
+        }
+        let schedule = &self.schedule;
+        let caches = &self.caches;
+        let idempotency_keys = &self.idempotency_keys;
+        let names = &self.names;
+        let instrumentation = Instrumentation::new(&self.layers, &self.names, &self.dag);
+        // Output types of nodes to run, as the runner borrows the graph.
+        let type_infos = match options.record {
+            Some(_) => self
+                .dag
+                .graph()
+                .node_indices()
+                .filter(|index| matches!(self.dag[*index].node, Node::Curry(_)))
+                .map(|index| (index, self.output_type_info(index)))
+                .collect(),
+            None => HashMap::new(),
+        };
+        let wrap = |index, future| {
+            let future = match &options.replay {
+                Some(replay) => replay.stub(index, future),
+                None => {
+                    let future = match (&options.cache, caches.get(&index)) {
+                        (Some(cache), Some(spec)) => cache::cached(cache, spec, future),
+                        _ => future,
+                    };
+                    let future = match (&options.ledger, idempotency_keys.get(&index)) {
+                        (Some(ledger), Some(key)) => ledger::idempotent(ledger, key, future),
+                        _ => future,
+                    };
+                    let future = instrumentation.wrap(index, future);
+                    let future = match &options.report {
+                        Some(report) => report::instrument(report, &options.clock, index, future),
+                        None => future,
+                    };
+                    schedule.spawn(options, index, future)
+                }
+            };
+            #[cfg(feature = "inspector")]
+            let future = match &inspection {
+                Some((inspector, run)) => inspector.track(*run, index, future),
+                None => future,
+            };
+            #[cfg(feature = "tui")]
+            let future = match &options.terminal_progress {
+                Some(progress) => progress.track(index, future),
+                None => future,
+            };
+            let future = match &options.watchdog {
+                Some(watchdog) => watchdog.watch(
+                    &options.clock,
+                    index,
+                    names.get(&index).map(String::as_str),
+                    future,
+                ),
+                None => future,
+            };
+            let future = match &options.on_value {
+                Some(hook) => intercept(hook, index, future),
+                None => future,
+            };
+            match &options.record {
+                Some(recording) => replay::record(recording, index, type_infos[&index], future),
+                None => future,
+            }
+        };
+        let mut admission = schedule.admission(options);
+        let catch_error = &self.catch_error;
+        let mut runner = Runner::new(
+            &mut self.dag,
+            &self.adapters,
+            &wrap,
+            &mut admission,
+            &*options.clock,
+            options.handle.as_ref(),
+            options.policy,
+        );
+        runner.catch_error(catch_error);
+        runner.notify_outputs(&mut self.outputs);
+        let result = runner.run().await;
+        #[cfg(feature = "perf-counters")]
+        if let Some(report) = &options.report {
+            report.add_overhead(runner.overhead);
+        }
+        drop(runner);
+        // Nodes may be skipped or dropped without completing.
+        #[cfg(feature = "inspector")]
+        if let Some(inspector) = &options.inspector {
+            let _ = inspector.publish(self);
+        }
+        #[cfg(feature = "tui")]
+        if let Some(progress) = &options.terminal_progress {
+            progress.finish(self);
+        }
+        result
+    }
+
+    /// Runs the graph and takes the output value of `root`.
+    ///
+    /// **Panics** if `root` does not exist within the graph, is not completed after running
+    /// or its output type is not `T`.
+    pub async fn execute<T: 'static>(mut self, root: NodeIndex) -> Result<T, Err> {
+        self.try_run().await?;
+        match self.into_nodes().nth(root.index()).unwrap().downcast() {
+            Ok(value) => Ok(value),
+            Err(_) => panic!("Expecting root to be completed with the requested type"),
+        }
+    }
+
+    /// Runs the graph and takes the output value of `root`, like [`TryGraph::execute`] but without panicking.
+    ///
+    /// Returns [`ExecuteError::Value`] without running if `root` does not exist within the graph,
+    /// or after running if `root` is not completed, e.g. dropped or skipped, or its output type is not `T`.
+    pub async fn try_execute<T: 'static>(
+        mut self,
+        root: NodeIndex,
+    ) -> Result<T, ExecuteError<Err>> {
+        if self.dag.node_weight(root).is_none() {
+            return Err(ExecuteError::Value(GetValueError::NoSuchNode));
+        }
+        self.try_run_wrapped(|_, future| future).await?;
+        match self.into_nodes().nth(root.index()).unwrap() {
+            Node::Value { value, type_info } => downcast(value).map_err(|_| {
+                ExecuteError::Value(GetValueError::TypeMismatch {
+                    stored: type_info,
+                    requested: TypeInfo::of::<T>(),
+                })
+            }),
+            _ => Err(ExecuteError::Value(GetValueError::NotCompleted)),
+        }
+    }
+
+    /// `parent` is [`None`] if it's not added to the graph yet.
     fn type_check(
         &self,
+        parent: Option<NodeIndex>,
         child: NodeIndex,
         index: Edge,
         output_type_info: TypeInfo,
     ) -> Result<(), Error> {
-        let node = self.dag.node_weight(child).unwrap();
+        let node = &self.dag[child].node;
         let curry = match node {
             Node::Curry(curry) => curry,
             _ => return Err(Error::HasStarted(child)),
@@ -272,41 +878,164 @@ impl<'a, Err: 'a> TryGraph<'a, Err> {
         let input_type_info = curry
             .input_type_info(index)
             .ok_or_else(|| Error::OutOfRange(curry.num_inputs()))?;
-        check_type_equality(input_type_info, output_type_info)?;
+        self.check_type_equality(
+            parent,
+            Some(child),
+            index,
+            input_type_info,
+            output_type_info,
+        )?;
         Ok(())
     }
 
-    fn make_node<T: TryTask<'a, Err = Err> + 'a>(task: T) -> Node<'a, Err> {
-        let curry = CurriedTask::new(task);
-        Node::Curry(Box::new(curry))
+    /// `parent` or `child` is [`None`] if it's not added to the graph yet.
+    fn check_type_equality(
+        &self,
+        parent: Option<NodeIndex>,
+        child: Option<NodeIndex>,
+        index: Edge,
+        input: TypeInfo,
+        output: TypeInfo,
+    ) -> Result<(), Error> {
+        if input != output {
+            Err(Error::TypeMismatch {
+                input,
+                output,
+                parent: parent.map(|parent| Box::new(self.label(parent))),
+                child: child.map(|child| Box::new(self.label(child))),
+                index,
+            })
+        } else {
+            Ok(())
+        }
     }
 
-    fn output_type_info(&self, index: NodeIndex) -> TypeInfo {
-        let node = self.dag.node_weight(index).unwrap();
-        match node {
-            Node::Curry(curry) => curry.output_type_info(),
-            Node::Running(type_info) => *type_info,
-            Node::Value { type_info, .. } => *type_info,
+    fn label(&self, node: NodeIndex) -> NodeLabel {
+        NodeLabel {
+            index: node,
+            name: self.name(node).map(ToOwned::to_owned),
         }
     }
+
+    fn make_node<T: TryTask<'a, Err = Err> + 'a>(task: T) -> NodeWeight<'a, Err, D> {
+        NodeWeight::new(Box::new(CurriedTask::new(task)))
+    }
+
+    fn output_type_info(&self, index: NodeIndex) -> TypeInfo {
+        self.dag[index].node.output_type_info()
+    }
 }
 
-fn check_type_equality(input: TypeInfo, output: TypeInfo) -> Result<(), Error> {
-    if input != output {
-        Err(Error::TypeMismatch { input, output })
-    } else {
-        Ok(())
+/// Wraps task futures with what applies to every run.
+struct Instrumentation<'g, 'a, Err> {
+    layers: &'g Layers<'a, Err>,
+    #[cfg(any(feature = "log", feature = "metrics"))]
+    names: &'g HashMap<NodeIndex, String>,
+    #[cfg(feature = "opentelemetry")]
+    tracing: otel::Tracing,
+    #[cfg(feature = "tracing")]
+    spans: spans::Spans<'g, 'a>,
+}
+
+impl<'g, 'a, Err: 'a> Instrumentation<'g, 'a, Err> {
+    #[cfg_attr(
+        not(any(feature = "opentelemetry", feature = "tracing")),
+        allow(unused_variables)
+    )]
+    fn new<D>(
+        layers: &'g Layers<'a, Err>,
+        names: &'g HashMap<NodeIndex, String>,
+        dag: &daggy::Dag<NodeWeight<'a, Err, D>, Edge, Index>,
+    ) -> Self {
+        Self {
+            layers,
+            #[cfg(any(feature = "log", feature = "metrics"))]
+            names,
+            #[cfg(feature = "opentelemetry")]
+            tracing: otel::Tracing::new(layers.tracer.as_ref(), dag, names),
+            #[cfg(feature = "tracing")]
+            spans: spans::Spans::new(layers.span_factory.as_ref(), names, dag),
+        }
+    }
+
+    fn wrap(&self, node: NodeIndex, future: TaskFuture<'a, Err>) -> TaskFuture<'a, Err> {
+        let future = self.layers.apply(node, future);
+        #[cfg(feature = "log")]
+        let future = log::observe(self.names, node, future);
+        #[cfg(feature = "metrics")]
+        let future = metrics::observe(self.names, node, future);
+        #[cfg(feature = "opentelemetry")]
+        let future = self.tracing.trace(node, future);
+        #[cfg(feature = "tracing")]
+        let future = self.spans.instrument(node, future);
+        future
     }
 }
 
 mod infallible;
 
+pub use builder::GraphBuilder;
+pub use builder::Shard;
+pub use builder::ShardMap;
+pub use builder::ShardNode;
+pub use cache::Cacheable;
+pub use cache::NodeCache;
+pub use checkpoint::Checkpoint;
+pub use clock::*;
+#[cfg(feature = "serde")]
+pub use dynamic::DynamicGraph;
+pub use edge::EdgeMetadata;
+pub use expr::Bindings;
+pub use gather::Gathered;
+pub use group::RateLimit;
+pub use handle::RunHandle;
 pub use infallible::*;
+#[cfg(feature = "inspector")]
+pub use inspect::Inspector;
+pub use lane::Lane;
+pub use layer::TaskLayer;
+pub use ledger::Ledger;
+pub use lint::Lint;
+pub use lint::LintKind;
+pub use lint::LintOptions;
+pub use local::LocalGraph;
+pub use local::LocalTryGraph;
+pub use options::*;
+pub use output::OutputFuture;
+pub use params::NodeParams;
+#[cfg(feature = "serde")]
+pub use registry::Signature;
+#[cfg(feature = "serde")]
+pub use registry::TaskRegistry;
+pub use replay::Recording;
+pub use report::NodeStats;
+#[cfg(feature = "perf-counters")]
+pub use report::Overhead;
+pub use report::RunReport;
+pub use simulate::SimOptions;
+pub use simulate::SimReport;
+pub use spawn::SpawnedOutput;
+pub use spawn::SpawnedRun;
+pub use spec::EdgeSpec;
+pub use spec::NodeSpec;
+pub use spec::TopologySpec;
+pub use task::*;
+pub use topology::BoxedTask;
+pub use topology::GraphStats;
+#[cfg(feature = "tui")]
+pub use tui::TerminalProgress;
+pub use unrun::InputState;
+pub use unrun::UnrunNode;
+pub use watch::Update;
 
 #[cfg(test)]
+// The original tests spell out unit outputs.
+#[allow(clippy::unused_unit)]
 mod tests {
     use super::*;
     use futures::executor::block_on;
+    use futures::future::poll_fn;
+    use futures::FutureExt;
     use std::any::TypeId;
 
     #[test]
@@ -329,6 +1058,433 @@ mod tests {
         assert_eq!(result, 2);
     }
 
+    #[test]
+    fn test_try_get_value() {
+        let mut graph = Graph::new();
+        let node = graph.add_task(|| async { 1 });
+        assert!(matches!(
+            graph.try_get_value::<i32>(node),
+            Err(GetValueError::NotCompleted)
+        ));
+        assert!(matches!(
+            graph.try_get_value::<i32>(NodeIndex::new(1)),
+            Err(GetValueError::NoSuchNode)
+        ));
+        block_on(graph.run());
+        assert_eq!(graph.try_get_value::<i32>(node).unwrap(), 1);
+        match graph.try_get_value::<u32>(node).unwrap_err() {
+            GetValueError::TypeMismatch { stored, requested } => {
+                assert_eq!(stored, TypeInfo::of::<i32>());
+                assert_eq!(requested, TypeInfo::of::<u32>());
+            }
+            _ => panic!("Expecting type mismatch error"),
+        }
+    }
+
+    #[test]
+    fn test_into_values() {
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        let two = graph
+            .add_child_task(one, |v: i32| async move { v + 1 }, 0)
+            .unwrap();
+        let _ = graph.add_child_task(one, |_: i32| async {}, 0).unwrap();
+        let pending = graph.add_task(|_: i32| async {});
+        block_on(graph.run());
+
+        let values = graph.into_typed_values::<i32>();
+        assert_eq!(values, HashMap::from([(one, 1), (two, 2)]));
+        assert!(!values.contains_key(&pending));
+    }
+
+    #[test]
+    fn test_execute() {
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        let root = graph
+            .add_child_task(one, |v: i32| async move { v + 1 }, 0)
+            .unwrap();
+        assert_eq!(block_on(graph.execute::<i32>(root)).unwrap(), 2);
+
+        let mut graph = TryGraph::new();
+        let _ = graph.add_try_task::<_, (), _>(|| async { Err(()) });
+        let root = graph.add_try_task(|| async { Ok::<_, ()>(1) });
+        block_on(graph.execute::<i32>(root)).unwrap_err();
+    }
+
+    #[test]
+    fn test_try_execute() {
+        let build = || {
+            let mut graph = TryGraph::<&str>::new();
+            let one = graph.add_try_task(|| async { Ok(1) });
+            let root = graph
+                .add_child_try_task(one, |v: i32| async move { Ok(v + 1) }, 0)
+                .unwrap();
+            (graph, root)
+        };
+        let (graph, root) = build();
+        assert_eq!(block_on(graph.try_execute::<i32>(root)).unwrap(), 2);
+        let (graph, root) = build();
+        assert!(matches!(
+            block_on(graph.try_execute::<u8>(root)),
+            Err(ExecuteError::Value(GetValueError::TypeMismatch { .. }))
+        ));
+        let (graph, _) = build();
+        assert!(matches!(
+            block_on(graph.try_execute::<i32>(NodeIndex::new(2))),
+            Err(ExecuteError::Value(GetValueError::NoSuchNode))
+        ));
+
+        let mut graph = TryGraph::<&str>::new();
+        let failing = graph.add_try_task(|| async { Err::<i32, _>("boom") });
+        assert!(matches!(
+            block_on(graph.try_execute::<i32>(failing)),
+            Err(ExecuteError::Task("boom"))
+        ));
+    }
+
+    #[test]
+    fn test_add_future() {
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        let mut graph = TryGraph::new();
+        let received = graph.add_try_future(receiver);
+        let double = graph
+            .add_child_try_task(received, |v: i32| async move { Ok(v * 2) }, 0)
+            .unwrap();
+        sender.send(2).unwrap();
+        block_on(graph.try_run()).unwrap();
+        assert_eq!(graph.get_value::<i32>(double).unwrap(), 4);
+
+        let mut graph = Graph::new();
+        let ready = graph.add_future(futures::future::ready(1));
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<i32>(ready).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_send() {
+        fn assert_send<T: Send>(value: T) -> T {
+            value
+        }
+
+        let mut graph = Graph::new();
+        let node = graph.add_task(|| async { 1 });
+        let graph = std::thread::spawn(move || {
+            block_on(assert_send(graph.run()));
+            graph
+        })
+        .join()
+        .unwrap();
+        assert_eq!(graph.get_value::<i32>(node).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_sync_task() {
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        let root = graph.add_sync_task(|lhs: i32, rhs: i32| lhs + rhs);
+        graph.update_dependency(one, root, 0).unwrap();
+        graph.update_dependency(one, root, 1).unwrap();
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<i32>(root).unwrap(), 2);
+
+        let mut graph = TryGraph::new();
+        let _ = graph.add_sync_try_task(|| "a".parse::<i32>());
+        let _ = block_on(graph.try_run()).unwrap_err();
+    }
+
+    #[test]
+    fn test_blocking() {
+        let mut graph = Graph::new();
+        let main = std::thread::current().id();
+        let node = graph.add_sync_task(move || std::thread::current().id() != main);
+        graph.mark_blocking(node);
+        let options = RunOptions::new().blocking_spawner(|job| {
+            let _ = std::thread::spawn(job);
+        });
+        block_on(graph.run_with(&options));
+        assert!(graph.get_value::<bool>(node).unwrap());
+    }
+
+    #[test]
+    fn test_drop_consumed_values() {
+        let mut graph = Graph::new();
+        let buffer = graph.add_task(|| async { vec![0u8; 1024] });
+        let len = graph
+            .add_child_task(buffer, |v: Vec<u8>| async move { v.len() }, 0)
+            .unwrap();
+        block_on(graph.run_with(&RunOptions::new().drop_consumed_values()));
+        assert!(matches!(graph.as_dag()[buffer].node, Node::Dropped(_)));
+        assert_eq!(graph.get_value::<usize>(len).unwrap(), 1024);
+    }
+
+    #[test]
+    fn test_drop_consumed_values_skipped_child() {
+        let mut graph = TryGraph::<()>::new();
+        let failing = graph.add_try_task(|| async { Err::<i32, _>(()) });
+        let slow = graph.add_try_task(|| async {
+            crate::test_util::yield_now().await;
+            Ok(1)
+        });
+        let sum = graph
+            .add_child_try_task(slow, |a: i32, b: i32| async move { Ok(a + b) }, 0)
+            .unwrap();
+        graph.update_dependency(failing, sum, 1).unwrap();
+        let options = RunOptions::new().continue_on_error().drop_consumed_values();
+        block_on(graph.try_run_with(&options)).unwrap();
+        // The skipped child never consumes the value.
+        assert!(matches!(graph.as_dag()[sum].node, Node::Skipped { .. }));
+        assert_eq!(graph.get_value::<i32>(slow).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_continue_on_error() {
+        let mut graph = TryGraph::<&str>::new();
+        let failing = graph.add_try_task(|| async { Err::<i32, _>("boom") });
+        let child = graph
+            .add_child_try_task(failing, |v: i32| async move { Ok(v) }, 0)
+            .unwrap();
+        let independent = graph.add_try_task(|| async { Ok(1) });
+        let options = RunOptions::new().continue_on_error();
+        block_on(graph.try_run_with(&options)).unwrap();
+        assert_eq!(graph.failures(), vec![(failing, &"boom")]);
+        assert!(
+            matches!(graph.as_dag()[child].node, Node::Skipped { cause, .. } if cause == failing)
+        );
+        assert_eq!(graph.get_value::<i32>(independent).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_fair() {
+        use std::sync::Arc;
+        use std::sync::Mutex;
+        use std::task::Poll;
+
+        let mut graph = Graph::new();
+        let inputs = (0..8)
+            .map(|i| graph.add_task(move || async move { i }))
+            .collect::<Vec<_>>();
+        let doubles = inputs
+            .iter()
+            .map(|input| {
+                graph
+                    .add_child_task(*input, |v: i32| async move { v * 2 }, 0)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        block_on(graph.run_with(&RunOptions::new().fair()));
+        for (i, double) in doubles.into_iter().enumerate() {
+            assert_eq!(graph.get_value::<i32>(double).unwrap(), i as i32 * 2);
+        }
+
+        // Node `i` completes on its `i + 1`th poll, ending a step.
+        let polls = |options: RunOptions<'static>| {
+            let log = Arc::new(Mutex::new(vec![]));
+            let mut graph = Graph::new();
+            for i in 0..4 {
+                let log = log.clone();
+                let _ = graph.add_task(move || {
+                    let mut polls = 0;
+                    poll_fn(move |cx| {
+                        log.lock().unwrap().push(i);
+                        polls += 1;
+                        if polls > i {
+                            Poll::Ready(())
+                        } else {
+                            cx.waker().wake_by_ref();
+                            Poll::Pending
+                        }
+                    })
+                });
+            }
+            block_on(graph.run_with(&options));
+            let log = log.lock().unwrap().clone();
+            log
+        };
+        assert_eq!(polls(RunOptions::new()), [0, 1, 2, 3, 1, 2, 3, 2, 3, 3]);
+        // The first polled node rotates every step.
+        assert_eq!(
+            polls(RunOptions::new().fair()),
+            [0, 1, 2, 3, 2, 3, 1, 2, 3, 3]
+        );
+    }
+
+    #[test]
+    fn test_yield_budget() {
+        let run = |options: RunOptions<'static>| {
+            let mut graph = Graph::new();
+            let hub = graph.add_task(|| async { 1 });
+            let children = (0..100)
+                .map(|_| {
+                    graph
+                        .add_child_task(hub, |v: i32| async move { v + 1 }, 0)
+                        .unwrap()
+                })
+                .collect::<Vec<_>>();
+            let mut polls = 0;
+            let mut run = Box::pin(graph.run_with(&options));
+            block_on(poll_fn(|cx| {
+                polls += 1;
+                run.poll_unpin(cx)
+            }));
+            drop(run);
+            for child in children {
+                assert_eq!(graph.get_value::<i32>(child).unwrap(), 2);
+            }
+            polls
+        };
+        assert!(run(RunOptions::new().yield_budget(10)) >= run(RunOptions::new()) + 9);
+    }
+
+    #[test]
+    fn test_add_curry() {
+        use crate::curry::TaskFuture;
+        use crate::tuple::InsertResult;
+        use crate::tuple::TakeError;
+        use futures::FutureExt;
+        use std::convert::Infallible;
+
+        struct Constant(i32);
+
+        impl<'a> Curry<'a, Infallible> for Constant {
+            fn num_inputs(&self) -> Edge {
+                0
+            }
+
+            fn input_type_info(&self, _: Edge) -> Option<TypeInfo> {
+                None
+            }
+
+            fn output_type_info(&self) -> TypeInfo {
+                TypeInfo::of::<i32>()
+            }
+
+            fn ready(&self) -> bool {
+                true
+            }
+
+            fn curry(&mut self, _: Edge, _: DynAny) -> InsertResult {
+                unreachable!()
+            }
+
+            fn call(self: Box<Self>) -> Result<TaskFuture<'a, Infallible>, TakeError> {
+                let value: DynAny = DynAny::new(self.0);
+                Ok(futures::future::ok(value).boxed())
+            }
+        }
+
+        let mut graph = Graph::new();
+        let constant = graph.add_curry(Box::new(Constant(1)));
+        let text = graph.add_task(|_: String| async {});
+        assert!(graph.update_dependency(constant, text, 0).is_err());
+        let root = graph
+            .add_child_task(constant, |v: i32| async move { v + 1 }, 0)
+            .unwrap();
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<i32>(root).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_ready_checked_once() {
+        use crate::graph::fixtures::Probe;
+        use crate::task::IntoInfallibleTask;
+        use std::sync::atomic::Ordering;
+
+        let probe = Probe::new(Box::new(CurriedTask::new(
+            (|a: i32, b: i32, c: i32| async move { a + b + c }).into_task(),
+        )));
+        let checks = probe.checks();
+        let mut graph = Graph::new();
+        let sum = graph.add_curry(Box::new(probe));
+        for index in 0..3 {
+            let _ = graph
+                .add_parent_task(move || async move { index as i32 }, sum, index)
+                .unwrap();
+        }
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<i32>(sum).unwrap(), 3);
+        // Once when the run starts and once when the last parent completes.
+        assert_eq!(checks.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_completions_batched() {
+        use std::sync::Arc;
+        use std::sync::Mutex;
+        use std::task::Poll;
+
+        let log = Arc::new(Mutex::new(vec![]));
+        let mut graph = Graph::new();
+        // Polled first on every step, completing once all sources have.
+        let _ = graph.add_task({
+            let log = log.clone();
+            move || {
+                poll_fn(move |cx| {
+                    let mut log = log.lock().unwrap();
+                    log.push('o');
+                    if log.iter().filter(|event| **event == 's').count() == 3 {
+                        Poll::Ready(())
+                    } else {
+                        cx.waker().wake_by_ref();
+                        Poll::Pending
+                    }
+                })
+            }
+        });
+        for _ in 0..3 {
+            let log = log.clone();
+            let _ = graph.add_task(move || async move { log.lock().unwrap().push('s') });
+        }
+        block_on(graph.run());
+        // All sources complete between two polls of the observer, so they're handled in one step.
+        assert_eq!(*log.lock().unwrap(), ['o', 's', 's', 's', 'o']);
+    }
+
+    #[test]
+    fn test_try_run_checked() {
+        use crate::error::DependencyError;
+        use crate::error::RunError;
+        use crate::graph::fixtures::Probe;
+        use crate::task::IntoInfallibleTask;
+
+        // Declares an `i32` input but only accepts `u8`s.
+        let liar = Probe::new(Box::new(CurriedTask::new((|_: u8| async {}).into_task())))
+            .declaring(TypeInfo::of::<i32>());
+        let mut graph = Graph::new();
+        let parent = graph.add_task(|| async { 1 });
+        let liar = graph.add_curry(Box::new(liar));
+        graph.update_dependency(parent, liar, 0).unwrap();
+        let error = block_on(graph.try_run_checked(&RunOptions::new())).unwrap_err();
+        assert!(matches!(
+            error,
+            RunError::Dependency(DependencyError::Curry { child, index: 0, .. }) if child == liar
+        ));
+        assert!(error
+            .to_string()
+            .starts_with("RunError::Dependency(Curry {"));
+    }
+
+    #[test]
+    fn test_wire_by_type() {
+        let mut graph = Graph::new();
+        let child = graph.add_task(|n: usize, s: String, _: String| async move { s.repeat(n) });
+        let n = graph.add_task(|| async { 2usize });
+        let s = graph.add_task(|| async { "ab".to_owned() });
+        let x = graph.add_task(|| async { 'x' });
+        assert_eq!(graph.wire_by_type(n, child).unwrap(), 0);
+        match graph.wire_by_type(s, child).unwrap_err() {
+            Error::Ambiguous { output, candidates } => {
+                assert_eq!(output, TypeInfo::of::<String>());
+                assert_eq!(candidates, [1, 2]);
+            }
+            _ => panic!("Expecting ambiguous error"),
+        }
+        match graph.wire_by_type(x, child).unwrap_err() {
+            Error::Ambiguous { candidates, .. } => assert!(candidates.is_empty()),
+            _ => panic!("Expecting ambiguous error"),
+        }
+    }
+
     #[test]
     fn test_client_error() {
         let mut graph = TryGraph::new();
@@ -355,25 +1511,80 @@ mod tests {
         let mut graph = Graph::new();
         let root = graph.add_task(|_: ()| async { () });
 
-        let error = graph.type_check(root, 1, TypeInfo::of::<()>()).unwrap_err();
+        let error = graph
+            .type_check(None, root, 1, TypeInfo::of::<()>())
+            .unwrap_err();
         let len = match error {
             Error::OutOfRange(len) => len,
             _ => panic!("Expecting out of range error"),
         };
         assert_eq!(len, 1);
 
+        graph.set_name(root, "root");
         let error = graph
-            .type_check(root, 0, TypeInfo::of::<i32>())
+            .type_check(None, root, 0, TypeInfo::of::<i32>())
             .unwrap_err();
-        let (input, output) = match error {
-            Error::TypeMismatch { input, output } => (input, output),
+        assert_eq!(
+            error.to_string(),
+            "output of the new node (i32) cannot feed input 0 of node 'root' (expects ())"
+        );
+        let (input, output, parent, child, index) = match error {
+            Error::TypeMismatch {
+                input,
+                output,
+                parent,
+                child,
+                index,
+            } => (input, output, parent, child, index),
             _ => panic!("Expecting type mismatch error"),
         };
+        assert!(parent.is_none());
+        assert_eq!(child.unwrap().index, root);
+        assert_eq!(index, 0);
         assert_eq!(input.id(), TypeId::of::<()>());
         assert_eq!(output.id(), TypeId::of::<i32>());
         // Name is not guaranteed, but these asserts should be ok...
         assert!(input.name().contains("()"));
         assert!(output.name().contains("i32"));
+
+        let fetch = graph.add_task(|| async { String::new() });
+        graph.set_name(fetch, "fetch");
+        let parse = graph.add_task(|_: i32, _: i32| async {});
+        let error = graph.update_dependency(fetch, parse, 1).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "output of node 'fetch' ({}) cannot feed input 1 of node {} (expects i32)",
+                type_name::<String>(),
+                parse.index(),
+            )
+        );
+    }
+
+    #[test]
+    fn test_static_index() {
+        let mut graph = Graph::new();
+        let root = graph.add_task(|lhs: i32, rhs: i32| async move { lhs - rhs });
+        let _ = graph
+            .add_parent_task_at(|| async { 3 }, root, In::<0>)
+            .unwrap();
+        let one = graph
+            .add_parent_task_at(|| async { 1 }, root, In::<1>)
+            .unwrap();
+        let child = graph
+            .add_child_task_at(one, |lhs: i32, rhs: i32| async move { lhs + rhs }, In::<1>)
+            .unwrap();
+        graph
+            .update_dependency(root, child, In::<0>.into())
+            .unwrap();
+        let error = graph
+            .add_child_task_at(one, |_: u32| async {}, In::<0>)
+            .unwrap_err()
+            .error;
+        assert!(matches!(error, Error::TypeMismatch { .. }));
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<i32>(root).unwrap(), 2);
+        assert_eq!(graph.get_value::<i32>(child).unwrap(), 3);
     }
 
     #[test]
@@ -383,11 +1594,25 @@ mod tests {
         let parent = graph
             .add_parent_task(|_: ()| async { () }, root, 0)
             .unwrap();
+        graph.set_name(parent, "parent");
         let error = graph.update_dependency(root, parent, 0).unwrap_err();
-        match error {
-            Error::WouldCycle => (),
+        let path = match error {
+            Error::WouldCycle { path } => path,
             _ => panic!("Expecting would cycle error"),
-        }
+        };
+        assert_eq!(
+            path,
+            vec![
+                NodeLabel {
+                    index: parent,
+                    name: Some("parent".to_owned())
+                },
+                NodeLabel {
+                    index: root,
+                    name: None
+                },
+            ]
+        );
     }
 
     #[test]
@@ -0,0 +1,279 @@
+use super::error::Error;
+use super::Edge;
+use super::NodeIndex;
+use super::TryGraph;
+use crate::any::downcast;
+use crate::any::DynAny;
+use crate::any::IntoAny;
+use crate::any::TypeInfo;
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub(super) type Adapter<'a> = Arc<dyn Fn(DynAny) -> DynAny + Send + Sync + 'a>;
+
+/// What an edge carries when its parent fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Catch {
+    /// The error alone, and nothing on success.
+    Error,
+    /// The error as an `Err`, and the output as an `Ok` on success.
+    Result,
+}
+
+/// Conversions on dependency edges, by child and input index,
+/// and the edges carrying their parent's error.
+#[derive(Default, Clone)]
+pub(super) struct Adapters<'a>(
+    pub(super) HashMap<(NodeIndex, Edge), Adapter<'a>>,
+    pub(super) HashMap<(NodeIndex, Edge), Catch>,
+);
+
+impl<'a> Adapters<'a> {
+    /// Converts `value` if the edge into `child` at `index` has an adapter.
+    pub(super) fn apply(&self, child: NodeIndex, index: Edge, value: DynAny) -> DynAny {
+        match self.0.get(&(child, index)) {
+            Some(adapter) => adapter(value),
+            None => value,
+        }
+    }
+
+    /// What the edge into `child` at `index` carries when its parent fails, [`None`] if it's skipped.
+    pub(super) fn catch(&self, child: NodeIndex, index: Edge) -> Option<Catch> {
+        self.1.get(&(child, index)).copied()
+    }
+}
+
+/// Converts a task error into an input, by the input's type.
+pub(super) type CatchError<Err> = HashMap<TypeId, fn(&Err) -> DynAny>;
+
+fn error_into_any<Err: IntoAny>(error: &Err) -> DynAny {
+    DynAny::new(dyn_clone::clone(error))
+}
+
+fn error_into_result<P: IntoAny + Clone, Err: IntoAny + Clone>(error: &Err) -> DynAny {
+    DynAny::new(Err::<P, Err>(error.clone()))
+}
+
+impl<'a> std::fmt::Debug for Adapters<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Sets `parent` as `child`'s dependency at `index`, converting `parent`'s output by `adapter`.
+    ///
+    /// This saves an intermediate node for trivial conversions.
+    /// `parent`'s output type must be `P`, and `child`'s input type at `index` must be `C`.
+    ///
+    /// Otherwise the same as [`TryGraph::update_dependency`].
+    pub fn update_dependency_map<P: IntoAny, C: IntoAny>(
+        &mut self,
+        parent: NodeIndex,
+        child: NodeIndex,
+        index: Edge,
+        adapter: impl Fn(P) -> C + Send + Sync + 'a,
+    ) -> Result<(), Error> {
+        self.check_type_equality(
+            Some(parent),
+            Some(child),
+            index,
+            TypeInfo::of::<P>(),
+            self.output_type_info(parent),
+        )?;
+        let adapter: Adapter<'a> = Arc::new(move |value| {
+            // The parent's output type has been checked.
+            let value = downcast::<P>(value).ok().unwrap();
+            DynAny::new(adapter(value))
+        });
+        self.update_dependency_impl(parent, child, index, TypeInfo::of::<C>(), Some(adapter))
+    }
+
+    /// Sets `parent` as `handler`'s dependency at `index`, which is given `parent`'s error instead of its output.
+    ///
+    /// `handler` only runs if `parent` fails, and is skipped otherwise.
+    /// A failing parent with an error dependency doesn't abort the run, though it's still failed and its other
+    /// children are skipped.
+    /// `handler`'s input type at `index` must be `Err`.
+    ///
+    /// Otherwise the same as [`TryGraph::update_dependency`].
+    pub fn update_error_dependency(
+        &mut self,
+        parent: NodeIndex,
+        handler: NodeIndex,
+        index: Edge,
+    ) -> Result<(), Error>
+    where
+        Err: IntoAny,
+    {
+        self.update_dependency_impl(parent, handler, index, TypeInfo::of::<Err>(), None)?;
+        let _ = self.adapters.1.insert((handler, index), Catch::Error);
+        let _ = self
+            .catch_error
+            .insert(TypeId::of::<Err>(), error_into_any::<Err>);
+        Ok(())
+    }
+
+    /// Sets `parent` as `child`'s dependency at `index`, which is given `parent`'s result as a `Result<P, Err>`.
+    ///
+    /// Unlike other dependencies, `child` still runs if `parent` fails, given the error,
+    /// so it can degrade gracefully, e.g. by falling back to a default.
+    /// A failing parent with a result dependency doesn't abort the run, though its other children are skipped.
+    /// `parent`'s output type must be `P`, and `child`'s input type at `index` must be `Result<P, Err>`.
+    ///
+    /// Otherwise the same as [`TryGraph::update_dependency`].
+    pub fn update_result_dependency<P: IntoAny + Clone>(
+        &mut self,
+        parent: NodeIndex,
+        child: NodeIndex,
+        index: Edge,
+    ) -> Result<(), Error>
+    where
+        Err: IntoAny + Clone,
+    {
+        self.check_type_equality(
+            Some(parent),
+            Some(child),
+            index,
+            TypeInfo::of::<P>(),
+            self.output_type_info(parent),
+        )?;
+        let adapter: Adapter<'a> = Arc::new(|value| {
+            // The parent's output type has been checked.
+            let value = downcast::<P>(value).ok().unwrap();
+            DynAny::new(Ok::<P, Err>(value))
+        });
+        self.update_dependency_impl(
+            parent,
+            child,
+            index,
+            TypeInfo::of::<Result<P, Err>>(),
+            Some(adapter),
+        )?;
+        let _ = self.adapters.1.insert((child, index), Catch::Result);
+        let _ = self
+            .catch_error
+            .insert(TypeId::of::<Result<P, Err>>(), error_into_result::<P, Err>);
+        Ok(())
+    }
+
+    /// Sets `parent` as `child`'s dependency at `index`, converting `parent`'s output by [`Into`].
+    ///
+    /// See [`TryGraph::update_dependency_map`].
+    pub fn update_dependency_into<P: IntoAny + Into<C>, C: IntoAny>(
+        &mut self,
+        parent: NodeIndex,
+        child: NodeIndex,
+        index: Edge,
+    ) -> Result<(), Error> {
+        self.update_dependency_map(parent, child, index, P::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::Graph;
+    use crate::Node;
+    use crate::TryGraph;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_update_dependency_map() {
+        let mut graph = Graph::new();
+        let parent = graph.add_task(|| async { "four".to_owned() });
+        let child = graph.add_task(|v: usize| async move { v });
+        let error = graph
+            .update_dependency_map(parent, child, 0, |s: &'static str| s.len())
+            .unwrap_err();
+        assert!(matches!(error, Error::TypeMismatch { .. }));
+        let error = graph
+            .update_dependency_map(parent, child, 0, |s: String| s.len() as u32)
+            .unwrap_err();
+        assert!(matches!(error, Error::TypeMismatch { .. }));
+        graph
+            .update_dependency_map(parent, child, 0, |s: String| s.len())
+            .unwrap();
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<usize>(child).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_update_dependency_into() {
+        #[derive(Clone)]
+        struct Meters(f64);
+
+        impl From<Meters> for f64 {
+            fn from(meters: Meters) -> Self {
+                meters.0
+            }
+        }
+
+        let mut graph = Graph::new();
+        let parent = graph.add_task(|| async { Meters(1.5) });
+        let child = graph.add_task(|v: f64| async move { v * 2.0 });
+        assert!(graph.update_dependency(parent, child, 0).is_err());
+        graph
+            .update_dependency_into::<Meters, f64>(parent, child, 0)
+            .unwrap();
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<f64>(child).unwrap(), 3.0);
+    }
+
+    #[test]
+    fn test_update_error_dependency() {
+        for fails in [true, false] {
+            let mut graph = TryGraph::<String>::new();
+            let fetch = graph.add_try_task(move || async move {
+                match fails {
+                    true => Err("timeout".to_owned()),
+                    false => Ok(1),
+                }
+            });
+            let child = graph
+                .add_child_try_task(fetch, |v: i32| async move { Ok(v) }, 0)
+                .unwrap();
+            let handler =
+                graph.add_try_task(|error: String| async move { Ok(format!("handled {}", error)) });
+            assert!(graph.update_error_dependency(fetch, child, 0).is_err());
+            graph.update_error_dependency(fetch, handler, 0).unwrap();
+            block_on(graph.try_run()).unwrap();
+
+            let mut nodes = graph.into_nodes();
+            if fails {
+                assert!(matches!(nodes.next(), Some(Node::Failed { .. })));
+                assert!(matches!(nodes.next(), Some(Node::Skipped { .. })));
+                assert_eq!(
+                    nodes.next().unwrap().downcast::<String>().unwrap(),
+                    "handled timeout"
+                );
+            } else {
+                assert!(matches!(nodes.next(), Some(Node::Value { .. })));
+                assert!(matches!(nodes.next(), Some(Node::Value { .. })));
+                assert!(matches!(nodes.next(), Some(Node::Skipped { .. })));
+            }
+        }
+    }
+
+    #[test]
+    fn test_update_result_dependency() {
+        let mut graph = TryGraph::<String>::new();
+        let fallback = |result: Result<i32, String>| async move { Ok(result.unwrap_or(-1)) };
+        let fetches = [Ok(3), Err("timeout".to_owned())].map(|result| {
+            let fetch = graph.add_try_task(move || async move { result });
+            let child = graph.add_try_task(fallback);
+            assert!(graph.update_dependency(fetch, child, 0).is_err());
+            assert!(graph
+                .update_result_dependency::<u32>(fetch, child, 0)
+                .is_err());
+            graph
+                .update_result_dependency::<i32>(fetch, child, 0)
+                .unwrap();
+            child
+        });
+        block_on(graph.try_run()).unwrap();
+        assert_eq!(graph.get_value::<i32>(fetches[0]).unwrap(), 3);
+        assert_eq!(graph.get_value::<i32>(fetches[1]).unwrap(), -1);
+    }
+}
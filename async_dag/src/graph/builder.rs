@@ -0,0 +1,272 @@
+use super::error::BuildError;
+use super::error::Error;
+use super::DynCurry;
+use super::Edge;
+use super::Index;
+use super::NodeIndex;
+use super::TryGraph;
+use crate::curry::CurriedTask;
+use crate::task::IntoInfallibleTask;
+use crate::task::IntoTryTask;
+use daggy::petgraph::graph::IndexType;
+use std::convert::Infallible;
+
+/// A node of a [`Shard`], by the shard's position in its [`GraphBuilder`] and the order it was added in the shard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShardNode {
+    shard: usize,
+    index: usize,
+}
+
+impl ShardNode {
+    /// The `index`th node added to the `shard`th shard, for referring to nodes of other shards.
+    pub fn new(shard: usize, index: usize) -> Self {
+        Self { shard, index }
+    }
+
+    /// The position of the node's shard in its [`GraphBuilder`].
+    pub fn shard(&self) -> usize {
+        self.shard
+    }
+
+    /// The order the node was added in its shard.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+/// Part of a [`GraphBuilder`], filled independently of the other shards, e.g. on its own thread.
+#[derive(Debug)]
+pub struct Shard<'a, Err> {
+    id: usize,
+    curries: Vec<DynCurry<'a, Err>>,
+    names: Vec<(usize, String)>,
+    // Parent, child and input index, the child always being in this shard.
+    dependencies: Vec<(ShardNode, usize, Edge)>,
+}
+
+impl<'a, Err: 'a> Shard<'a, Err> {
+    /// The shard's position in its [`GraphBuilder`].
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// How many nodes were added to the shard.
+    pub fn len(&self) -> usize {
+        self.curries.len()
+    }
+
+    /// Returns `true` if no node was added to the shard.
+    pub fn is_empty(&self) -> bool {
+        self.curries.is_empty()
+    }
+
+    /// Adds a task to the shard.
+    ///
+    /// See [`TryGraph::add_try_task`].
+    pub fn add_try_task<Args, Ok, T: IntoTryTask<'a, Args, Ok, Err>>(
+        &mut self,
+        task: T,
+    ) -> ShardNode {
+        self.curries
+            .push(Box::new(CurriedTask::new(task.into_task())));
+        ShardNode::new(self.id, self.curries.len() - 1)
+    }
+
+    /// Sets the name of `node`, which must be in this shard.
+    ///
+    /// **Panics** if `node` is not in this shard.
+    pub fn set_name(&mut self, node: ShardNode, name: impl Into<String>) {
+        self.names.push((self.local(node), name.into()));
+    }
+
+    /// Sets `parent`, which may be in any shard, as `child`'s dependency at `index`.
+    ///
+    /// Dependencies are checked when the graph is [built](GraphBuilder::build).
+    ///
+    /// **Panics** if `child` is not in this shard.
+    pub fn update_dependency(&mut self, parent: ShardNode, child: ShardNode, index: Edge) {
+        let child = self.local(child);
+        self.dependencies.push((parent, child, index));
+    }
+
+    fn local(&self, node: ShardNode) -> usize {
+        assert!(
+            node.shard == self.id && node.index < self.curries.len(),
+            "Node is not in this shard"
+        );
+        node.index
+    }
+}
+
+impl<'a> Shard<'a, Infallible> {
+    /// Adds an infallible task to the shard.
+    ///
+    /// See [`Shard::add_try_task`].
+    pub fn add_task<Args, Ok, T: IntoInfallibleTask<'a, Args, Ok>>(
+        &mut self,
+        task: T,
+    ) -> ShardNode {
+        self.curries
+            .push(Box::new(CurriedTask::new(task.into_task())));
+        ShardNode::new(self.id, self.curries.len() - 1)
+    }
+}
+
+/// Where the nodes of each [`Shard`] ended up in the built [`TryGraph`].
+#[derive(Debug, Clone)]
+pub struct ShardMap {
+    // The index of each shard's first node.
+    offsets: Vec<usize>,
+}
+
+impl ShardMap {
+    /// The [`NodeIndex`] of `node` in the built graph.
+    ///
+    /// **Panics** if `node`'s shard doesn't exist.
+    pub fn get(&self, node: ShardNode) -> NodeIndex {
+        NodeIndex::new(self.offsets[node.shard] + node.index)
+    }
+}
+
+/// Builds a large [`TryGraph`] from [`Shard`]s filled in parallel, then merged in order.
+///
+/// Shards refer to each other's nodes by [`ShardNode`]s, which are resolved when merging.
+#[derive(Debug)]
+pub struct GraphBuilder<'a, Err> {
+    shards: Vec<Shard<'a, Err>>,
+}
+
+impl<'a, Err: 'a> GraphBuilder<'a, Err> {
+    /// Creates a builder with `shards` empty shards.
+    pub fn new(shards: usize) -> Self {
+        Self {
+            shards: (0..shards)
+                .map(|id| Shard {
+                    id,
+                    curries: vec![],
+                    names: vec![],
+                    dependencies: vec![],
+                })
+                .collect(),
+        }
+    }
+
+    /// The shards, to be filled e.g. by [`std::thread::scope`]d threads.
+    pub fn shards_mut(&mut self) -> &mut [Shard<'a, Err>] {
+        &mut self.shards
+    }
+
+    /// Merges the shards into a [`TryGraph`], returning where their nodes ended up.
+    ///
+    /// Dependencies are type checked as by [`TryGraph::add_edges`], returning the first error.
+    /// A dependency whose parent doesn't exist is reported as [`BuildError::NoSuchNode`],
+    /// and more nodes than [`Index`] can address as [`Error::IndexOverflow`].
+    pub fn build(self) -> Result<(TryGraph<'a, Err>, ShardMap), BuildError> {
+        let lens = self.shards.iter().map(Shard::len).collect::<Vec<_>>();
+        let mut offsets = Vec::with_capacity(lens.len());
+        let mut nodes = 0;
+        for len in &lens {
+            offsets.push(nodes);
+            nodes += len;
+        }
+        let map = ShardMap { offsets };
+        let edges = self
+            .shards
+            .iter()
+            .map(|shard| shard.dependencies.len())
+            .sum();
+
+        if nodes > <Index as IndexType>::max().index() {
+            return Err(Error::IndexOverflow.into());
+        }
+        let mut graph = TryGraph::with_capacity(nodes, edges);
+        let mut dependencies = Vec::with_capacity(edges);
+        for shard in self.shards {
+            let id = shard.id;
+            for curry in shard.curries {
+                let _ = graph.add_curry(curry);
+            }
+            for (index, name) in shard.names {
+                graph.set_name(map.get(ShardNode::new(id, index)), name);
+            }
+            for (parent, child, index) in shard.dependencies {
+                if parent.index >= lens.get(parent.shard).copied().unwrap_or(0) {
+                    return Err(BuildError::NoSuchNode(parent));
+                }
+                dependencies.push((map.get(parent), map.get(ShardNode::new(id, child)), index));
+            }
+        }
+        graph.add_edges(dependencies)?;
+        Ok((graph, map))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GraphBuilder;
+    use super::ShardNode;
+    use crate::error::BuildError;
+    use crate::error::Error;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_graph_builder() {
+        let mut builder = GraphBuilder::new(4);
+        std::thread::scope(|scope| {
+            for shard in builder.shards_mut() {
+                let _ = scope.spawn(move || {
+                    let id = shard.id() as i32;
+                    let source = shard.add_task(move || async move { id });
+                    let child = shard.add_task(|v: i32, w: i32| async move { v + w });
+                    shard.set_name(child, format!("child {}", id));
+                    shard.update_dependency(source, child, 0);
+                    // Depends on the previous shard's source, or its own.
+                    let previous = ShardNode::new(shard.id().saturating_sub(1), 0);
+                    shard.update_dependency(previous, child, 1);
+                });
+            }
+        });
+        let (mut graph, map) = builder.build().unwrap();
+        block_on(graph.run());
+        for id in 0..4 {
+            let child = map.get(ShardNode::new(id, 1));
+            assert_eq!(graph.name(child), Some(format!("child {}", id).as_str()));
+            let previous = id.saturating_sub(1) as i32;
+            assert_eq!(graph.get_value::<i32>(child).unwrap(), id as i32 + previous);
+        }
+    }
+
+    #[test]
+    fn test_graph_builder_type_check() {
+        let mut builder = GraphBuilder::new(2);
+        let parent = builder.shards_mut()[0].add_task(|| async { 1 });
+        let shard = &mut builder.shards_mut()[1];
+        let child = shard.add_task(|_: String| async {});
+        shard.update_dependency(parent, child, 0);
+        assert!(matches!(
+            builder.build().unwrap_err(),
+            BuildError::Graph(Error::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_graph_builder_missing_parent() {
+        let mut builder = GraphBuilder::new(1);
+        let shard = &mut builder.shards_mut()[0];
+        let child = shard.add_task(|_: i32| async {});
+        shard.update_dependency(ShardNode::new(0, 1), child, 0);
+        let error = builder.build().unwrap_err();
+        assert!(matches!(error, BuildError::NoSuchNode(node) if node == ShardNode::new(0, 1)));
+        assert_eq!(error.to_string(), "shard 0 has no node 1");
+
+        let mut builder = GraphBuilder::new(1);
+        let shard = &mut builder.shards_mut()[0];
+        let child = shard.add_task(|_: i32| async {});
+        shard.update_dependency(ShardNode::new(1, 0), child, 0);
+        assert!(matches!(
+            builder.build().unwrap_err(),
+            BuildError::NoSuchNode(node) if node == ShardNode::new(1, 0)
+        ));
+    }
+}
@@ -0,0 +1,293 @@
+use super::adapter::Adapter;
+use super::adapter::Catch;
+use super::error::Error;
+use super::Edge;
+use super::EdgeMetadata;
+use super::NodeIndex;
+use super::TryGraph;
+use crate::task::IntoInfallibleTask;
+use crate::task::IntoTryTask;
+use std::collections::HashMap;
+use std::convert::Infallible;
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Creates an empty [`TryGraph`] with space for `nodes` nodes and `edges` dependencies.
+    pub fn with_capacity(nodes: usize, edges: usize) -> Self {
+        Self {
+            dag: daggy::Dag::with_capacity(nodes, edges),
+            dependencies: HashMap::with_capacity(edges),
+            ..Self::default()
+        }
+    }
+
+    /// Removes all nodes and dependencies, keeping the allocated storage for building the next graph.
+    ///
+    /// Layers and graph wide settings, e.g. resource capacities and group limits, are kept,
+    /// while open [checkpoints](TryGraph::checkpoint) are invalidated.
+    ///
+    /// Reusing one graph per request avoids reallocating node and edge storage for every request.
+    /// Tasks themselves stay boxed one by one: they're type erased and may borrow for `'a`,
+    /// so a box can't be downcast back to its task type to be refilled by the next request,
+    /// and running a task consumes its box to build the task's future anyway.
+    pub fn clear(&mut self) {
+        self.dag.clear();
+        self.dependencies.clear();
+        self.adapters.0.clear();
+        self.adapters.1.clear();
+        self.input_names.clear();
+        self.context = None;
+        self.names.clear();
+        self.kinds.clear();
+        self.schedule.clear_nodes();
+        self.edge_metadata.clear();
+        self.interned.clear();
+        self.caches.clear();
+        self.idempotency_keys.clear();
+        self.params.clear();
+        self.templates.clear();
+        self.given_inputs.clear();
+        self.outputs.clear();
+        self.reset_journal();
+    }
+
+    /// Releases storage left unused by [`TryGraph::clear`], [`TryGraph::rollback`] or removed dependencies.
+    ///
+    /// Nodes are only ever removed from the end, so their indices stay dense and no node moves.
+    pub fn compact(&mut self) {
+        self.dag.shrink_to_fit();
+        self.dependencies.shrink_to_fit();
+        self.adapters.0.shrink_to_fit();
+        self.adapters.1.shrink_to_fit();
+        self.input_names.shrink_to_fit();
+        self.names.shrink_to_fit();
+        self.kinds.shrink_to_fit();
+        self.schedule.shrink_to_fit();
+        self.edge_metadata.shrink_to_fit();
+        self.interned.shrink_to_fit();
+        self.caches.shrink_to_fit();
+        self.idempotency_keys.shrink_to_fit();
+        self.params.shrink_to_fit();
+        self.templates.shrink_to_fit();
+        self.given_inputs.shrink_to_fit();
+        self.outputs.shrink_to_fit();
+    }
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Adds all `tasks`, returning their [`NodeIndex`]es in order.
+    ///
+    /// See [`TryGraph::add_try_task`].
+    pub fn extend_try_tasks<Args, Ok, T: IntoTryTask<'a, Args, Ok, Err>>(
+        &mut self,
+        tasks: impl IntoIterator<Item = T>,
+    ) -> Vec<NodeIndex> {
+        let tasks = tasks.into_iter();
+        self.dag.reserve_nodes(tasks.size_hint().0);
+        tasks
+            .map(|task| self.add_task_impl(task.into_task()))
+            .collect()
+    }
+
+    /// Sets dependencies in bulk, each as `(parent, child, index)`.
+    ///
+    /// The dependencies are set atomically, none is set on error.
+    /// Only the added dependencies are undone on error, so the cost doesn't grow with the graph.
+    ///
+    /// See [`TryGraph::update_dependency`].
+    pub fn add_edges(
+        &mut self,
+        edges: impl IntoIterator<Item = (NodeIndex, NodeIndex, Edge)>,
+    ) -> Result<(), Error> {
+        let edges = edges.into_iter().collect::<Vec<_>>();
+        self.dag.reserve_edges(edges.len());
+        self.dependencies.reserve(edges.len());
+        // Type checks don't depend on other edges, so they're all done before mutating.
+        for &(parent, child, index) in &edges {
+            self.type_check(Some(parent), child, index, self.output_type_info(parent))?;
+        }
+        let mut replaced = vec![];
+        for (position, &(parent, child, index)) in edges.iter().enumerate() {
+            if let Some(edge) = self.dependencies.get(&(child, index)) {
+                replaced.push(Replaced {
+                    parent: self.dag.edge_endpoints(*edge).unwrap().0,
+                    child,
+                    index,
+                    adapter: self.adapters.0.get(&(child, index)).cloned(),
+                    catch: self.adapters.1.get(&(child, index)).copied(),
+                    metadata: self.edge_metadata.get(&(child, index)).cloned(),
+                });
+            }
+            // Only a cycle or an index overflow is left to fail on.
+            if let Err(error) = self.update_dependency(parent, child, index) {
+                for &(_, child, index) in edges[..position].iter().rev() {
+                    let _ = self.remove_dependency(child, index);
+                }
+                for dependency in replaced.into_iter().rev() {
+                    self.restore(dependency);
+                }
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn restore(&mut self, dependency: Replaced<'a>) {
+        let Replaced {
+            parent,
+            child,
+            index,
+            adapter,
+            catch,
+            metadata,
+        } = dependency;
+        let _ = self.remove_dependency(child, index);
+        // The graph had this dependency before, with a subset of the other dependencies it has now.
+        let edge = self.dag.add_edge(parent, child, index).unwrap();
+        let _ = self.dependencies.insert((child, index), edge);
+        self.record_dependency(child, index);
+        if let Some(adapter) = adapter {
+            let _ = self.adapters.0.insert((child, index), adapter);
+        }
+        if let Some(catch) = catch {
+            let _ = self.adapters.1.insert((child, index), catch);
+        }
+        if let Some(metadata) = metadata {
+            let _ = self.edge_metadata.insert((child, index), metadata);
+        }
+    }
+}
+
+/// A dependency replaced by [`TryGraph::add_edges`], restored if a later edge fails or a checkpoint is rolled back.
+pub(super) struct Replaced<'a> {
+    pub(super) parent: NodeIndex,
+    pub(super) child: NodeIndex,
+    pub(super) index: Edge,
+    pub(super) adapter: Option<Adapter<'a>>,
+    pub(super) catch: Option<Catch>,
+    pub(super) metadata: Option<EdgeMetadata>,
+}
+
+impl<'a, D> TryGraph<'a, Infallible, D> {
+    /// Adds all infallible `tasks`, returning their [`NodeIndex`]es in order.
+    ///
+    /// See [`TryGraph::extend_try_tasks`].
+    pub fn extend_tasks<Args, Ok, T: IntoInfallibleTask<'a, Args, Ok>>(
+        &mut self,
+        tasks: impl IntoIterator<Item = T>,
+    ) -> Vec<NodeIndex> {
+        let tasks = tasks.into_iter();
+        self.dag.reserve_nodes(tasks.size_hint().0);
+        tasks
+            .map(|task| self.add_task_impl(task.into_task()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::Graph;
+    use crate::TryGraph;
+    use futures::executor::block_on;
+    use std::convert::Infallible;
+
+    #[test]
+    fn test_bulk() {
+        let mut graph = Graph::with_capacity(11, 10);
+        let inputs = graph.extend_tasks((0..10).map(|i| move || async move { i }));
+        let sum = graph.add_sync_task(
+            |a: i32, b: i32, c: i32, d: i32, e: i32, f: i32, g: i32, h: i32, i: i32, j: i32| {
+                a + b + c + d + e + f + g + h + i + j
+            },
+        );
+        assert!(graph
+            .add_edges([(inputs[0], sum, 0), (inputs[1], sum, 10)])
+            .is_err());
+        assert_eq!(graph.as_dag().edge_count(), 0);
+        graph
+            .add_edges(inputs.iter().zip(0..).map(|(input, i)| (*input, sum, i)))
+            .unwrap();
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<i32>(sum).unwrap(), 45);
+    }
+
+    #[test]
+    fn test_add_edges_cycle() {
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        let double = graph
+            .add_child_task(one, |v: i32| async move { v * 2 }, 0)
+            .unwrap();
+        let sum = graph
+            .add_child_task(double, |a: i32, b: i32| async move { a + b }, 0)
+            .unwrap();
+        graph.update_dependency(one, sum, 1).unwrap();
+        let increment = graph.add_task(|v: i32| async move { v + 1 });
+        graph.update_dependency(one, increment, 0).unwrap();
+
+        // Replaces existing dependencies, then cycles.
+        assert!(matches!(
+            graph.add_edges([
+                (increment, sum, 1),
+                (sum, increment, 0),
+                (increment, double, 0)
+            ]),
+            Err(Error::WouldCycle { .. })
+        ));
+        assert_eq!(graph.as_dag().edge_count(), 4);
+        let parents = |graph: &Graph<'_>, child| {
+            graph
+                .dependencies(child)
+                .into_iter()
+                .map(|(index, parent, _)| (index, parent))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(parents(&graph, double), [(0, one)]);
+        assert_eq!(parents(&graph, sum), [(0, double), (1, one)]);
+        assert_eq!(parents(&graph, increment), [(0, one)]);
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<i32>(sum).unwrap(), 3);
+        assert_eq!(graph.get_value::<i32>(increment).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut graph = Graph::with_capacity(2, 1);
+        let capacity = graph.as_dag().graph().capacity();
+        for i in 0..3 {
+            let parent = graph.add_task(move || async move { i });
+            graph.set_name(parent, "parent");
+            let child = graph
+                .add_child_task(parent, |v: i32| async move { v + 1 }, 0)
+                .unwrap();
+            block_on(graph.run());
+            assert_eq!(graph.get_value::<i32>(child).unwrap(), i + 1);
+            graph.clear();
+            assert_eq!(graph.as_dag().node_count(), 0);
+            assert_eq!(graph.as_dag().graph().capacity(), capacity);
+        }
+    }
+
+    #[test]
+    fn test_with_capacity_data() {
+        let mut graph: TryGraph<'_, Infallible, &str> = TryGraph::with_capacity(2, 1);
+        let one = graph.add_task(|| async { 1 });
+        assert_eq!(graph.set_node_data(one, "one"), None);
+        assert_eq!(graph.as_dag().graph().capacity(), (2, 1));
+        assert_eq!(graph.node_data(one), Some(&"one"));
+    }
+
+    #[test]
+    fn test_compact() {
+        let mut graph = Graph::with_capacity(100, 100);
+        let checkpoint = graph.checkpoint();
+        let one = graph.add_task(|| async { 1 });
+        let _ = graph.add_child_task(one, |_: i32| async {}, 0).unwrap();
+        graph.rollback(checkpoint);
+        let one = graph.add_task(|| async { 1 });
+        graph.compact();
+        assert_eq!(graph.as_dag().graph().capacity(), (1, 0));
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<i32>(one).unwrap(), 1);
+    }
+}
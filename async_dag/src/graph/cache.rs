@@ -0,0 +1,182 @@
+use super::NodeIndex;
+use super::TryGraph;
+use crate::any::DynAny;
+use crate::any::IntoAny;
+use crate::any::TypeInfo;
+use crate::curry::TaskFuture;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use std::sync::Arc;
+
+/// A persistent store of node outputs, e.g. backed by files, sled or Redis.
+///
+/// Set by [`RunOptions::cache`](super::RunOptions::cache).
+pub trait NodeCache: Send + Sync {
+    /// Gets the serialized value stored under `key` if any.
+    fn get(&self, key: &str) -> BoxFuture<'static, Option<Vec<u8>>>;
+
+    /// Stores the serialized `value` under `key`.
+    fn put(&self, key: &str, value: Vec<u8>) -> BoxFuture<'static, ()>;
+}
+
+/// A value which can be serialized into a [`NodeCache`].
+pub trait Cacheable: Sized {
+    /// Serializes `self`.
+    fn to_bytes(&self) -> Vec<u8>;
+
+    /// Deserializes a value, [`None`] if `bytes` is invalid.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>;
+}
+
+impl Cacheable for Vec<u8> {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.clone()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Some(bytes.to_vec())
+    }
+}
+
+impl Cacheable for String {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
+
+macro_rules! cacheable_number {
+    ($($t:ty),*) => {
+        $(
+            impl Cacheable for $t {
+                fn to_bytes(&self) -> Vec<u8> {
+                    self.to_le_bytes().to_vec()
+                }
+
+                fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                    bytes.try_into().ok().map(<$t>::from_le_bytes)
+                }
+            }
+        )*
+    };
+}
+
+cacheable_number!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+/// How to cache a node's output.
+#[derive(Debug, Clone)]
+pub(super) struct CacheSpec {
+    key: String,
+    encode: fn(&DynAny) -> Vec<u8>,
+    decode: fn(&[u8]) -> Option<DynAny>,
+}
+
+fn encode<T: Cacheable + 'static>(value: &DynAny) -> Vec<u8> {
+    // The output type has been checked.
+    value.downcast_ref::<T>().unwrap().to_bytes()
+}
+
+fn decode<T: Cacheable + IntoAny>(bytes: &[u8]) -> Option<DynAny> {
+    let value: DynAny = DynAny::new(T::from_bytes(bytes)?);
+    Some(value)
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Caches `node`'s output under `key` in the [`NodeCache`] set by [`RunOptions::cache`](super::RunOptions::cache).
+    ///
+    /// When the cache has a valid value, the task's future is dropped without being polled.
+    /// Otherwise the output is stored after the task completes.
+    /// Dependencies still run, as the key is expected to identify the output regardless.
+    ///
+    /// **Panics** if `node` does not exist within the graph or its output type is not `T`.
+    pub fn cache_output<T: Cacheable + IntoAny>(
+        &mut self,
+        node: NodeIndex,
+        key: impl Into<String>,
+    ) {
+        assert_eq!(
+            self.output_type_info(node),
+            TypeInfo::of::<T>(),
+            "Caching output of another type"
+        );
+        let spec = CacheSpec {
+            key: key.into(),
+            encode: encode::<T>,
+            decode: decode::<T>,
+        };
+        let _ = self.caches.insert(node, spec);
+    }
+}
+
+/// Consults `cache` before polling `future`.
+pub(super) fn cached<'a, Err: 'a>(
+    cache: &Arc<dyn NodeCache>,
+    spec: &CacheSpec,
+    future: TaskFuture<'a, Err>,
+) -> TaskFuture<'a, Err> {
+    let cache = cache.clone();
+    let spec = spec.clone();
+    async move {
+        if let Some(value) = cache
+            .get(&spec.key)
+            .await
+            .and_then(|bytes| (spec.decode)(&bytes))
+        {
+            return Ok(value);
+        }
+        let value = future.await?;
+        cache.put(&spec.key, (spec.encode)(&value)).await;
+        Ok(value)
+    }
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NodeCache;
+    use crate::Graph;
+    use crate::RunOptions;
+    use futures::executor::block_on;
+    use futures::future::BoxFuture;
+    use futures::FutureExt;
+    use std::collections::HashMap;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryCache(Mutex<HashMap<String, Vec<u8>>>);
+
+    impl NodeCache for MemoryCache {
+        fn get(&self, key: &str) -> BoxFuture<'static, Option<Vec<u8>>> {
+            futures::future::ready(self.0.lock().unwrap().get(key).cloned()).boxed()
+        }
+
+        fn put(&self, key: &str, value: Vec<u8>) -> BoxFuture<'static, ()> {
+            let _ = self.0.lock().unwrap().insert(key.to_owned(), value);
+            futures::future::ready(()).boxed()
+        }
+    }
+
+    #[test]
+    fn test_cache_output() {
+        let cache = Arc::new(MemoryCache::default());
+        let calls = AtomicUsize::new(0);
+        for _ in 0..2 {
+            let mut graph = Graph::new();
+            let node = graph.add_task(|| async {
+                let _ = calls.fetch_add(1, Ordering::Relaxed);
+                42i64
+            });
+            graph.cache_output::<i64>(node, "answer");
+            block_on(graph.run_with(&RunOptions::new().cache(cache.clone())));
+            assert_eq!(graph.get_value::<i64>(node).unwrap(), 42);
+        }
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        assert_eq!(cache.0.lock().unwrap()["answer"], 42i64.to_le_bytes());
+    }
+}
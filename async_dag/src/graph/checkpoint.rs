@@ -0,0 +1,281 @@
+use super::Edge;
+use super::NodeIndex;
+use super::TryGraph;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+// Identifies graphs, so checkpoints can't be rolled back on other graphs.
+static NEXT_GRAPH: AtomicU64 = AtomicU64::new(0);
+
+type Undo<'a, Err, D> = Box<dyn FnOnce(&mut TryGraph<'a, Err, D>) + Send + Sync + 'a>;
+
+/// How to undo the changes made since the outermost open [`Checkpoint`].
+pub(super) struct Journal<'a, Err, D> {
+    graph: u64,
+    // Bumped whenever the journal is reset, invalidating the checkpoints into it.
+    epoch: u64,
+    recording: bool,
+    undo: Vec<Undo<'a, Err, D>>,
+}
+
+impl<Err, D> Journal<'_, Err, D> {
+    fn reset(&mut self) {
+        self.epoch += 1;
+        self.recording = false;
+        self.undo.clear();
+    }
+}
+
+impl<Err, D> Default for Journal<'_, Err, D> {
+    fn default() -> Self {
+        Self {
+            graph: NEXT_GRAPH.fetch_add(1, Ordering::Relaxed),
+            epoch: 0,
+            recording: false,
+            undo: vec![],
+        }
+    }
+}
+
+impl<Err, D> fmt::Debug for Journal<'_, Err, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Journal")
+            .field("graph", &self.graph)
+            .field("epoch", &self.epoch)
+            .field("recording", &self.recording)
+            .field("undo", &self.undo.len())
+            .finish()
+    }
+}
+
+/// Undoes `map.insert(key, _)` which returned `previous`.
+pub(super) fn restore<K: Eq + Hash, V>(map: &mut HashMap<K, V>, key: K, previous: Option<V>) {
+    let _ = match previous {
+        Some(previous) => map.insert(key, previous),
+        None => map.remove(&key),
+    };
+}
+
+/// The construction state of a [`TryGraph`], created by [`TryGraph::checkpoint`].
+///
+/// Only the numbers of nodes, layers and recorded changes are kept, changes made afterwards are undone from a journal.
+#[derive(Debug)]
+pub struct Checkpoint {
+    graph: u64,
+    epoch: u64,
+    undo: usize,
+    // Whether no other checkpoint was open, so rolling back or committing this one stops journaling.
+    outermost: bool,
+    nodes: usize,
+    layers: usize,
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Records the current construction state, which can be restored by [`TryGraph::rollback`].
+    ///
+    /// Useful for interactive builders to add nodes and dependencies tentatively.
+    /// Changes are journaled from now on, until the checkpoint is rolled back or [committed](TryGraph::commit).
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        let outermost = !self.journal.recording;
+        self.journal.recording = true;
+        Checkpoint {
+            graph: self.journal.graph,
+            epoch: self.journal.epoch,
+            undo: self.journal.undo.len(),
+            outermost,
+            nodes: self.dag.node_count(),
+            layers: self.layers.len(),
+        }
+    }
+
+    /// Undoes all changes since `checkpoint` was created,
+    /// removing added nodes and restoring dependencies, names and other settings.
+    ///
+    /// Running states, output values and [node data](TryGraph::set_node_data) of the remaining nodes are kept.
+    ///
+    /// **Panics** if `checkpoint` was not created from `self`,
+    /// or an enclosing checkpoint has been rolled back or committed since.
+    pub fn rollback(&mut self, checkpoint: Checkpoint) {
+        self.check(&checkpoint);
+        let undo = self.journal.undo.split_off(checkpoint.undo);
+        // Undoing is not journaled.
+        self.journal.recording = false;
+        for undo in undo.into_iter().rev() {
+            undo(self);
+        }
+        self.journal.recording = true;
+        while self.dag.node_count() > checkpoint.nodes {
+            // Removing the last node doesn't move other nodes, and its dependencies have been undone.
+            let last = NodeIndex::new(self.dag.node_count() - 1);
+            let _ = self.dag.remove_node(last);
+        }
+        self.layers.truncate(checkpoint.layers);
+        self.interned
+            .retain(|_, node| node.index() < checkpoint.nodes);
+        self.caches
+            .retain(|node, _| node.index() < checkpoint.nodes);
+        self.kinds.retain(|node, _| node.index() < checkpoint.nodes);
+        self.idempotency_keys
+            .retain(|node, _| node.index() < checkpoint.nodes);
+        self.params
+            .retain(|node, _| node.index() < checkpoint.nodes);
+        self.templates
+            .retain(|node, _| node.index() < checkpoint.nodes);
+        self.given_inputs
+            .retain(|(node, _)| node.index() < checkpoint.nodes);
+        self.outputs.truncate(checkpoint.nodes);
+        if checkpoint.outermost {
+            self.journal.reset();
+        }
+    }
+
+    /// Keeps all changes since `checkpoint` was created.
+    ///
+    /// Committing the outermost checkpoint stops journaling, while changes committed to an inner one
+    /// can still be undone by rolling back an enclosing checkpoint.
+    ///
+    /// **Panics** in the same cases as [`TryGraph::rollback`].
+    pub fn commit(&mut self, checkpoint: Checkpoint) {
+        self.check(&checkpoint);
+        if checkpoint.outermost {
+            self.journal.reset();
+        }
+    }
+
+    /// Mutates the graph by `mutate` atomically.
+    ///
+    /// If `mutate` returns an error, e.g. from a failed [`TryGraph::update_dependency`],
+    /// all its changes are rolled back, so the graph is never left half-mutated.
+    pub fn batch<R, E>(&mut self, mutate: impl FnOnce(&mut Self) -> Result<R, E>) -> Result<R, E> {
+        let checkpoint = self.checkpoint();
+        let result = mutate(self);
+        if result.is_err() {
+            self.rollback(checkpoint);
+        } else {
+            self.commit(checkpoint);
+        }
+        result
+    }
+
+    /// Journals how to undo a change if a checkpoint is open.
+    pub(super) fn record(&mut self, undo: impl FnOnce(&mut Self) + Send + Sync + 'a) {
+        if self.journal.recording {
+            self.journal.undo.push(Box::new(undo));
+        }
+    }
+
+    /// Journals how to undo adding `child`'s dependency at `index`.
+    pub(super) fn record_dependency(&mut self, child: NodeIndex, index: Edge) {
+        self.record(move |graph| {
+            let _ = graph.remove_dependency(child, index);
+        });
+    }
+
+    /// Forgets all checkpoints, e.g. when the graph is cleared.
+    pub(super) fn reset_journal(&mut self) {
+        self.journal.reset();
+    }
+
+    fn check(&self, checkpoint: &Checkpoint) {
+        assert_eq!(
+            checkpoint.graph, self.journal.graph,
+            "Checkpoint was not created from this graph"
+        );
+        assert!(
+            checkpoint.epoch == self.journal.epoch && checkpoint.undo <= self.journal.undo.len(),
+            "Checkpoint was invalidated by an enclosing one"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Graph;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_rollback() {
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        let two = graph.add_task(|| async { 2 });
+        let sum = graph
+            .add_child_task(one, |a: i32, b: i32| async move { a + b }, 0)
+            .unwrap();
+        graph.update_dependency(two, sum, 1).unwrap();
+        let checkpoint = graph.checkpoint();
+
+        let ten = graph.add_task(|| async { 10 });
+        graph.update_dependency(ten, sum, 1).unwrap();
+        graph.set_name(one, "one");
+        graph.set_group(sum, "sums");
+        assert!(graph.remove_dependency(sum, 0));
+        graph.layer(|_, _| -> crate::TaskFuture<'static, _> { unreachable!() });
+        graph.rollback(checkpoint);
+
+        assert_eq!(graph.as_dag().node_count(), 3);
+        assert_eq!(graph.name(one), None);
+        assert_eq!(graph.group(sum), None);
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<i32>(sum).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_batch() {
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        let result = graph.batch(|tx| {
+            let double = tx.add_child_task(one, |v: i32| async move { v * 2 }, 0)?;
+            let text = tx.add_task(|| async { "text" });
+            tx.update_dependency(text, double, 0)?;
+            Ok::<_, Box<dyn std::error::Error>>(double)
+        });
+        assert!(result.is_err());
+        assert_eq!(graph.as_dag().node_count(), 1);
+        assert_eq!(graph.as_dag().edge_count(), 0);
+
+        let double = graph
+            .batch(|tx| tx.add_child_task(one, |v: i32| async move { v * 2 }, 0))
+            .unwrap();
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<i32>(double).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_nested_checkpoints() {
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        let outer = graph.checkpoint();
+        let inner = graph.checkpoint();
+        graph.set_name(one, "one");
+        graph.commit(inner);
+        let inner = graph.checkpoint();
+        let _ = graph.add_task(|| async { 2 });
+        graph.rollback(inner);
+        assert_eq!(graph.as_dag().node_count(), 1);
+        assert_eq!(graph.name(one), Some("one"));
+        graph.rollback(outer);
+        assert_eq!(graph.name(one), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Checkpoint was not created from this graph")]
+    fn test_rollback_other_graph() {
+        let mut graph = Graph::new();
+        let mut other = Graph::new();
+        let checkpoint = other.checkpoint();
+        graph.rollback(checkpoint);
+    }
+
+    #[test]
+    #[should_panic(expected = "Checkpoint was invalidated by an enclosing one")]
+    fn test_rollback_invalidated() {
+        let mut graph = Graph::new();
+        let outer = graph.checkpoint();
+        let inner = graph.checkpoint();
+        graph.rollback(outer);
+        graph.rollback(inner);
+    }
+}
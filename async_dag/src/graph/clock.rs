@@ -0,0 +1,28 @@
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use futures_timer::Delay;
+use std::time::Duration;
+use std::time::Instant;
+
+/// A source of time for time aware scheduling.
+pub trait Clock: Send + Sync {
+    /// The current time.
+    fn now(&self) -> Instant;
+
+    /// A future that completes after `duration` has passed.
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+}
+
+/// The system [`Clock`], sleeping with [`futures_timer`] so no particular runtime is required.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Delay::new(duration).boxed()
+    }
+}
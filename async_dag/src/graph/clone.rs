@@ -0,0 +1,144 @@
+use super::Node;
+use super::NodeIndex;
+use super::NodeWeight;
+use super::TryGraph;
+use crate::curry::CurriedTask;
+use crate::curry::Curry;
+use crate::task::IntoTryTask;
+use crate::task::TryTask;
+use crate::tuple::Tuple;
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Adds a task which is cloned along with the graph by [`TryGraph::try_clone`].
+    ///
+    /// A closure can be cloned if everything it captures can.
+    /// The node can also be recomputed after [`TryGraph::update_value`].
+    ///
+    /// See [`TryGraph::add_try_task`].
+    pub fn add_cloneable_try_task<Args, Ok, T>(&mut self, task: T) -> NodeIndex
+    where
+        T: IntoTryTask<'a, Args, Ok, Err>,
+        T::Task: Clone,
+        <<T::Task as TryTask<'a>>::Inputs as Tuple>::Option: Clone,
+    {
+        self.add_cloneable_task_impl(task.into_task())
+    }
+
+    pub(super) fn add_cloneable_task_impl<T>(&mut self, task: T) -> NodeIndex
+    where
+        T: TryTask<'a, Err = Err> + Clone + 'a,
+        <T::Inputs as Tuple>::Option: Clone,
+    {
+        let curry = CurriedTask::new_cloneable(task);
+        // Kept for recomputing, see `TryGraph::update_value`.
+        let template = curry.clone_curry().unwrap();
+        let node = self.dag.add_node(NodeWeight::new(Box::new(curry)));
+        let _ = self.templates.insert(node, template);
+        node
+    }
+
+    /// Clones the graph, so one constructed graph can be run many times, e.g. concurrently for multiple requests.
+    ///
+    /// Returns [`None`] if any node is running or has failed,
+    /// or has a task not added by [`TryGraph::add_cloneable_try_task`].
+    pub fn try_clone(&self) -> Option<Self>
+    where
+        D: Clone,
+    {
+        let mut nodes = self
+            .dag
+            .raw_nodes()
+            .iter()
+            .map(|node| match &node.weight.node {
+                Node::Curry(curry) => curry.clone_curry().map(Node::Curry),
+                Node::Running(_) | Node::Failed { .. } => None,
+                Node::Value { value, type_info } => Some(Node::Value {
+                    value: value.clone(),
+                    type_info: *type_info,
+                }),
+                Node::Dropped(type_info) => Some(Node::Dropped(*type_info)),
+                Node::Skipped { type_info, cause } => Some(Node::Skipped {
+                    type_info: *type_info,
+                    cause: *cause,
+                }),
+            })
+            .map(|node| node.map(Some))
+            .collect::<Option<Vec<_>>>()?;
+        let dag = self.dag.map(
+            |index, weight| NodeWeight {
+                node: nodes[index.index()].take().unwrap(),
+                inputs: weight.inputs.clone(),
+                data: weight.data.clone(),
+            },
+            |_, edge| *edge,
+        );
+        Some(Self {
+            dag,
+            dependencies: self.dependencies.clone(),
+            adapters: self.adapters.clone(),
+            input_names: self.input_names.clone(),
+            context: self.context,
+            names: self.names.clone(),
+            kinds: self.kinds.clone(),
+            schedule: self.schedule.clone(),
+            layers: self.layers.clone(),
+            edge_metadata: self.edge_metadata.clone(),
+            interned: self.interned.clone(),
+            caches: self.caches.clone(),
+            idempotency_keys: self.idempotency_keys.clone(),
+            params: self.params.clone(),
+            catch_error: self.catch_error.clone(),
+            templates: self
+                .templates
+                .iter()
+                .map(|(node, template)| (*node, template.clone_curry().unwrap()))
+                .collect(),
+            given_inputs: self.given_inputs.clone(),
+            outputs: Default::default(),
+            journal: Default::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Graph;
+    use crate::TryGraph;
+    use futures::executor::block_on;
+    use futures::future::join;
+
+    #[test]
+    fn test_try_clone() {
+        let offset = 10;
+        let mut graph = Graph::new();
+        let input = graph.add_cloneable_task(move || async move { offset });
+        let output = graph.add_cloneable_task(|v: i32| async move { v * 2 });
+        graph.update_dependency(input, output, 0).unwrap();
+        graph.set_name(output, "output");
+
+        let (mut first, mut second) = (graph.try_clone().unwrap(), graph.try_clone().unwrap());
+        let _ = block_on(join(first.run(), second.run()));
+        assert_eq!(first.get_value::<i32>(output).unwrap(), 20);
+        assert_eq!(second.get_value::<i32>(output).unwrap(), 20);
+        assert_eq!(first.name(output), Some("output"));
+        assert!(graph.get_value::<i32>(output).is_none());
+
+        // Values are cloned as well.
+        assert_eq!(
+            first.try_clone().unwrap().get_value::<i32>(output).unwrap(),
+            20
+        );
+
+        let _ = graph.add_task(|| async {});
+        assert!(graph.try_clone().is_none());
+    }
+
+    #[test]
+    fn test_try_clone_failed() {
+        let mut graph = TryGraph::new();
+        let _ = graph.add_cloneable_try_task(|| async { Err::<i32, _>(()) });
+        assert!(graph.try_clone().is_some());
+        assert!(block_on(graph.try_run()).is_err());
+        assert!(graph.try_clone().is_none());
+    }
+}
@@ -0,0 +1,93 @@
+use super::NodeIndex;
+use super::TryGraph;
+use super::UNCHECKED;
+use crate::any::downcast;
+use crate::any::IntoAny;
+use crate::any::TypeInfo;
+use crate::curry::TaskFuture;
+use futures::channel::mpsc;
+use futures::stream;
+use futures::stream::BoxStream;
+use futures::FutureExt;
+use futures::StreamExt;
+use std::collections::HashSet;
+
+impl<'a, Err: Send + 'a, D: Send + 'a> TryGraph<'a, Err, D> {
+    /// Runs the graph, yielding the value of every [sink](TryGraph::sinks) as it completes,
+    /// e.g. for graphs ending in per partition reports of the same type.
+    ///
+    /// Sinks completed by previous runs are not yielded. The stream ends after the run,
+    /// which is aborted on the first error like [`TryGraph::try_run`].
+    ///
+    /// **Panics** if any sink's output type is not `T`, or a value can't be passed between nodes.
+    pub fn run_collect<T: IntoAny>(&mut self) -> BoxStream<'_, Result<(NodeIndex, T), Err>> {
+        let sinks = self.sinks().collect::<HashSet<_>>();
+        for sink in &sinks {
+            assert_eq!(
+                self.output_type_info(*sink),
+                TypeInfo::of::<T>(),
+                "Expecting every sink to output the collected type"
+            );
+        }
+        let (sender, receiver) = mpsc::unbounded();
+        let run = async move {
+            let outer = |index, future: TaskFuture<'a, Err>| {
+                if !sinks.contains(&index) {
+                    return future;
+                }
+                let sender = sender.clone();
+                future
+                    .inspect(move |output| {
+                        if let Ok(value) = output {
+                            // The output type has been checked.
+                            let value = downcast::<T>(value.clone()).unwrap();
+                            let _ = sender.unbounded_send((index, value));
+                        }
+                    })
+                    .boxed()
+            };
+            self.try_run_wrapped(outer)
+                .await
+                .map_err(|error| error.into_task().expect(UNCHECKED))
+        };
+        let errors = run
+            .into_stream()
+            .filter_map(|result| async move { result.err().map(Err) });
+        stream::select(receiver.map(Ok), errors).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Graph;
+    use crate::TryGraph;
+    use futures::executor::block_on;
+    use futures::StreamExt;
+
+    #[test]
+    fn test_run_collect() {
+        let mut graph = Graph::new();
+        let input = graph.add_task(|| async { 1 });
+        let sinks = (0..3)
+            .map(|i| {
+                graph
+                    .add_child_task(input, move |v: i32| async move { v + i }, 0)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let mut collected = block_on(
+            graph
+                .run_collect::<i32>()
+                .map(Result::unwrap)
+                .collect::<Vec<_>>(),
+        );
+        collected.sort();
+        assert_eq!(collected, [(sinks[0], 1), (sinks[1], 2), (sinks[2], 3)]);
+        assert_eq!(block_on(graph.run_collect::<i32>().count()), 0);
+
+        let mut graph = TryGraph::<&str>::new();
+        let _ = graph.add_try_task(|| async { Err::<i32, _>("boom") });
+        let collected = block_on(graph.run_collect::<i32>().collect::<Vec<_>>());
+        assert!(matches!(collected[..], [Err("boom")]));
+    }
+}
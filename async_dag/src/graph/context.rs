@@ -0,0 +1,67 @@
+use super::NodeIndex;
+use super::TryGraph;
+use crate::error::ErrorWithTask;
+use crate::task::IntoTryTask;
+use std::sync::Arc;
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Sets the context shared by all tasks added by [`TryGraph::add_context_try_task`],
+    /// such as configuration, connection pools or loggers.
+    ///
+    /// The context is the output of a node, whose index is returned.
+    ///
+    /// **Panics** if the context has been set.
+    pub fn set_context<C: Send + Sync + 'static>(&mut self, context: Arc<C>) -> NodeIndex
+    where
+        Err: Send,
+    {
+        assert!(self.context.is_none(), "Context has been set");
+        let node = self.add_sync_try_task(move || Ok(context));
+        self.context = Some(node);
+        self.record(|graph| graph.context = None);
+        node
+    }
+
+    /// Gets the node outputting the context if it's set.
+    pub fn context(&self) -> Option<NodeIndex> {
+        self.context
+    }
+
+    /// Adds a task whose first input is the context set by [`TryGraph::set_context`], as an `Arc<C>`.
+    ///
+    /// The other inputs are left for dependencies as usual.
+    ///
+    /// **Panics** if the context is not set.
+    pub fn add_context_try_task<Args, Ok, T: IntoTryTask<'a, Args, Ok, Err>>(
+        &mut self,
+        task: T,
+    ) -> Result<NodeIndex, ErrorWithTask<T::Task>> {
+        let context = self.context.expect("Context is not set");
+        self.add_child_task_impl(context, task.into_task(), 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Graph;
+    use futures::executor::block_on;
+    use std::sync::Arc;
+
+    struct Config {
+        factor: i32,
+    }
+
+    #[test]
+    fn test_context() {
+        let mut graph = Graph::new();
+        let _ = graph.set_context(Arc::new(Config { factor: 3 }));
+        let input = graph.add_task(|| async { 2 });
+        let output = graph
+            .add_context_task(|config: Arc<Config>, v: i32| async move { config.factor * v })
+            .unwrap();
+        graph.update_dependency(input, output, 1).unwrap();
+        assert!(graph.add_context_task(|_: Arc<i32>| async {}).is_err());
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<i32>(output).unwrap(), 6);
+    }
+}
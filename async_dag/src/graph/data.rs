@@ -0,0 +1,46 @@
+use super::NodeIndex;
+use super::TryGraph;
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Attaches `data` to `node`, returning the previous data if any.
+    ///
+    /// The data is stored in the node's [`NodeWeight`](super::NodeWeight), so it's visible through [`TryGraph::as_dag`].
+    ///
+    /// Useful for schedulers built on top to keep cost models, ownership tags, etc.
+    ///
+    /// **Panics** if `node` does not exist within the graph.
+    pub fn set_node_data(&mut self, node: NodeIndex, data: D) -> Option<D> {
+        self.dag[node].data.replace(data)
+    }
+
+    /// Gets the data attached to `node` if any.
+    pub fn node_data(&self, node: NodeIndex) -> Option<&D> {
+        self.dag.node_weight(node)?.data.as_ref()
+    }
+
+    /// Gets the data attached to `node` mutably if any.
+    pub fn node_data_mut(&mut self, node: NodeIndex) -> Option<&mut D> {
+        self.dag.node_weight_mut(node)?.data.as_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TryGraph;
+    use futures::executor::block_on;
+    use std::convert::Infallible;
+
+    #[test]
+    fn test_node_data() {
+        let mut graph = TryGraph::<Infallible, u32>::default();
+        let node = graph.add_task(|| async { 1 });
+        assert_eq!(graph.node_data(node), None);
+        assert_eq!(graph.set_node_data(node, 5), None);
+        *graph.node_data_mut(node).unwrap() += 1;
+        assert_eq!(graph.set_node_data(node, 10), Some(6));
+        block_on(graph.run());
+        assert_eq!(graph.node_data(node), Some(&10));
+        assert_eq!(graph.as_dag()[node].data, Some(10));
+        assert_eq!(graph.get_value::<i32>(node).unwrap(), 1);
+    }
+}
@@ -0,0 +1,166 @@
+use super::render::State;
+use super::NodeIndex;
+use super::RunReport;
+use super::TryGraph;
+use daggy::petgraph::visit::EdgeRef;
+use std::fmt::Write;
+use std::time::Duration;
+
+impl State {
+    /// The fill color in DOT exports.
+    fn color(self) -> &'static str {
+        match self {
+            State::Pending => "white",
+            State::Running => "gold",
+            State::Value => "palegreen",
+            State::Dropped => "darkseagreen",
+            State::Failed => "tomato",
+            State::Skipped => "lightgray",
+        }
+    }
+}
+
+// Escapes `text` within a quoted DOT string.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// The structure of a graph to be rendered with any node states.
+#[derive(Debug, Clone, Default)]
+pub(super) struct DotGraph {
+    // Labels without states, by node index.
+    nodes: Vec<String>,
+    // Sources, targets and labels.
+    edges: Vec<(usize, usize, String)>,
+}
+
+impl DotGraph {
+    /// Renders with the state and duration of each node.
+    pub(super) fn render(&self, state: impl Fn(usize) -> (State, Option<Duration>)) -> String {
+        let mut dot = String::from("digraph {\n");
+        for (index, label) in self.nodes.iter().enumerate() {
+            let (state, duration) = state(index);
+            let mut label = format!("{}\n{}", label, state.name());
+            if let Some(duration) = duration {
+                let _ = write!(label, " in {:?}", duration);
+            }
+            let _ = writeln!(
+                dot,
+                "    {} [label=\"{}\", style=filled, fillcolor={}]",
+                index,
+                escape(&label),
+                state.color()
+            );
+        }
+        for (source, target, label) in &self.edges {
+            let _ = writeln!(
+                dot,
+                "    {} -> {} [label=\"{}\"]",
+                source,
+                target,
+                escape(label)
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Exports the graph in the Graphviz DOT format.
+    ///
+    /// Nodes are labeled by index, name, output type and state, and filled by state:
+    /// white if pending, gold if running, green if completed, red if failed and gray if skipped.
+    /// Edges are labeled by input index, and by [`EdgeMetadata::label`](super::EdgeMetadata::label) if any.
+    ///
+    /// With a `report` collected by [`RunOptions::report`](super::RunOptions::report),
+    /// nodes are also annotated with how long they ran.
+    pub fn to_dot(&self, report: Option<&RunReport>) -> String {
+        self.dot_graph().render(|index| {
+            let node = NodeIndex::new(index);
+            let duration = report
+                .and_then(|report| report.node(node))
+                .and_then(|stats| stats.duration);
+            (self.dag[node].node.state(), duration)
+        })
+    }
+
+    pub(super) fn dot_graph(&self) -> DotGraph {
+        let nodes = self
+            .dag
+            .graph()
+            .node_indices()
+            .map(|node| {
+                let mut label = node.index().to_string();
+                if let Some(name) = self.name(node) {
+                    let _ = write!(label, " '{}'", name);
+                }
+                let _ = write!(label, "\n{}", self.output_type_info(node).name());
+                label
+            })
+            .collect();
+        let edges = self
+            .dag
+            .graph()
+            .edge_references()
+            .map(|edge| {
+                let index = *edge.weight();
+                let mut label = index.to_string();
+                if let Some(metadata_label) = self
+                    .edge_metadata(edge.target(), index)
+                    .and_then(|metadata| metadata.label.as_ref())
+                {
+                    let _ = write!(label, ": {}", metadata_label);
+                }
+                (edge.source().index(), edge.target().index(), label)
+            })
+            .collect();
+        DotGraph { nodes, edges }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::EdgeMetadata;
+    use crate::RunOptions;
+    use crate::RunReport;
+    use crate::TryGraph;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_to_dot() {
+        let mut graph = TryGraph::<&str>::new();
+        let one = graph.add_try_task(|| async { Ok(1) });
+        graph.set_name(one, "one");
+        let failing = graph
+            .add_child_try_task(one, |_: i32| async { Err::<i32, _>("boom") }, 0)
+            .unwrap();
+        let _ = graph
+            .add_child_try_task(failing, |v: i32| async move { Ok(v) }, 0)
+            .unwrap();
+        graph.set_edge_metadata(
+            failing,
+            0,
+            EdgeMetadata {
+                label: Some("\"seed\"".to_owned()),
+                ..Default::default()
+            },
+        );
+        let report = RunReport::new();
+        let options = RunOptions::new().continue_on_error().report(report.clone());
+        block_on(graph.try_run_with(&options)).unwrap();
+
+        let dot = graph.to_dot(Some(&report));
+        let duration = format!("{:?}", report.node(one).unwrap().duration.unwrap());
+        assert!(dot.contains(&format!(
+            "0 [label=\"0 'one'\\ni32\\nvalue in {}\", style=filled, fillcolor=palegreen]",
+            duration
+        )));
+        assert!(dot.contains("fillcolor=tomato"));
+        assert!(dot.contains(r#"2 [label="2\ni32\nskipped", style=filled, fillcolor=lightgray]"#));
+        assert!(dot.contains(r#"0 -> 1 [label="0: \"seed\""]"#));
+        assert!(dot.contains("1 -> 2 [label=\"0\"]"));
+    }
+}
@@ -0,0 +1,378 @@
+use super::error::SpecError;
+use super::EdgeSpec;
+use super::NodeSpec;
+use super::TopologySpec;
+use crate::tuple::TupleIndex;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Id(String),
+    Arrow,
+    Punct(char),
+}
+
+fn tokenize(dot: &str) -> Result<Vec<(Token, usize)>, SpecError> {
+    let error = |message: &str, offset| SpecError::Syntax {
+        message: message.to_owned(),
+        offset,
+    };
+    let mut tokens = vec![];
+    let mut chars = dot.char_indices().peekable();
+    while let Some((offset, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '#' => while chars.next_if(|(_, c)| *c != '\n').is_some() {},
+            '/' if chars.next_if(|(_, c)| *c == '/').is_some() => {
+                while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+            }
+            '/' if chars.next_if(|(_, c)| *c == '*').is_some() => loop {
+                match chars.next() {
+                    Some((_, '*')) if chars.next_if(|(_, c)| *c == '/').is_some() => break,
+                    Some(_) => {}
+                    None => return Err(error("unterminated comment", offset)),
+                }
+            },
+            '-' if chars.next_if(|(_, c)| *c == '>').is_some() => {
+                tokens.push((Token::Arrow, offset));
+            }
+            '-' if chars.next_if(|(_, c)| *c == '-').is_some() => {
+                return Err(error("undirected edges are not supported", offset));
+            }
+            '{' | '}' | '[' | ']' | '=' | ';' | ',' => tokens.push((Token::Punct(c), offset)),
+            '"' => {
+                let mut id = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, '"')) => id.push('"'),
+                            // A line continuation.
+                            Some((_, '\n')) => {}
+                            Some((_, c)) => {
+                                id.push('\\');
+                                id.push(c);
+                            }
+                            None => return Err(error("unterminated string", offset)),
+                        },
+                        Some((_, c)) => id.push(c),
+                        None => return Err(error("unterminated string", offset)),
+                    }
+                }
+                tokens.push((Token::Id(id), offset));
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let mut id = c.to_string();
+                while let Some((_, c)) =
+                    chars.next_if(|(_, c)| c.is_alphanumeric() || *c == '_' || *c == '.')
+                {
+                    id.push(c);
+                }
+                tokens.push((Token::Id(id), offset));
+            }
+            _ => return Err(error(&format!("unexpected '{}'", c), offset)),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    // The end of input, for errors there.
+    end: usize,
+}
+
+impl Parser {
+    fn offset(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .map_or(self.end, |(_, offset)| *offset)
+    }
+
+    fn error(&self, message: &str) -> SpecError {
+        SpecError::Syntax {
+            message: message.to_owned(),
+            offset: self.offset(),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let matches = self.peek() == Some(token);
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let matches =
+            matches!(self.peek(), Some(Token::Id(id)) if id.eq_ignore_ascii_case(keyword));
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), SpecError> {
+        if self.eat(&Token::Punct(c)) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", c)))
+        }
+    }
+
+    fn id(&mut self) -> Result<String, SpecError> {
+        match self.peek() {
+            Some(Token::Id(id)) => {
+                let id = id.clone();
+                self.position += 1;
+                Ok(id)
+            }
+            _ => Err(self.error("expected an id")),
+        }
+    }
+
+    /// Parses any number of `[key=value, ...]` lists.
+    fn attributes(&mut self) -> Result<Vec<(String, String, usize)>, SpecError> {
+        let mut attributes = vec![];
+        while self.eat(&Token::Punct('[')) {
+            while !self.eat(&Token::Punct(']')) {
+                let offset = self.offset();
+                let key = self.id()?;
+                self.expect('=')?;
+                attributes.push((key, self.id()?, offset));
+                let _ = self.eat(&Token::Punct(',')) || self.eat(&Token::Punct(';'));
+            }
+        }
+        Ok(attributes)
+    }
+}
+
+fn parse_index(value: &str, offset: usize) -> Result<TupleIndex, SpecError> {
+    value.parse().map_err(|_| SpecError::Syntax {
+        message: format!("invalid input index '{}'", value),
+        offset,
+    })
+}
+
+impl TopologySpec {
+    /// Parses a Graphviz DOT digraph as a skeleton to be filled with tasks.
+    ///
+    /// Every node id becomes a node named after it, in order of first appearance.
+    /// A node's `kind` attribute is its task kind, `type` its output type and `arity` its number of inputs,
+    /// which defaults to one past its highest wired input.
+    /// An edge's `input` attribute is the child input index it feeds,
+    /// which defaults to the lowest index not taken by another edge into the child.
+    /// Other attributes, graph attributes and comments are ignored. Subgraphs and undirected graphs are not supported.
+    /// Fails if the DOT is malformed, two edges feed the same input or an edge connects a node to itself.
+    ///
+    /// ```text
+    /// digraph {
+    ///     load [kind=read_file, type="alloc::string::String"]
+    ///     count [type=usize]
+    ///     load -> repeat [input=0]
+    ///     count -> repeat
+    /// }
+    /// ```
+    pub fn from_dot(dot: &str) -> Result<Self, SpecError> {
+        let mut parser = Parser {
+            tokens: tokenize(dot)?,
+            position: 0,
+            end: dot.len(),
+        };
+        let _ = parser.keyword("strict");
+        if !parser.keyword("digraph") {
+            return Err(parser.error("expected 'digraph'"));
+        }
+        if !matches!(parser.peek(), Some(Token::Punct('{'))) {
+            let _ = parser.id()?;
+        }
+        parser.expect('{')?;
+
+        let mut spec = TopologySpec::default();
+        let mut positions = HashMap::<String, usize>::new();
+        let mut position = |spec: &mut TopologySpec, id: String| {
+            *positions.entry(id).or_insert_with_key(|id| {
+                spec.nodes.push(NodeSpec {
+                    name: Some(id.clone()),
+                    ..Default::default()
+                });
+                spec.nodes.len() - 1
+            })
+        };
+        // Edges by their child input index if given.
+        let mut edges = vec![];
+        let mut explicit_arities = HashMap::new();
+        while !parser.eat(&Token::Punct('}')) {
+            if parser.peek().is_none() {
+                return Err(parser.error("expected '}'"));
+            }
+            if parser.keyword("subgraph") || parser.peek() == Some(&Token::Punct('{')) {
+                return Err(parser.error("subgraphs are not supported"));
+            }
+            if parser.keyword("graph") || parser.keyword("node") || parser.keyword("edge") {
+                let _ = parser.attributes()?;
+            } else {
+                let mut chain = vec![parser.id()?];
+                if parser.eat(&Token::Punct('=')) {
+                    // A graph attribute.
+                    let _ = parser.id()?;
+                } else {
+                    while parser.eat(&Token::Arrow) {
+                        chain.push(parser.id()?);
+                    }
+                    let attributes = parser.attributes()?;
+                    let chain = chain
+                        .into_iter()
+                        .map(|id| position(&mut spec, id))
+                        .collect::<Vec<_>>();
+                    if let [node] = chain[..] {
+                        for (key, value, offset) in attributes {
+                            match key.as_str() {
+                                "kind" => spec.nodes[node].kind = Some(value),
+                                "type" => spec.nodes[node].output_type = Some(value),
+                                "arity" => {
+                                    let _ =
+                                        explicit_arities.insert(node, parse_index(&value, offset)?);
+                                }
+                                _ => {}
+                            }
+                        }
+                    } else {
+                        let mut input = None;
+                        for (key, value, offset) in attributes {
+                            if key == "input" {
+                                input = Some(parse_index(&value, offset)?);
+                            }
+                        }
+                        for pair in chain.windows(2) {
+                            edges.push((pair[0], pair[1], input));
+                        }
+                    }
+                }
+            }
+            let _ = parser.eat(&Token::Punct(';'));
+        }
+        if parser.peek().is_some() {
+            return Err(parser.error("trailing tokens"));
+        }
+
+        let mut taken = edges
+            .iter()
+            .filter_map(|(_, child, input)| input.map(|input| (*child, input)))
+            .collect::<HashSet<_>>();
+        for (parent, child, input) in edges {
+            let input = match input {
+                Some(input) => input,
+                None => {
+                    let input = (0..=TupleIndex::MAX)
+                        .find(|input| !taken.contains(&(child, *input)))
+                        .ok_or_else(|| parser.error("too many inputs"))?;
+                    let _ = taken.insert((child, input));
+                    input
+                }
+            };
+            spec.edges.push(EdgeSpec {
+                parent,
+                child,
+                input,
+                metadata: None,
+            });
+        }
+        for (position, edge) in spec.edges.iter().enumerate() {
+            let arity = &mut spec.nodes[edge.child].arity;
+            let input_arity = edge
+                .input
+                .checked_add(1)
+                .ok_or(SpecError::InputOutOfRange(position))?;
+            *arity = (*arity).max(input_arity);
+        }
+        for (node, arity) in explicit_arities {
+            spec.nodes[node].arity = spec.nodes[node].arity.max(arity);
+        }
+        spec.validate()?;
+        Ok(spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::SpecError;
+    use crate::EdgeSpec;
+    use crate::NodeSpec;
+    use crate::TopologySpec;
+
+    #[test]
+    fn test_from_dot() {
+        let spec = TopologySpec::from_dot(
+            r#"
+            // A prototype.
+            digraph pipeline {
+                rankdir=LR
+                node [shape=box]
+                load [kind=read, type="alloc::string::String", label="Load"];
+                count [type=usize]
+                count -> repeat
+                load -> repeat [input=0]
+                repeat -> "print it"  /* sink */
+            }
+            "#,
+        )
+        .unwrap();
+        let node = |name: &str, output_type: Option<&str>, arity| NodeSpec {
+            name: Some(name.to_owned()),
+            kind: None,
+            output_type: output_type.map(ToOwned::to_owned),
+            arity,
+        };
+        assert_eq!(
+            spec.nodes,
+            [
+                NodeSpec {
+                    kind: Some("read".to_owned()),
+                    ..node("load", Some("alloc::string::String"), 0)
+                },
+                node("count", Some("usize"), 0),
+                node("repeat", None, 2),
+                node("print it", None, 1),
+            ]
+        );
+        let edge = |parent, child, input| EdgeSpec {
+            parent,
+            child,
+            input,
+            metadata: None,
+        };
+        assert_eq!(spec.edges, [edge(1, 2, 1), edge(0, 2, 0), edge(2, 3, 0)]);
+
+        assert_eq!(
+            TopologySpec::from_dot("digraph { a -- b }").unwrap_err(),
+            SpecError::Syntax {
+                message: "undirected edges are not supported".to_owned(),
+                offset: 12,
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_dot_invalid_edges() {
+        assert_eq!(
+            TopologySpec::from_dot("digraph { a -> b [input=255] }"),
+            Err(SpecError::InputOutOfRange(0))
+        );
+        assert_eq!(
+            TopologySpec::from_dot("digraph { a -> c [input=0]; b -> c [input=0] }"),
+            Err(SpecError::DuplicateInput(1))
+        );
+        assert_eq!(
+            TopologySpec::from_dot("digraph { a -> a }"),
+            Err(SpecError::SelfLoop(0))
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use super::error::Error;
+use super::Edge;
+use super::NodeIndex;
+use super::TryGraph;
+use crate::any::downcast;
+use crate::any::DynAny;
+use crate::any::TypeInfo;
+use crate::curry::Curry;
+use crate::curry::TaskFuture;
+use crate::task::IntoTryTask;
+use crate::task::TryTask;
+use crate::tuple::InsertError;
+use crate::tuple::InsertErrorKind;
+use crate::tuple::InsertResult;
+use crate::tuple::TakeError;
+use crate::tuple::Tuple;
+use crate::tuple::TupleIndex;
+use futures::FutureExt;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::any::TypeId;
+
+/// A graph whose edges all carry [`serde_json::Value`]s, for pipelines defined entirely at runtime.
+///
+/// Only typed tasks converting at their boundaries and JSON values can be added, so every edge carries a [`Value`].
+#[derive(Debug)]
+pub struct DynamicGraph<'a, Err = serde_json::Error> {
+    graph: TryGraph<'a, Err>,
+}
+
+/// Runs a typed task on JSON inputs, outputting JSON.
+struct Dynamic<T> {
+    task: T,
+    inputs: Vec<Option<Value>>,
+}
+
+impl<'a, T> Curry<'a, T::Err> for Dynamic<T>
+where
+    T: TryTask<'a> + Send + 'a,
+    T::Inputs: DeserializeOwned,
+    T::Ok: Serialize,
+    T::Err: From<serde_json::Error>,
+{
+    fn num_inputs(&self) -> TupleIndex {
+        T::Inputs::LEN
+    }
+
+    fn input_type_info(&self, index: TupleIndex) -> Option<TypeInfo> {
+        (index < T::Inputs::LEN).then(TypeInfo::of::<Value>)
+    }
+
+    fn output_type_info(&self) -> TypeInfo {
+        TypeInfo::of::<Value>()
+    }
+
+    fn ready(&self) -> bool {
+        self.inputs.iter().all(Option::is_some)
+    }
+
+    fn curry(&mut self, index: TupleIndex, value: DynAny) -> InsertResult {
+        let input = match self.inputs.get_mut(usize::from(index)) {
+            Some(input) => input,
+            None => {
+                return Err(InsertError {
+                    kind: InsertErrorKind::OutOfRange,
+                    value: value.into_any(),
+                })
+            }
+        };
+        *input = Some(downcast::<Value>(value).map_err(|value| InsertError {
+            kind: InsertErrorKind::TypeMismatch {
+                expected: TypeId::of::<Value>(),
+                expected_name: TypeInfo::of::<Value>().name(),
+            },
+            value: value.into_any(),
+        })?);
+        Ok(())
+    }
+
+    fn call(self: Box<Self>) -> Result<TaskFuture<'a, T::Err>, TakeError> {
+        let Dynamic { task, inputs } = *self;
+        let inputs = inputs
+            .into_iter()
+            .enumerate()
+            .map(|(index, input)| {
+                input.ok_or(TakeError {
+                    // Indices are bounded by `TupleIndex`.
+                    index: index as TupleIndex,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(async move {
+            // Tuples deserialize from arrays, except `()` which deserializes from `null`.
+            let inputs = if inputs.is_empty() {
+                Value::Null
+            } else {
+                Value::Array(inputs)
+            };
+            let inputs = serde_json::from_value(inputs)?;
+            let output = serde_json::to_value(task.run(inputs).await?)?;
+            let output: DynAny = DynAny::new(output);
+            Ok(output)
+        }
+        .boxed())
+    }
+}
+
+impl<'a, Err: 'a> DynamicGraph<'a, Err> {
+    /// Creates an empty graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a typed task taking and outputting [`serde_json::Value`]s.
+    ///
+    /// Every input is deserialized as the task's input type, and the output serialized, when the task runs.
+    /// Failing conversions fail the task with the [`serde_json::Error`].
+    pub fn add_try_task<Args, Ok, T>(&mut self, task: T) -> NodeIndex
+    where
+        T: IntoTryTask<'a, Args, Ok, Err>,
+        <T::Task as TryTask<'a>>::Inputs: DeserializeOwned,
+        T::Task: Send,
+        Ok: Serialize,
+        Err: From<serde_json::Error>,
+    {
+        self.graph.add_curry(Box::new(Dynamic {
+            task: task.into_task(),
+            inputs: vec![None; usize::from(<T::Task as TryTask<'a>>::Inputs::LEN)],
+        }))
+    }
+
+    /// Adds a node outputting `value`, e.g. a parameter.
+    pub fn add_value(&mut self, value: Value) -> NodeIndex
+    where
+        Err: Send,
+    {
+        self.graph.add_try_task(move || async move { Ok(value) })
+    }
+
+    /// Sets `parent` as `child`'s dependency at `index`, see [`TryGraph::update_dependency`].
+    pub fn update_dependency(
+        &mut self,
+        parent: NodeIndex,
+        child: NodeIndex,
+        index: Edge,
+    ) -> Result<(), Error> {
+        self.graph.update_dependency(parent, child, index)
+    }
+
+    /// Sets the name of `node`, see [`TryGraph::set_name`].
+    pub fn set_name(&mut self, node: NodeIndex, name: impl Into<String>) {
+        self.graph.set_name(node, name);
+    }
+
+    /// Runs the graph, see [`TryGraph::try_run`].
+    pub async fn try_run(&mut self) -> Result<(), Err> {
+        self.graph.try_run().await
+    }
+
+    /// Gets the output value of `node`, see [`TryGraph::get_value`].
+    pub fn get_value(&self, node: NodeIndex) -> Option<Value> {
+        self.graph.get_value(node)
+    }
+
+    /// The underlying graph, e.g. to inspect its topology.
+    pub fn graph(&self) -> &TryGraph<'a, Err> {
+        &self.graph
+    }
+
+    /// Unwraps the underlying graph, which can then be given tasks of any types.
+    pub fn into_graph(self) -> TryGraph<'a, Err> {
+        self.graph
+    }
+}
+
+impl<'a, Err: 'a> Default for DynamicGraph<'a, Err> {
+    fn default() -> Self {
+        Self {
+            graph: TryGraph::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DynamicGraph;
+    use futures::executor::block_on;
+    use serde_json::json;
+
+    #[test]
+    fn test_dynamic_graph() {
+        let mut graph: DynamicGraph<'_> = DynamicGraph::new();
+        let name = graph.add_value(json!("world"));
+        let times = graph.add_value(json!(2));
+        let greet = graph.add_try_task(|name: String, times: usize| async move {
+            Ok(vec![format!("hello {}", name); times])
+        });
+        graph.update_dependency(name, greet, 0).unwrap();
+        graph.update_dependency(times, greet, 1).unwrap();
+        block_on(graph.try_run()).unwrap();
+        assert_eq!(
+            graph.get_value(greet).unwrap(),
+            json!(["hello world", "hello world"])
+        );
+
+        let mut graph: DynamicGraph<'_> = DynamicGraph::new();
+        let text = graph.add_value(json!("not a number"));
+        let double = graph.add_try_task(|n: i64| async move { Ok(n * 2) });
+        graph.update_dependency(text, double, 0).unwrap();
+        assert!(block_on(graph.try_run()).is_err());
+    }
+
+    #[test]
+    fn test_dynamic_graph_no_inputs() {
+        let mut graph: DynamicGraph<'_> = DynamicGraph::new();
+        let answer = graph.add_try_task(|| async { Ok(42) });
+        block_on(graph.try_run()).unwrap();
+        assert_eq!(graph.get_value(answer).unwrap(), json!(42));
+    }
+}
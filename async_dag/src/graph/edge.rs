@@ -0,0 +1,86 @@
+use super::checkpoint::restore;
+use super::Edge;
+use super::NodeIndex;
+use super::TryGraph;
+use daggy::Walker;
+
+/// User metadata of a dependency edge, in addition to its input index.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdgeMetadata {
+    /// A human readable label.
+    pub label: Option<String>,
+    /// A weight, e.g. the cost of transferring the value.
+    pub weight: Option<f64>,
+    /// Where the dependency comes from, e.g. the config file declaring it.
+    pub provenance: Option<String>,
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Attaches `metadata` to `child`'s dependency at `index`, replacing the previous one if any.
+    ///
+    /// The metadata is removed with the dependency.
+    ///
+    /// **Panics** if `child` has no dependency at `index`.
+    pub fn set_edge_metadata(&mut self, child: NodeIndex, index: Edge, metadata: EdgeMetadata) {
+        assert!(
+            self.dependencies.contains_key(&(child, index)),
+            "No dependency at input {}",
+            index
+        );
+        let previous = self.edge_metadata.insert((child, index), metadata);
+        self.record(move |graph| restore(&mut graph.edge_metadata, (child, index), previous));
+    }
+
+    /// Gets the metadata of `child`'s dependency at `index` if any.
+    pub fn edge_metadata(&self, child: NodeIndex, index: Edge) -> Option<&EdgeMetadata> {
+        self.edge_metadata.get(&(child, index))
+    }
+
+    /// Gets `child`'s dependencies as input indices, parents and metadata, ordered by input index.
+    ///
+    /// **Panics** if `child` does not exist within the graph.
+    pub fn dependencies(&self, child: NodeIndex) -> Vec<(Edge, NodeIndex, Option<&EdgeMetadata>)> {
+        assert!(self.dag.node_weight(child).is_some());
+        let mut dependencies = self
+            .dag
+            .parents(child)
+            .iter(&self.dag)
+            .map(|(edge, parent)| {
+                let index = self.dag[edge];
+                (index, parent, self.edge_metadata(child, index))
+            })
+            .collect::<Vec<_>>();
+        dependencies.sort_by_key(|(index, ..)| *index);
+        dependencies
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EdgeMetadata;
+    use crate::Graph;
+
+    #[test]
+    fn test_edge_metadata() {
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        let two = graph.add_task(|| async { 2 });
+        let sum = graph.add_task(|a: i32, b: i32| async move { a + b });
+        graph.update_dependency(two, sum, 1).unwrap();
+        graph.update_dependency(one, sum, 0).unwrap();
+        let metadata = EdgeMetadata {
+            label: Some("rhs".to_owned()),
+            weight: Some(2.0),
+            ..Default::default()
+        };
+        graph.set_edge_metadata(sum, 1, metadata.clone());
+        assert_eq!(
+            graph.dependencies(sum),
+            vec![(0, one, None), (1, two, Some(&metadata))]
+        );
+
+        graph.update_dependency(one, sum, 1).unwrap();
+        assert_eq!(graph.edge_metadata(sum, 1), None);
+    }
+}
@@ -1,9 +1,30 @@
 //! The error types.
 
 use super::NodeIndex;
+use super::ShardNode;
 use crate::any::TypeInfo;
+use crate::tuple::InsertErrorKind;
+use crate::tuple::TakeError;
 use crate::tuple::TupleIndex;
 
+/// A node's index and its name if it has one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeLabel {
+    /// The node's index.
+    pub index: NodeIndex,
+    /// The node's name.
+    pub name: Option<String>,
+}
+
+impl std::fmt::Display for NodeLabel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "'{}'", name),
+            None => write!(f, "{}", self.index.index()),
+        }
+    }
+}
+
 /// Errors that can happen during graph construction.
 #[derive(Debug)]
 #[allow(variant_size_differences)]
@@ -18,9 +39,43 @@ pub enum Error {
         input: TypeInfo,
         /// The output type from the parent.
         output: TypeInfo,
+        /// The parent, [`None`] if it was being added or the output is a value given by
+        /// [`TryGraph::set_input`](super::TryGraph::set_input).
+        parent: Option<Box<NodeLabel>>,
+        /// The child, [`None`] if it was being added.
+        child: Option<Box<NodeLabel>>,
+        /// The child's input index.
+        index: TupleIndex,
     },
     /// Adding the specified dependency would have caused the graph to cycle.
-    WouldCycle,
+    WouldCycle {
+        /// The existing path from the child back to the parent, both inclusive.
+        path: Vec<NodeLabel>,
+    },
+    /// The parent's `output` type doesn't match exactly one of the child's inputs.
+    Ambiguous {
+        /// The output type from the parent.
+        output: TypeInfo,
+        /// The child's input indices of the `output` type, empty if there's none.
+        candidates: Vec<TupleIndex>,
+    },
+    /// The dependent node has no input with the specified name.
+    NoSuchInput(String),
+    /// No resource is defined with the specified name.
+    NoSuchResource(String),
+    /// A node would require more units of a resource than its capacity, so it could never start.
+    ExceedsCapacity {
+        /// The node.
+        node: NodeIndex,
+        /// The resource's name.
+        resource: String,
+        /// The units the node requires.
+        amount: usize,
+        /// The resource's capacity.
+        capacity: usize,
+    },
+    /// The graph already has as many nodes or dependencies as its [`Index`](super::Index) type can address.
+    IndexOverflow,
 }
 
 impl std::fmt::Display for Error {
@@ -28,18 +83,228 @@ impl std::fmt::Display for Error {
         match self {
             Self::HasStarted(index) => f.debug_tuple("Error::HasStarted").field(index).finish(),
             Self::OutOfRange(len) => f.debug_tuple("Error::OutOfRange").field(len).finish(),
-            Self::TypeMismatch { input, output } => f
-                .debug_struct("Error::TypeMismatch")
-                .field("input", input)
+            Self::TypeMismatch {
+                input,
+                output,
+                parent,
+                child,
+                index,
+            } => write!(
+                f,
+                "output of {} ({}) cannot feed input {} of {} (expects {})",
+                DisplayNode(parent.as_deref()),
+                output.name(),
+                index,
+                DisplayNode(child.as_deref()),
+                input.name(),
+            ),
+            Self::WouldCycle { path } => f
+                .debug_struct("Error::WouldCycle")
+                .field("path", path)
+                .finish(),
+            Self::Ambiguous { output, candidates } => f
+                .debug_struct("Error::Ambiguous")
                 .field("output", output)
+                .field("candidates", candidates)
                 .finish(),
-            Self::WouldCycle => f.debug_tuple("Error::WouldCycle").finish(),
+            Self::NoSuchInput(name) => f.debug_tuple("Error::NoSuchInput").field(name).finish(),
+            Self::NoSuchResource(name) => {
+                f.debug_tuple("Error::NoSuchResource").field(name).finish()
+            }
+            Self::ExceedsCapacity {
+                node,
+                resource,
+                amount,
+                capacity,
+            } => f
+                .debug_struct("Error::ExceedsCapacity")
+                .field("node", node)
+                .field("resource", resource)
+                .field("amount", amount)
+                .field("capacity", capacity)
+                .finish(),
+            Self::IndexOverflow => write!(f, "Error::IndexOverflow"),
         }
     }
 }
 
 impl std::error::Error for Error {}
 
+struct DisplayNode<'a>(Option<&'a NodeLabel>);
+
+impl<'a> std::fmt::Display for DisplayNode<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(label) => write!(f, "node {}", label),
+            None => f.write_str("the new node"),
+        }
+    }
+}
+
+/// A node's inputs didn't match what its [`Curry`](crate::Curry) accepts at runtime,
+/// e.g. a custom [`Curry`](crate::Curry) declared other input types than it accepts.
+#[derive(Debug)]
+#[allow(variant_size_differences)]
+pub enum DependencyError {
+    /// The child rejected the parent's output.
+    Curry {
+        /// The completed node.
+        parent: NodeIndex,
+        /// The node the output was given to.
+        child: NodeIndex,
+        /// The child's input index.
+        index: TupleIndex,
+        /// The output type from the parent.
+        output: TypeInfo,
+        /// Why the child rejected it.
+        kind: InsertErrorKind,
+    },
+    /// The node claimed to be ready but some input is missing.
+    Call {
+        /// The ready node.
+        node: NodeIndex,
+        /// The missing input.
+        error: TakeError,
+    },
+    /// The node's output isn't of its declared type, e.g. replaced by [`RunOptions::on_value`](super::RunOptions::on_value).
+    Output {
+        /// The completed node.
+        node: NodeIndex,
+        /// The declared output type.
+        expected: TypeInfo,
+    },
+}
+
+impl std::fmt::Display for DependencyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Curry {
+                parent,
+                child,
+                index,
+                output,
+                kind,
+            } => f
+                .debug_struct("DependencyError::Curry")
+                .field("parent", parent)
+                .field("child", child)
+                .field("index", index)
+                .field("output", output)
+                .field("kind", kind)
+                .finish(),
+            Self::Call { node, error } => f
+                .debug_struct("DependencyError::Call")
+                .field("node", node)
+                .field("error", error)
+                .finish(),
+            Self::Output { node, expected } => f
+                .debug_struct("DependencyError::Output")
+                .field("node", node)
+                .field("expected", expected)
+                .finish(),
+        }
+    }
+}
+
+impl std::error::Error for DependencyError {}
+
+/// Errors that can happen during a run checked by [`TryGraph::try_run_checked`](super::TryGraph::try_run_checked).
+#[derive(Debug)]
+pub enum RunError<Err> {
+    /// A task failed.
+    Task(Err),
+    /// Values couldn't be passed between nodes.
+    Dependency(DependencyError),
+}
+
+impl<Err> RunError<Err> {
+    /// Takes the task error, or returns the dependency error.
+    pub fn into_task(self) -> Result<Err, DependencyError> {
+        match self {
+            Self::Task(error) => Ok(error),
+            Self::Dependency(error) => Err(error),
+        }
+    }
+}
+
+impl<Err: std::fmt::Debug> std::fmt::Display for RunError<Err> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Task(error) => f.debug_tuple("RunError::Task").field(error).finish(),
+            Self::Dependency(error) => f.debug_tuple("RunError::Dependency").field(error).finish(),
+        }
+    }
+}
+
+impl<Err: std::fmt::Debug> std::error::Error for RunError<Err> {}
+
+/// Errors that can happen in [`TryGraph::try_execute`](super::TryGraph::try_execute).
+#[derive(Debug)]
+pub enum ExecuteError<Err> {
+    /// A task failed.
+    Task(Err),
+    /// Values couldn't be passed between nodes.
+    Dependency(DependencyError),
+    /// The root's output value couldn't be taken after running.
+    Value(GetValueError),
+}
+
+impl<Err> From<RunError<Err>> for ExecuteError<Err> {
+    fn from(error: RunError<Err>) -> Self {
+        match error {
+            RunError::Task(error) => Self::Task(error),
+            RunError::Dependency(error) => Self::Dependency(error),
+        }
+    }
+}
+
+impl<Err: std::fmt::Debug> std::fmt::Display for ExecuteError<Err> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Task(error) => f.debug_tuple("ExecuteError::Task").field(error).finish(),
+            Self::Dependency(error) => f
+                .debug_tuple("ExecuteError::Dependency")
+                .field(error)
+                .finish(),
+            Self::Value(error) => f.debug_tuple("ExecuteError::Value").field(error).finish(),
+        }
+    }
+}
+
+impl<Err: std::fmt::Debug> std::error::Error for ExecuteError<Err> {}
+
+/// Errors that can happen when getting a node's output value.
+#[derive(Debug)]
+pub enum GetValueError {
+    /// The node's task hasn't done running.
+    NotCompleted,
+    /// The node's output type is not the requested one.
+    TypeMismatch {
+        /// The output type of the node.
+        stored: TypeInfo,
+        /// The requested type.
+        requested: TypeInfo,
+    },
+    /// The node does not exist within the graph.
+    NoSuchNode,
+}
+
+impl std::fmt::Display for GetValueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotCompleted => f.debug_tuple("GetValueError::NotCompleted").finish(),
+            Self::TypeMismatch { stored, requested } => f
+                .debug_struct("GetValueError::TypeMismatch")
+                .field("stored", stored)
+                .field("requested", requested)
+                .finish(),
+            Self::NoSuchNode => f.debug_tuple("GetValueError::NoSuchNode").finish(),
+        }
+    }
+}
+
+impl std::error::Error for GetValueError {}
+
 /// An [`Error`] and a [`TryTask`](crate::task::TryTask).
 #[derive(Debug)]
 pub struct ErrorWithTask<T> {
@@ -59,3 +324,180 @@ impl<T: std::fmt::Debug> std::fmt::Display for ErrorWithTask<T> {
 }
 
 impl<T: std::fmt::Debug> std::error::Error for ErrorWithTask<T> {}
+
+/// An expression couldn't be built, see [`TryGraph::from_expr`](super::TryGraph::from_expr).
+#[derive(Debug)]
+pub enum ExprError {
+    /// The expression is malformed.
+    Syntax {
+        /// What's wrong.
+        message: String,
+        /// The byte offset into the expression where it went wrong.
+        offset: usize,
+    },
+    /// No task or value is bound to the name.
+    Unbound(String),
+    /// A task is applied to the wrong number of arguments.
+    Arity {
+        /// The task's name.
+        name: String,
+        /// How many inputs the task takes.
+        expected: TupleIndex,
+        /// How many arguments it's applied to.
+        found: usize,
+    },
+    /// An argument's output can't feed the task's input.
+    Graph(Error),
+}
+
+impl From<Error> for ExprError {
+    fn from(error: Error) -> Self {
+        Self::Graph(error)
+    }
+}
+
+impl std::fmt::Display for ExprError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Syntax { message, offset } => write!(f, "{} at byte {}", message, offset),
+            Self::Unbound(name) => write!(f, "'{}' is not bound", name),
+            Self::Arity {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "'{}' takes {} arguments but {} were given",
+                name, expected, found
+            ),
+            Self::Graph(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+/// A [`GraphBuilder`](super::GraphBuilder) couldn't merge its shards, see [`GraphBuilder::build`](super::GraphBuilder::build).
+#[derive(Debug)]
+pub enum BuildError {
+    /// A dependency's parent doesn't exist.
+    NoSuchNode(ShardNode),
+    /// Adding the nodes or dependencies to the graph failed.
+    Graph(Error),
+}
+
+impl From<Error> for BuildError {
+    fn from(error: Error) -> Self {
+        Self::Graph(error)
+    }
+}
+
+impl std::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoSuchNode(node) => {
+                write!(f, "shard {} has no node {}", node.shard(), node.index())
+            }
+            Self::Graph(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// A [`TopologySpec`](super::TopologySpec) couldn't be parsed or is invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpecError {
+    /// The input is malformed.
+    Syntax {
+        /// What's wrong.
+        message: String,
+        /// The byte offset into the input where it went wrong.
+        offset: usize,
+    },
+    /// The edge at the position refers to a missing node.
+    MissingNode(usize),
+    /// The edge at the position feeds an input beyond the child's arity, or beyond any arity.
+    InputOutOfRange(usize),
+    /// The edge at the position feeds the same input as an earlier edge.
+    DuplicateInput(usize),
+    /// The edge at the position connects a node to itself.
+    SelfLoop(usize),
+}
+
+impl std::fmt::Display for SpecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Syntax { message, offset } => write!(f, "{} at byte {}", message, offset),
+            Self::MissingNode(edge) => write!(f, "edge {} refers to a missing node", edge),
+            Self::InputOutOfRange(edge) => {
+                write!(f, "edge {} feeds an input beyond the child's arity", edge)
+            }
+            Self::DuplicateInput(edge) => {
+                write!(
+                    f,
+                    "edge {} feeds an input already fed by another edge",
+                    edge
+                )
+            }
+            Self::SelfLoop(edge) => write!(f, "edge {} connects a node to itself", edge),
+        }
+    }
+}
+
+impl std::error::Error for SpecError {}
+
+/// A graph couldn't be assembled from a [`TaskRegistry`](super::TaskRegistry).
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub enum RegistryError {
+    /// The node at the position in the spec has no kind.
+    MissingKind(usize),
+    /// No task kind is registered by the name.
+    UnknownKind(String),
+    /// The parameters couldn't be deserialized for the kind.
+    Params {
+        /// The kind.
+        kind: String,
+        /// Why the parameters are invalid.
+        error: serde_json::Error,
+    },
+    /// The arity or output type of the node at the position in the spec disagrees with its kind.
+    Signature {
+        /// The node's position in the spec.
+        node: usize,
+        /// The kind's signature.
+        signature: super::Signature,
+    },
+    /// Wiring an edge failed.
+    Graph(Error),
+}
+
+#[cfg(feature = "serde")]
+impl From<Error> for RegistryError {
+    fn from(error: Error) -> Self {
+        Self::Graph(error)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingKind(node) => write!(f, "node {} has no kind", node),
+            Self::UnknownKind(kind) => write!(f, "unknown task kind '{}'", kind),
+            Self::Params { kind, error } => {
+                write!(f, "invalid parameters for task kind '{}': {}", kind, error)
+            }
+            Self::Signature { node, signature } => write!(
+                f,
+                "node {} disagrees with its kind's signature {:?}",
+                node, signature
+            ),
+            Self::Graph(error) => error.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::error::Error for RegistryError {}
@@ -0,0 +1,233 @@
+use super::error::ExprError;
+use super::DynCurry;
+use super::NodeIndex;
+use super::TryGraph;
+use crate::any::IntoAny;
+use crate::curry::CurriedTask;
+use crate::task::IntoInfallibleTask;
+use crate::task::IntoTryTask;
+use std::collections::HashMap;
+use std::convert::Infallible;
+
+type Factory<'a, Err> = Box<dyn Fn() -> DynCurry<'a, Err> + Send + Sync + 'a>;
+
+/// Named tasks and values an expression is built from, see [`TryGraph::from_expr`].
+pub struct Bindings<'a, Err> {
+    factories: HashMap<String, Factory<'a, Err>>,
+}
+
+impl<'a, Err> std::fmt::Debug for Bindings<'a, Err> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.factories.keys()).finish()
+    }
+}
+
+impl<'a, Err> Default for Bindings<'a, Err> {
+    fn default() -> Self {
+        Self {
+            factories: HashMap::new(),
+        }
+    }
+}
+
+impl<'a, Err: 'a> Bindings<'a, Err> {
+    /// Creates empty bindings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds `name` to `task`, cloned for every occurrence, replacing the previous binding if any.
+    pub fn try_task<Args, Ok, T>(mut self, name: impl Into<String>, task: T) -> Self
+    where
+        T: IntoTryTask<'a, Args, Ok, Err> + Clone + Send + Sync + 'a,
+    {
+        let factory: Factory<'a, Err> =
+            Box::new(move || Box::new(CurriedTask::new(task.clone().into_task())));
+        let _ = self.factories.insert(name.into(), factory);
+        self
+    }
+
+    /// Binds `name` to a node outputting `value`, replacing the previous binding if any.
+    pub fn value<T: IntoAny + Clone + Sync>(self, name: impl Into<String>, value: T) -> Self
+    where
+        Err: Send,
+    {
+        self.try_task(name, move || async move { Ok(value) })
+    }
+}
+
+impl<'a> Bindings<'a, Infallible> {
+    /// Binds `name` to an infallible `task`. See [`Bindings::try_task`].
+    pub fn task<Args, Ok, T>(mut self, name: impl Into<String>, task: T) -> Self
+    where
+        T: IntoInfallibleTask<'a, Args, Ok> + Clone + Send + Sync + 'a,
+    {
+        let factory: Factory<'a, Infallible> =
+            Box::new(move || Box::new(CurriedTask::new(task.clone().into_task())));
+        let _ = self.factories.insert(name.into(), factory);
+        self
+    }
+}
+
+/// A parsed call `name(args...)`, or a bare `name` without arguments.
+struct Call {
+    name: String,
+    args: Vec<Call>,
+}
+
+struct Parser<'e> {
+    expr: &'e str,
+    offset: usize,
+}
+
+impl<'e> Parser<'e> {
+    fn error(&self, message: &str) -> ExprError {
+        ExprError::Syntax {
+            message: message.to_owned(),
+            offset: self.offset,
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        let rest = &self.expr[self.offset..];
+        self.offset += rest.len() - rest.trim_start().len();
+        self.expr[self.offset..].chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let matches = self.peek() == Some(c);
+        if matches {
+            self.offset += c.len_utf8();
+        }
+        matches
+    }
+
+    fn call(&mut self) -> Result<Call, ExprError> {
+        let _ = self.peek();
+        let rest = &self.expr[self.offset..];
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("expected a name"));
+        }
+        let name = rest[..len].to_owned();
+        self.offset += len;
+        let mut args = vec![];
+        if self.eat('(') && !self.eat(')') {
+            loop {
+                args.push(self.call()?);
+                if self.eat(')') {
+                    break;
+                }
+                if !self.eat(',') {
+                    return Err(self.error("expected ',' or ')'"));
+                }
+            }
+        }
+        Ok(Call { name, args })
+    }
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Adds the nodes of `expr`, returning the root's index. See [`TryGraph::from_expr`].
+    ///
+    /// The graph is left unchanged on errors.
+    pub fn add_expr(
+        &mut self,
+        expr: &str,
+        bindings: &Bindings<'a, Err>,
+    ) -> Result<NodeIndex, ExprError> {
+        let mut parser = Parser { expr, offset: 0 };
+        let call = parser.call()?;
+        if parser.peek().is_some() {
+            return Err(parser.error("trailing characters"));
+        }
+        self.batch(|graph| graph.add_call(&call, bindings))
+    }
+
+    fn add_call(
+        &mut self,
+        call: &Call,
+        bindings: &Bindings<'a, Err>,
+    ) -> Result<NodeIndex, ExprError> {
+        let factory = bindings
+            .factories
+            .get(&call.name)
+            .ok_or_else(|| ExprError::Unbound(call.name.clone()))?;
+        let curry = factory();
+        let expected = curry.num_inputs();
+        if usize::from(expected) != call.args.len() {
+            return Err(ExprError::Arity {
+                name: call.name.clone(),
+                expected,
+                found: call.args.len(),
+            });
+        }
+        let node = self.add_curry(curry);
+        for (index, arg) in (0..expected).zip(&call.args) {
+            let parent = self.add_call(arg, bindings)?;
+            self.update_dependency(parent, node, index)?;
+        }
+        Ok(node)
+    }
+}
+
+impl<'a, Err: 'a> TryGraph<'a, Err> {
+    /// Builds the graph of a nested call expression such as `sum(sum(a, b), c)`, returning it and the root's index.
+    ///
+    /// Every occurrence of a name adds a node from its binding in `bindings`, whose inputs are the nodes of the
+    /// arguments in order. A name without parentheses is a call without arguments, e.g. of a bound value.
+    pub fn from_expr(
+        expr: &str,
+        bindings: &Bindings<'a, Err>,
+    ) -> Result<(Self, NodeIndex), ExprError> {
+        let mut graph = Self::new();
+        let root = graph.add_expr(expr, bindings)?;
+        Ok((graph, root))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Bindings;
+    use crate::error::Error;
+    use crate::error::ExprError;
+    use crate::Graph;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_from_expr() {
+        let bindings = Bindings::new()
+            .task("sum", |a: i32, b: i32| async move { a + b })
+            .task("show", |a: i32| async move { a.to_string() })
+            .value("a", 1)
+            .value("b", 2)
+            .value("c", 4);
+        let (mut graph, root) = Graph::from_expr("sum(sum(a, b), c)", &bindings).unwrap();
+        assert_eq!(graph.stats().node_count, 5);
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<i32>(root).unwrap(), 7);
+
+        assert!(matches!(
+            Graph::from_expr("sum(a, d)", &bindings),
+            Err(ExprError::Unbound(name)) if name == "d"
+        ));
+        assert!(matches!(
+            Graph::from_expr("sum(a)", &bindings),
+            Err(ExprError::Arity {
+                expected: 2,
+                found: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            Graph::from_expr("sum(a, show(b))", &bindings),
+            Err(ExprError::Graph(Error::TypeMismatch { .. }))
+        ));
+        assert!(matches!(
+            Graph::from_expr("sum(a b)", &bindings),
+            Err(ExprError::Syntax { offset: 6, .. })
+        ));
+    }
+}
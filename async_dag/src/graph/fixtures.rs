@@ -0,0 +1,96 @@
+use super::DynCurry;
+use super::Edge;
+use crate::any::DynAny;
+use crate::any::TypeInfo;
+use crate::curry::Curry;
+use crate::curry::TaskFuture;
+use crate::tuple::InsertResult;
+use crate::tuple::TakeError;
+use std::convert::Infallible;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+pub(super) use super::runner::yield_now;
+
+/// Tracks how many tasks are running at the same time.
+#[derive(Debug, Clone, Default)]
+pub struct Concurrency {
+    running: Arc<AtomicUsize>,
+    max: Arc<AtomicUsize>,
+}
+
+impl Concurrency {
+    /// Counts as running until a yield is done.
+    pub async fn track(self) {
+        let current = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        let _ = self.max.fetch_max(current, Ordering::SeqCst);
+        yield_now().await;
+        let _ = self.running.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// The maximum number of tasks that were running at the same time.
+    pub fn max(&self) -> usize {
+        self.max.load(Ordering::SeqCst)
+    }
+}
+
+/// Wraps a curry, counting how many times its readiness is checked,
+/// and optionally declaring another input type than the inner curry accepts.
+pub struct Probe<'a> {
+    inner: DynCurry<'a, Infallible>,
+    declared: Option<TypeInfo>,
+    checks: Arc<AtomicUsize>,
+}
+
+impl<'a> Probe<'a> {
+    /// Wraps `inner` as is.
+    pub fn new(inner: DynCurry<'a, Infallible>) -> Self {
+        Self {
+            inner,
+            declared: None,
+            checks: Arc::default(),
+        }
+    }
+
+    /// Declares `declared` as the type of every input.
+    pub fn declaring(self, declared: TypeInfo) -> Self {
+        Self {
+            declared: Some(declared),
+            ..self
+        }
+    }
+
+    /// The number of readiness checks, shared with the probe.
+    pub fn checks(&self) -> Arc<AtomicUsize> {
+        self.checks.clone()
+    }
+}
+
+impl<'a> Curry<'a, Infallible> for Probe<'a> {
+    fn num_inputs(&self) -> Edge {
+        self.inner.num_inputs()
+    }
+
+    fn input_type_info(&self, index: Edge) -> Option<TypeInfo> {
+        let type_info = self.inner.input_type_info(index)?;
+        Some(self.declared.unwrap_or(type_info))
+    }
+
+    fn output_type_info(&self) -> TypeInfo {
+        self.inner.output_type_info()
+    }
+
+    fn ready(&self) -> bool {
+        let _ = self.checks.fetch_add(1, Ordering::SeqCst);
+        self.inner.ready()
+    }
+
+    fn curry(&mut self, index: Edge, value: DynAny) -> InsertResult {
+        self.inner.curry(index, value)
+    }
+
+    fn call(self: Box<Self>) -> Result<TaskFuture<'a, Infallible>, TakeError> {
+        self.inner.call()
+    }
+}
@@ -0,0 +1,198 @@
+use super::NodeIndex;
+use super::TryGraph;
+use crate::any::downcast;
+use crate::any::DynAny;
+use crate::any::TypeInfo;
+use crate::curry::Curry;
+use crate::curry::TaskFuture;
+use crate::tuple::InsertError;
+use crate::tuple::InsertErrorKind;
+use crate::tuple::InsertResult;
+use crate::tuple::TakeError;
+use crate::tuple::TupleIndex;
+use futures::FutureExt;
+use std::collections::HashMap;
+
+/// The output of a node added by [`TryGraph::add_gather`], the upstream values by name.
+#[derive(Clone, Default)]
+pub struct Gathered {
+    values: HashMap<String, DynAny>,
+}
+
+impl Gathered {
+    /// Gets the value named `name`.
+    ///
+    /// Returns [`None`] if there's no such value or its type is not `T`.
+    pub fn get<T: 'static>(&self, name: &str) -> Option<T> {
+        downcast(self.values.get(name)?.clone()).ok()
+    }
+
+    /// Iterates over the names of the values in arbitrary order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(String::as_str)
+    }
+}
+
+impl std::fmt::Debug for Gathered {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Gathered")
+            .field("names", &self.values.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Collects inputs of any types into a [`Gathered`].
+#[derive(Clone)]
+struct Gather {
+    names: Vec<String>,
+    type_infos: Vec<TypeInfo>,
+    values: Vec<Option<DynAny>>,
+}
+
+impl<'a, Err: 'a> Curry<'a, Err> for Gather {
+    fn num_inputs(&self) -> TupleIndex {
+        self.names.len() as TupleIndex
+    }
+
+    fn input_type_info(&self, index: TupleIndex) -> Option<TypeInfo> {
+        self.type_infos.get(index as usize).copied()
+    }
+
+    fn output_type_info(&self) -> TypeInfo {
+        TypeInfo::of::<Gathered>()
+    }
+
+    fn ready(&self) -> bool {
+        self.values.iter().all(Option::is_some)
+    }
+
+    fn curry(&mut self, index: TupleIndex, value: DynAny) -> InsertResult {
+        let kind = match self.type_infos.get(index as usize) {
+            Some(type_info) if value.type_id() == type_info.id() => {
+                self.values[index as usize] = Some(value);
+                return Ok(());
+            }
+            Some(type_info) => InsertErrorKind::TypeMismatch {
+                expected: type_info.id(),
+                expected_name: type_info.name(),
+            },
+            None => InsertErrorKind::OutOfRange,
+        };
+        Err(InsertError {
+            kind,
+            value: value.into_any(),
+        })
+    }
+
+    fn call(self: Box<Self>) -> Result<TaskFuture<'a, Err>, TakeError> {
+        if let Some(index) = self.values.iter().position(Option::is_none) {
+            return Err(TakeError {
+                index: index as TupleIndex,
+            });
+        }
+        let values = self
+            .names
+            .into_iter()
+            .zip(self.values.into_iter().map(Option::unwrap))
+            .collect();
+        let output: DynAny = DynAny::new(Gathered { values });
+        Ok(async move { Ok(output) }.boxed())
+    }
+
+    fn clone_curry(&self) -> Option<Box<dyn Curry<'a, Err> + Send + 'a>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Adds a node depending on every node of `outputs`, outputting their values as a [`Gathered`]
+    /// under the paired names, so a multi output graph ends in a single node to get the value of.
+    ///
+    /// The node's inputs are named after the values, see [`TryGraph::set_input_names`].
+    ///
+    /// **Panics** if any node does not exist within the graph, names are duplicated,
+    /// or there are more outputs than a task can have inputs.
+    pub fn add_gather(&mut self, outputs: &[(NodeIndex, &str)]) -> NodeIndex {
+        assert!(
+            outputs.len() <= TupleIndex::MAX as usize,
+            "Gathering more outputs than a task can have inputs"
+        );
+        let gather = Gather {
+            names: outputs.iter().map(|(_, name)| (*name).to_owned()).collect(),
+            type_infos: outputs
+                .iter()
+                .map(|(node, _)| self.output_type_info(*node))
+                .collect(),
+            values: vec![None; outputs.len()],
+        };
+        let template = <Gather as Curry<'a, Err>>::clone_curry(&gather).unwrap();
+        let node = self.add_curry(Box::new(gather));
+        let _ = self.templates.insert(node, template);
+        self.set_input_names(node, outputs.iter().map(|(_, name)| *name));
+        for ((parent, _), index) in outputs.iter().zip(0..) {
+            // The input types are the parents' output types, and the new node has no children.
+            self.update_dependency(*parent, node, index).unwrap();
+        }
+        node
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Gathered;
+    use crate::Graph;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_gather() {
+        let mut graph = Graph::new();
+        let count = graph.add_task(|| async { 2usize });
+        let text = graph.add_task(|| async { "ab".to_owned() });
+        let gather = graph.add_gather(&[(count, "count"), (text, "text")]);
+        assert_eq!(graph.input_index(gather, "text"), Some(1));
+        block_on(graph.run());
+
+        let gathered = graph.get_value::<Gathered>(gather).unwrap();
+        assert_eq!(gathered.get::<usize>("count"), Some(2));
+        assert_eq!(gathered.get::<String>("text").unwrap(), "ab");
+        assert_eq!(gathered.get::<i32>("count"), None);
+        assert_eq!(gathered.get::<usize>("missing"), None);
+        let mut names = gathered.names().collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["count", "text"]);
+    }
+
+    #[test]
+    fn test_gather_max_inputs() {
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        let names = (0..=u8::MAX).map(|i| i.to_string()).collect::<Vec<_>>();
+        let outputs = names[1..]
+            .iter()
+            .map(|name| (one, name.as_str()))
+            .collect::<Vec<_>>();
+        let gather = graph.add_gather(&outputs);
+        assert_eq!(graph.input_index(gather, "255"), Some(254));
+        block_on(graph.run());
+        assert_eq!(
+            graph
+                .get_value::<Gathered>(gather)
+                .unwrap()
+                .get::<i32>("255"),
+            Some(1)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Gathering more outputs than a task can have inputs")]
+    fn test_gather_too_many_inputs() {
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        let names = (0..=u8::MAX).map(|i| i.to_string()).collect::<Vec<_>>();
+        let outputs = names
+            .iter()
+            .map(|name| (one, name.as_str()))
+            .collect::<Vec<_>>();
+        let _ = graph.add_gather(&outputs);
+    }
+}
@@ -0,0 +1,380 @@
+use super::checkpoint::restore;
+use super::clock::Clock;
+use super::runner::Admission;
+use super::NodeIndex;
+use super::TryGraph;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::time::Duration;
+use std::time::Instant;
+
+/// A token bucket rate limit.
+///
+/// Every started node takes a token, and tokens are refilled continuously.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    burst: f64,
+    per_second: f64,
+}
+
+impl RateLimit {
+    /// Allows `count` starts per `period`, and up to `count` starts at once.
+    ///
+    /// **Panics** if `count` or `period` is zero.
+    pub fn new(count: u32, period: Duration) -> Self {
+        assert!(
+            count > 0 && !period.is_zero(),
+            "Rate limit must be positive"
+        );
+        Self {
+            burst: count.into(),
+            per_second: f64::from(count) / period.as_secs_f64(),
+        }
+    }
+
+    /// Allows `count` starts per second.
+    pub fn per_second(count: u32) -> Self {
+        Self::new(count, Duration::from_secs(1))
+    }
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Puts `node` into group `name`, replacing the previous one if any.
+    ///
+    /// **Panics** if `node` does not exist within the graph.
+    pub fn set_group(&mut self, node: NodeIndex, name: impl Into<String>) {
+        assert!(self.dag.node_weight(node).is_some());
+        let previous = self.schedule.groups.insert(node, name.into());
+        self.record(move |graph| restore(&mut graph.schedule.groups, node, previous));
+    }
+
+    /// Gets the group of `node` if it's put into one.
+    pub fn group(&self, node: NodeIndex) -> Option<&str> {
+        self.schedule.groups.get(&node).map(String::as_str)
+    }
+
+    /// Limits how many nodes in group `name` can run at the same time, replacing the previous limit if any.
+    ///
+    /// Group limits apply on top of lane limits and other constraints.
+    ///
+    /// **Panics** if `limit` is `0`.
+    pub fn set_group_limit(&mut self, name: impl Into<String>, limit: usize) {
+        assert!(limit > 0, "Group limit must be positive");
+        let name = name.into();
+        let previous = self.schedule.group_limits.insert(name.clone(), limit);
+        self.record(move |graph| restore(&mut graph.schedule.group_limits, name, previous));
+    }
+
+    /// Weighs group `name` by `weight` when nodes compete under a [global concurrency limit](super::RunOptions::concurrency_limit),
+    /// replacing the previous weight if any.
+    ///
+    /// Ready nodes are started so each group's running nodes stay proportional to its weight,
+    /// so a huge fan-out can't starve a small group. Groups, and nodes without one, weigh `1` by default.
+    ///
+    /// **Panics** if `weight` is `0`.
+    pub fn set_group_weight(&mut self, name: impl Into<String>, weight: u32) {
+        assert!(weight > 0, "Group weight must be positive");
+        let name = name.into();
+        let previous = self.schedule.group_weights.insert(name.clone(), weight);
+        self.record(move |graph| restore(&mut graph.schedule.group_weights, name, previous));
+    }
+
+    /// Limits how fast nodes in group `name` are started, replacing the previous limit if any.
+    ///
+    /// Ready nodes are delayed until a token is available.
+    pub fn set_rate_limit(&mut self, name: impl Into<String>, limit: RateLimit) {
+        let name = name.into();
+        let previous = self.schedule.rate_limits.insert(name.clone(), limit);
+        self.record(move |graph| restore(&mut graph.schedule.rate_limits, name, previous));
+    }
+}
+
+/// Enforces the global concurrency limit, offering ready nodes in weighted fair order between groups.
+pub(super) struct FairShare<'g> {
+    groups: &'g HashMap<NodeIndex, String>,
+    weights: &'g HashMap<String, u32>,
+    limit: Option<usize>,
+    // Running nodes by group, `None` for nodes without one.
+    running: HashMap<Option<&'g str>, usize>,
+    // Buffers for ordering, reused by every call:
+    // the queue of each group by the order groups first became ready, and the groups with ready nodes left.
+    slots: HashMap<Option<&'g str>, usize>,
+    queues: Vec<VecDeque<usize>>,
+    shares: BinaryHeap<Share>,
+}
+
+/// A group's share of running nodes, greatest when running the least relative to its weight.
+struct Share {
+    running: u64,
+    weight: u64,
+    slot: usize,
+}
+
+impl Ord for Share {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (other.running * self.weight)
+            .cmp(&(self.running * other.weight))
+            // Groups that became ready first go first.
+            .then(other.slot.cmp(&self.slot))
+    }
+}
+
+impl PartialOrd for Share {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Share {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Share {}
+
+impl<'g> FairShare<'g> {
+    pub(super) fn new(
+        groups: &'g HashMap<NodeIndex, String>,
+        weights: &'g HashMap<String, u32>,
+        limit: Option<usize>,
+    ) -> Self {
+        Self {
+            groups,
+            weights,
+            limit,
+            running: HashMap::new(),
+            slots: HashMap::new(),
+            queues: vec![],
+            shares: BinaryHeap::new(),
+        }
+    }
+
+    fn group(&self, node: NodeIndex) -> Option<&'g str> {
+        self.groups.get(&node).map(String::as_str)
+    }
+
+    fn weight(&self, group: Option<&str>) -> u64 {
+        group
+            .and_then(|group| self.weights.get(group))
+            .map_or(1, |weight| (*weight).into())
+    }
+}
+
+impl<'g> Admission for FairShare<'g> {
+    fn admit(&mut self, node: NodeIndex) -> bool {
+        let limit = match self.limit {
+            Some(limit) => limit,
+            None => return true,
+        };
+        if self.running.values().sum::<usize>() >= limit {
+            return false;
+        }
+        *self.running.entry(self.group(node)).or_default() += 1;
+        true
+    }
+
+    fn release(&mut self, node: NodeIndex) {
+        if self.limit.is_some() {
+            *self.running.get_mut(&self.group(node)).unwrap() -= 1;
+        }
+    }
+
+    fn order(&mut self, ready: &[NodeIndex], order: &mut Vec<usize>) -> bool {
+        if self.limit.is_none() {
+            return false;
+        }
+        self.slots.clear();
+        for (position, node) in ready.iter().enumerate() {
+            let group = self.group(*node);
+            let slot = match self.slots.get(&group) {
+                Some(slot) => *slot,
+                None => {
+                    let slot = self.slots.len();
+                    let _ = self.slots.insert(group, slot);
+                    match self.queues.get_mut(slot) {
+                        Some(queue) => queue.clear(),
+                        None => self.queues.push(VecDeque::new()),
+                    }
+                    slot
+                }
+            };
+            self.queues[slot].push_back(position);
+        }
+        self.shares.clear();
+        for (group, slot) in &self.slots {
+            self.shares.push(Share {
+                running: self.running.get(group).copied().unwrap_or_default() as u64,
+                weight: self.weight(*group),
+                slot: *slot,
+            });
+        }
+        // Repeatedly takes from the group running the least relative to its weight.
+        while let Some(mut share) = self.shares.pop() {
+            let queue = &mut self.queues[share.slot];
+            order.push(queue.pop_front().unwrap());
+            if !queue.is_empty() {
+                share.running += 1;
+                self.shares.push(share);
+            }
+        }
+        true
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// Enforces group rate limits.
+pub(super) struct RateLimits<'g> {
+    groups: &'g HashMap<NodeIndex, String>,
+    limits: &'g HashMap<String, RateLimit>,
+    clock: &'g dyn Clock,
+    buckets: HashMap<&'g str, Bucket>,
+    retry_at: Option<Instant>,
+}
+
+impl<'g> RateLimits<'g> {
+    pub(super) fn new(
+        groups: &'g HashMap<NodeIndex, String>,
+        limits: &'g HashMap<String, RateLimit>,
+        clock: &'g dyn Clock,
+    ) -> Self {
+        Self {
+            groups,
+            limits,
+            clock,
+            buckets: HashMap::new(),
+            retry_at: None,
+        }
+    }
+}
+
+impl<'g> Admission for RateLimits<'g> {
+    fn admit(&mut self, node: NodeIndex) -> bool {
+        let (name, limit) = match self
+            .groups
+            .get(&node)
+            .and_then(|group| self.limits.get_key_value(group))
+        {
+            Some(name_and_limit) => name_and_limit,
+            None => return true,
+        };
+        let now = self.clock.now();
+        // Buckets start full and are created lazily, so the clock is untouched without limits.
+        let bucket = self.buckets.entry(name.as_str()).or_insert(Bucket {
+            tokens: limit.burst,
+            refilled_at: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * limit.per_second).min(limit.burst);
+        bucket.refilled_at = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            let wait = Duration::from_secs_f64((1.0 - bucket.tokens) / limit.per_second);
+            let retry_at = now + wait;
+            self.retry_at = Some(self.retry_at.map_or(retry_at, |at| at.min(retry_at)));
+            false
+        }
+    }
+
+    fn release(&mut self, _: NodeIndex) {}
+
+    fn retry_at(&mut self) -> Option<Instant> {
+        self.retry_at.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::fixtures::yield_now;
+    use crate::graph::fixtures::Concurrency;
+    use crate::test_util;
+    use crate::test_util::MockClock;
+    use crate::Graph;
+    use crate::RunOptions;
+    use futures::executor::block_on;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_group_limit() {
+        let downloads = Concurrency::default();
+        let others = Concurrency::default();
+        let mut graph = Graph::new();
+        for _ in 0..4 {
+            let node = graph.add_task(|| downloads.clone().track());
+            graph.set_group(node, "downloads");
+            let _ = graph.add_task(|| others.clone().track());
+        }
+        graph.set_group_limit("downloads", 2);
+        block_on(graph.run());
+        assert_eq!(downloads.max(), 2);
+        assert_eq!(others.max(), 4);
+    }
+
+    #[test]
+    fn test_group_weight() {
+        let started = Arc::new(Mutex::new(vec![]));
+        let mut graph = Graph::new();
+        let mut add = |group: &str| {
+            let started = started.clone();
+            let group = group.to_owned();
+            let node = graph.add_task({
+                let group = group.clone();
+                move || async move {
+                    started.lock().unwrap().push(group);
+                    yield_now().await;
+                }
+            });
+            graph.set_group(node, group);
+        };
+        for _ in 0..8 {
+            add("bulk");
+        }
+        for _ in 0..3 {
+            add("interactive");
+        }
+        graph.set_group_weight("interactive", 2);
+        block_on(graph.run_with(&RunOptions::new().concurrency_limit(3)));
+        let started = started.lock().unwrap();
+        // Two of the three slots go to the interactive group, despite it becoming ready last.
+        assert_eq!(
+            started[..3]
+                .iter()
+                .filter(|group| *group == "interactive")
+                .count(),
+            2
+        );
+        assert_eq!(started.len(), 11);
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let clock = MockClock::new();
+        let mut graph = Graph::new();
+        for _ in 0..3 {
+            let clock = clock.clone();
+            let node = graph.add_task(move || async move { clock.elapsed() });
+            graph.set_group(node, "api_calls");
+        }
+        graph.set_rate_limit("api_calls", RateLimit::new(1, Duration::from_millis(20)));
+        test_util::block_on(
+            &clock,
+            graph.run_with(&RunOptions::new().clock(clock.clone())),
+        );
+        let mut started = graph
+            .into_typed_values::<Duration>()
+            .into_values()
+            .collect::<Vec<_>>();
+        started.sort();
+        assert_eq!(started, [0, 20, 40].map(Duration::from_millis));
+    }
+}
@@ -0,0 +1,237 @@
+use super::NodeIndex;
+use futures::channel::oneshot;
+use futures::future::poll_fn;
+use futures::future::BoxFuture;
+use futures::future::Shared as SharedFuture;
+use futures::task::AtomicWaker;
+use futures::FutureExt;
+use std::future::Future;
+use std::mem::take;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Poll;
+
+#[derive(Debug, Default)]
+struct Shared {
+    cancelled: Mutex<Vec<NodeIndex>>,
+    waker: AtomicWaker,
+    shutdown: Mutex<Shutdown>,
+}
+
+#[derive(Debug)]
+struct Shutdown {
+    // Dropped on shutdown, completing `receiver`.
+    sender: Option<oneshot::Sender<()>>,
+    receiver: SharedFuture<oneshot::Receiver<()>>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        let (sender, receiver) = oneshot::channel();
+        Self {
+            sender: Some(sender),
+            receiver: receiver.shared(),
+        }
+    }
+}
+
+/// A handle to control a run from elsewhere, e.g. from within a task.
+///
+/// Pass a clone to [`RunOptions::handle`](super::RunOptions::handle).
+#[derive(Debug, Clone, Default)]
+pub struct RunHandle {
+    shared: Arc<Shared>,
+}
+
+impl RunHandle {
+    /// Creates a handle not attached to any run yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prevents `node` and its descendants from starting, and drops them if they're running.
+    ///
+    /// They are set to [`Node::Skipped`](super::Node::Skipped), leaving the rest of the run untouched.
+    /// Completed nodes keep their values. Cancellations the run didn't get to before ending are forgotten.
+    pub fn cancel_subtree(&self, node: NodeIndex) {
+        self.shared.cancelled.lock().unwrap().push(node);
+        self.shared.waker.wake();
+    }
+
+    /// Stops the run gracefully: no more nodes start, and running nodes are given
+    /// [`RunOptions::grace_period`](super::RunOptions::grace_period) to complete before they're dropped.
+    ///
+    /// Tasks can opt in to wind down early, e.g. to release external resources,
+    /// by watching [`RunHandle::is_shutdown`] or awaiting [`RunHandle::on_shutdown`].
+    /// Nodes not completed are set to [`Node::Skipped`](super::Node::Skipped), and the run returns [`Ok`].
+    ///
+    /// Dropping the run's future instead still drops running tasks immediately,
+    /// as waiting for them needs the future to be polled.
+    ///
+    /// The handle is re-armed for later runs once the run ends.
+    pub fn shutdown(&self) {
+        drop(self.shared.shutdown.lock().unwrap().sender.take());
+    }
+
+    /// Returns `true` if [`RunHandle::shutdown`] has been called and the handle not re-armed since.
+    pub fn is_shutdown(&self) -> bool {
+        self.shared.shutdown.lock().unwrap().sender.is_none()
+    }
+
+    /// Completes when [`RunHandle::shutdown`] is called.
+    pub fn on_shutdown(&self) -> impl Future<Output = ()> + Send + 'static {
+        self.shared
+            .shutdown
+            .lock()
+            .unwrap()
+            .receiver
+            .clone()
+            .map(|_| ())
+    }
+
+    /// Re-arms the handle after a shut down run ended, and forgets cancellations the run didn't get to.
+    pub(super) fn finish_run(&self) {
+        self.shared.cancelled.lock().unwrap().clear();
+        let mut shutdown = self.shared.shutdown.lock().unwrap();
+        if shutdown.sender.is_none() {
+            *shutdown = Shutdown::default();
+        }
+    }
+
+    /// Completes with the nodes cancelled since last time.
+    pub(super) fn cancelled(&self) -> BoxFuture<'static, Vec<NodeIndex>> {
+        let shared = self.shared.clone();
+        poll_fn(move |cx| {
+            shared.waker.register(cx.waker());
+            let cancelled = take(&mut *shared.cancelled.lock().unwrap());
+            if cancelled.is_empty() {
+                Poll::Pending
+            } else {
+                Poll::Ready(cancelled)
+            }
+        })
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::fixtures::yield_now;
+    use crate::Graph;
+    use crate::Node;
+    use crate::RunHandle;
+    use crate::RunOptions;
+    use futures::executor::block_on;
+    use futures::future::pending;
+    use std::time::Duration;
+
+    #[test]
+    fn test_cancel_subtree() {
+        let handle = RunHandle::new();
+        let mut graph = Graph::new();
+        let stuck = graph.add_task(pending::<i32>);
+        let child = graph
+            .add_child_task(stuck, |v: i32| async move { v }, 0)
+            .unwrap();
+        let other = graph.add_task({
+            let handle = handle.clone();
+            move || async move {
+                yield_now().await;
+                handle.cancel_subtree(stuck);
+                1
+            }
+        });
+        let sibling = graph
+            .add_child_task(other, |v: i32| async move { v + 1 }, 0)
+            .unwrap();
+
+        block_on(graph.run_with(&RunOptions::new().handle(handle)));
+        assert!(
+            matches!(graph.as_dag()[stuck].node, Node::Skipped { cause, .. } if cause == stuck)
+        );
+        assert!(
+            matches!(graph.as_dag()[child].node, Node::Skipped { cause, .. } if cause == stuck)
+        );
+        assert_eq!(graph.get_value::<i32>(sibling).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_cancellations_cleared() {
+        let handle = RunHandle::new();
+        let options = RunOptions::new().handle(handle.clone());
+        let mut graph = Graph::new();
+        let _ = graph.add_task({
+            let handle = handle.clone();
+            move || async move { handle.cancel_subtree(0.into()) }
+        });
+        block_on(graph.run_with(&options));
+
+        let mut graph = Graph::new();
+        let node = graph.add_task(|| async {
+            yield_now().await;
+            1
+        });
+        block_on(graph.run_with(&options));
+        assert_eq!(graph.get_value::<i32>(node).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_shutdown() {
+        let handle = RunHandle::new();
+        let mut graph = Graph::new();
+        // Winds down on shutdown, completing after the runner notices it.
+        let cooperative = graph.add_task({
+            let handle = handle.clone();
+            move || async move {
+                handle.on_shutdown().await;
+                yield_now().await;
+                1
+            }
+        });
+        let stuck = graph.add_task(pending::<i32>);
+        let trigger = graph.add_task({
+            let handle = handle.clone();
+            move || async move {
+                yield_now().await;
+                handle.shutdown();
+            }
+        });
+        let after = graph
+            .add_child_task(cooperative, |v: i32| async move { v }, 0)
+            .unwrap();
+
+        let options = RunOptions::new()
+            .handle(handle.clone())
+            .grace_period(Duration::from_millis(10));
+        block_on(graph.run_with(&options));
+        assert!(!handle.is_shutdown());
+        assert_eq!(graph.get_value::<i32>(cooperative).unwrap(), 1);
+        assert!(matches!(graph.as_dag()[trigger].node, Node::Value { .. }));
+        assert!(
+            matches!(graph.as_dag()[stuck].node, Node::Skipped { cause, .. } if cause == stuck)
+        );
+        assert!(
+            matches!(graph.as_dag()[after].node, Node::Skipped { cause, .. } if cause == after)
+        );
+    }
+
+    #[test]
+    fn test_shutdown_rearmed() {
+        let handle = RunHandle::new();
+        let options = RunOptions::new().handle(handle.clone());
+        let mut graph = Graph::new();
+        let _ = graph.add_task({
+            let handle = handle.clone();
+            move || async move { handle.shutdown() }
+        });
+        let stuck = graph.add_task(pending::<i32>);
+        block_on(graph.run_with(&options));
+        assert!(matches!(graph.as_dag()[stuck].node, Node::Skipped { .. }));
+        assert!(!handle.is_shutdown());
+
+        let mut graph = Graph::new();
+        let node = graph.add_task(|| async { 1 });
+        block_on(graph.run_with(&options));
+        assert_eq!(graph.get_value::<i32>(node).unwrap(), 1);
+    }
+}
@@ -0,0 +1,89 @@
+#[cfg(not(any(feature = "small-index", feature = "large-index")))]
+pub type Index = u32;
+
+// Features are additive, so `large-index` wins when another crate in the build enables it.
+#[cfg(all(feature = "small-index", not(feature = "large-index")))]
+pub type Index = u16;
+
+#[cfg(feature = "large-index")]
+pub type Index = usize;
+
+#[cfg(test)]
+mod tests {
+    use crate::NodeIndex;
+
+    #[test]
+    #[cfg(not(any(feature = "small-index", feature = "large-index")))]
+    fn test_default_index() {
+        assert_eq!(size_of::<NodeIndex>(), 4);
+    }
+
+    #[test]
+    #[cfg(feature = "large-index")]
+    fn test_large_index() {
+        use crate::Graph;
+
+        assert_eq!(size_of::<NodeIndex>(), size_of::<usize>());
+        let mut graph = Graph::new();
+        let nodes = graph.extend_tasks((0..=u16::MAX).map(|_| || async { 1 }));
+        let last = graph
+            .add_child_task(nodes[0], |v: i32| async move { v }, 0)
+            .unwrap();
+        assert_eq!(last.index(), usize::from(u16::MAX) + 1);
+    }
+
+    #[test]
+    #[cfg(all(feature = "small-index", not(feature = "large-index")))]
+    fn test_small_index_overflow() {
+        use crate::error::BuildError;
+        use crate::error::Error;
+        use crate::Graph;
+        use crate::GraphBuilder;
+
+        assert_eq!(size_of::<NodeIndex>(), 2);
+        // Indices up to `u16::MAX` exclusive address as many nodes.
+        let mut graph = Graph::new();
+        let nodes = graph.extend_tasks((0..u16::MAX).map(|_| || async { 1 }));
+        let error = graph
+            .add_child_task(nodes[0], |v: i32| async move { v }, 0)
+            .unwrap_err();
+        assert!(matches!(error.error, Error::IndexOverflow));
+        let error = graph
+            .add_parent_task(|| async { 1 }, nodes[0], 0)
+            .unwrap_err();
+        assert!(!matches!(error.error, Error::IndexOverflow));
+
+        let mut builder = GraphBuilder::new(1);
+        for _ in 0..=u16::MAX {
+            let _ = builder.shards_mut()[0].add_task(|| async { 1 });
+        }
+        assert!(matches!(
+            builder.build().unwrap_err(),
+            BuildError::Graph(Error::IndexOverflow)
+        ));
+    }
+
+    #[test]
+    #[cfg(all(feature = "small-index", not(feature = "large-index")))]
+    fn test_small_index_edge_overflow() {
+        use crate::error::Error;
+        use crate::Graph;
+
+        let mut graph = Graph::new();
+        let source = graph.add_task(|| async { 1 });
+        let sums =
+            graph.extend_tasks((0..=u16::MAX / 2).map(|_| |a: i32, b: i32| async move { a + b }));
+        let mut inputs = sums.iter().flat_map(|&sum| [(sum, 0), (sum, 1)]);
+        let (last, index) = inputs.next_back().unwrap();
+        graph
+            .add_edges(inputs.map(|(sum, index)| (source, sum, index)))
+            .unwrap();
+        assert_eq!(graph.as_dag().edge_count(), usize::from(u16::MAX));
+        assert!(matches!(
+            graph.update_dependency(source, last, index).unwrap_err(),
+            Error::IndexOverflow
+        ));
+        // Replacing a dependency doesn't add any.
+        graph.update_dependency(source, sums[0], 0).unwrap();
+    }
+}
@@ -1,15 +1,26 @@
 use super::Edge;
 use super::NodeIndex;
+use super::OutputFuture;
+use super::RunOptions;
 use super::TryGraph;
+use super::Update;
 use crate::any::IntoAny;
 use crate::error::ErrorWithTask;
 use crate::task::IntoInfallibleTask;
+use crate::task::IntoSyncInfallibleTask;
+use crate::task::TryTask;
+use crate::tuple::In;
+use crate::tuple::InputIndex;
+use crate::tuple::Tuple;
+use crate::tuple::TupleIndex;
+use futures::Stream;
 use std::convert::Infallible;
+use std::future::Future;
 
 /// A [`TryGraph`] with infallible tasks.
 pub type Graph<'a> = TryGraph<'a, Infallible>;
 
-impl<'a> Graph<'a> {
+impl<'a, D> TryGraph<'a, Infallible, D> {
     /// Adds an infallible task. See [`TryGraph::add_try_task`].
     pub fn add_task<Args, Ok, T: IntoInfallibleTask<'a, Args, Ok>>(
         &mut self,
@@ -18,6 +29,44 @@ impl<'a> Graph<'a> {
         self.add_task_impl(task.into_task())
     }
 
+    /// Adds an infallible synchronous task. See [`TryGraph::add_sync_try_task`].
+    pub fn add_sync_task<Args, Ok, T: IntoSyncInfallibleTask<'a, Args, Ok>>(
+        &mut self,
+        task: T,
+    ) -> NodeIndex {
+        self.add_task_impl(task.into_task())
+    }
+
+    /// Adds an infallible task, also returning a future of its output value.
+    ///
+    /// See [`TryGraph::add_try_task_with_output`].
+    pub fn add_task_with_output<Args, Ok, T: IntoInfallibleTask<'a, Args, Ok>>(
+        &mut self,
+        task: T,
+    ) -> (NodeIndex, OutputFuture<Ok>) {
+        self.add_task_with_output_impl(task.into_task())
+    }
+
+    /// Adds an existing infallible `future` as a node. See [`TryGraph::add_try_future`].
+    pub fn add_future<Ok: IntoAny>(
+        &mut self,
+        future: impl Future<Output = Ok> + Send + 'a,
+    ) -> NodeIndex {
+        self.add_task(move || future)
+    }
+
+    /// Adds an infallible task which is cloned along with the graph.
+    ///
+    /// See [`TryGraph::add_cloneable_try_task`].
+    pub fn add_cloneable_task<Args, Ok, T>(&mut self, task: T) -> NodeIndex
+    where
+        T: IntoInfallibleTask<'a, Args, Ok>,
+        T::Task: Clone,
+        <<T::Task as TryTask<'a>>::Inputs as Tuple>::Option: Clone,
+    {
+        self.add_cloneable_task_impl(task.into_task())
+    }
+
     /// Adds an infallible task and set it as `child`'s dependency at `index`.
     ///
     /// See [`TryGraph::add_parent_try_task`].
@@ -39,11 +88,71 @@ impl<'a> Graph<'a> {
         task: T,
         index: Edge,
     ) -> Result<NodeIndex, ErrorWithTask<T::Task>> {
-        self.add_child_task_impl::<Ok, _>(parent, task.into_task(), index)
+        self.add_child_task_impl(parent, task.into_task(), index)
     }
 
-    /// Infallible version of [`TryGraph::run`].
+    /// Adds an infallible task and set it as `child`'s dependency at `index`.
+    ///
+    /// See [`TryGraph::add_parent_try_task_at`].
+    pub fn add_parent_task_at<
+        Args,
+        Ok: IntoAny,
+        T: IntoInfallibleTask<'a, Args, Ok>,
+        const I: TupleIndex,
+    >(
+        &mut self,
+        task: T,
+        child: NodeIndex,
+        index: In<I>,
+    ) -> Result<NodeIndex, ErrorWithTask<T::Task>> {
+        self.add_parent_task_impl::<Ok, _>(task.into_task(), child, index.into())
+    }
+
+    /// Adds an infallible task and set it's dependency at `index` to `parent`.
+    ///
+    /// See [`TryGraph::add_child_try_task_at`].
+    pub fn add_child_task_at<Args, Ok, T, const I: TupleIndex>(
+        &mut self,
+        parent: NodeIndex,
+        task: T,
+        _index: In<I>,
+    ) -> Result<NodeIndex, ErrorWithTask<T::Task>>
+    where
+        T: IntoInfallibleTask<'a, Args, Ok>,
+        In<I>: InputIndex<<T::Task as TryTask<'a>>::Inputs>,
+    {
+        self.add_child_task_impl(parent, task.into_task(), <In<I> as InputIndex<_>>::INDEX)
+    }
+
+    /// Adds an infallible task whose first input is the context.
+    ///
+    /// **Panics** if the context is not set.
+    ///
+    /// See [`TryGraph::add_context_try_task`].
+    pub fn add_context_task<Args, Ok, T: IntoInfallibleTask<'a, Args, Ok>>(
+        &mut self,
+        task: T,
+    ) -> Result<NodeIndex, ErrorWithTask<T::Task>> {
+        let context = self.context.expect("Context is not set");
+        self.add_child_task_impl(context, task.into_task(), 0)
+    }
+
+    /// Infallible version of [`TryGraph::try_run`].
     pub async fn run(&mut self) {
         self.try_run().await.unwrap();
     }
+
+    /// Infallible version of [`TryGraph::try_watch`].
+    pub async fn watch(
+        &mut self,
+        updates: impl Stream<Item = Update>,
+        on_change: impl FnMut(&Self),
+    ) {
+        self.try_watch(updates, on_change).await.unwrap();
+    }
+
+    /// Infallible version of [`TryGraph::try_run_with`].
+    pub async fn run_with(&mut self, options: &RunOptions<'a>) {
+        self.try_run_with(options).await.unwrap();
+    }
 }
@@ -0,0 +1,223 @@
+use super::checkpoint::restore;
+use super::error::Error;
+use super::Edge;
+use super::Node;
+use super::NodeIndex;
+use super::TryGraph;
+use crate::any::DynAny;
+use crate::any::IntoAny;
+use crate::any::TypeInfo;
+use crate::task::FieldsTask;
+use crate::task::StructTask;
+use crate::tuple::TupleIndex;
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Adds a [`FieldsTask`] without specifying its dependencies, naming its inputs after the fields.
+    pub fn add_struct_task<T: FieldsTask<Err = Err>>(&mut self) -> NodeIndex {
+        let node = self.add_try_task(StructTask::<T>::new());
+        self.set_input_names(node, T::INPUT_NAMES.iter().copied());
+        node
+    }
+
+    /// Names the inputs of `node`'s task, in order, replacing the previous names if any.
+    ///
+    /// Named inputs can be wired by [`TryGraph::update_dependency_named`],
+    /// which keeps working when task signatures are reordered.
+    ///
+    /// **Panics** if `node` does not exist within the graph or has started running.
+    ///
+    /// **Panics** if the number of names differs from the number of inputs, or names are duplicated.
+    pub fn set_input_names<S: Into<String>>(
+        &mut self,
+        node: NodeIndex,
+        names: impl IntoIterator<Item = S>,
+    ) {
+        let num_inputs = match &self.dag[node].node {
+            Node::Curry(curry) => curry.num_inputs(),
+            _ => panic!("Node {} has started running", node.index()),
+        };
+        let names = names.into_iter().map(Into::into).collect::<Vec<String>>();
+        assert_eq!(
+            names.len(),
+            num_inputs as usize,
+            "Expecting a name for every input"
+        );
+        for (index, name) in names.iter().enumerate() {
+            assert!(
+                !names[..index].contains(name),
+                "Input name {} is duplicated",
+                name
+            );
+        }
+        let previous = self.input_names.insert(node, names);
+        self.record(move |graph| restore(&mut graph.input_names, node, previous));
+    }
+
+    /// Gets the index of `node`'s input named `name`.
+    pub fn input_index(&self, node: NodeIndex, name: &str) -> Option<Edge> {
+        self.input_names
+            .get(&node)?
+            .iter()
+            .position(|input| input == name)
+            .map(|index| index as Edge)
+    }
+
+    /// Sets `parent` as `child`'s dependency at the input named `name`.
+    ///
+    /// Returns [`Error::NoSuchInput`] if `child` has no such input,
+    /// otherwise the same as [`TryGraph::update_dependency`].
+    pub fn update_dependency_named(
+        &mut self,
+        parent: NodeIndex,
+        child: NodeIndex,
+        name: &str,
+    ) -> Result<(), Error> {
+        let index = self
+            .input_index(child, name)
+            .ok_or_else(|| Error::NoSuchInput(name.to_owned()))?;
+        self.update_dependency(parent, child, index)
+    }
+
+    /// Gives `value` to `node`'s input at `index` directly, without a parent node,
+    /// e.g. for tests or callers to satisfy inputs of partially wired graphs.
+    ///
+    /// If `node` has a dependency at `index`, it will be removed, so the value isn't overwritten when running.
+    /// Returns an error if `node` has started running or the input type at `index` isn't `T`.
+    ///
+    /// **Panics** if `node` does not exist within the graph.
+    pub fn set_input<T: IntoAny>(
+        &mut self,
+        node: NodeIndex,
+        index: Edge,
+        value: T,
+    ) -> Result<(), Error> {
+        self.type_check(None, node, index, TypeInfo::of::<T>())?;
+        let _ = self.remove_dependency(node, index);
+        let value: DynAny = DynAny::new(value);
+        // Cloneable nodes are reset to their templates by `TryGraph::update_value`.
+        if let Some(template) = self.templates.get_mut(&node) {
+            template
+                .curry(index, value.clone())
+                .expect("Input type has been checked");
+        }
+        match &mut self.dag[node].node {
+            Node::Curry(curry) => curry
+                .curry(index, value)
+                .expect("Input type has been checked"),
+            _ => unreachable!("Node has been checked"),
+        }
+        let _ = self.given_inputs.insert((node, index));
+        Ok(())
+    }
+
+    /// Lists `node`'s inputs which have neither a dependency nor a value given by [`TryGraph::set_input`],
+    /// by index with their types, so they can be checked before running.
+    ///
+    /// Empty if `node` has started running.
+    ///
+    /// **Panics** if `node` does not exist within the graph.
+    pub fn missing_inputs(&self, node: NodeIndex) -> Vec<(TupleIndex, TypeInfo)> {
+        let curry = match &self.dag[node].node {
+            Node::Curry(curry) => curry,
+            _ => return vec![],
+        };
+        (0..curry.num_inputs())
+            .filter(|index| {
+                !self.dependencies.contains_key(&(node, *index))
+                    && !self.given_inputs.contains(&(node, *index))
+            })
+            .map(|index| (index, curry.input_type_info(index).unwrap()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::any::TypeInfo;
+    use crate::error::Error;
+    use crate::Graph;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_update_dependency_named() {
+        let mut graph = Graph::new();
+        let root = graph.add_task(|lhs: i32, rhs: i32| async move { lhs - rhs });
+        graph.set_input_names(root, ["lhs", "rhs"]);
+        let three = graph.add_task(|| async { 3 });
+        let one = graph.add_task(|| async { 1 });
+        graph.update_dependency_named(three, root, "lhs").unwrap();
+        graph.update_dependency_named(one, root, "rhs").unwrap();
+        assert!(matches!(
+            graph.update_dependency_named(one, root, "other"),
+            Err(Error::NoSuchInput(name)) if name == "other"
+        ));
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<i32>(root).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_set_input() {
+        let mut graph = Graph::new();
+        let root = graph.add_task(|lhs: i32, rhs: i32| async move { lhs - rhs });
+        let one = graph.add_task(|| async { 1 });
+        graph.update_dependency(one, root, 1).unwrap();
+        graph.set_input(root, 0, 3).unwrap();
+        graph.set_input(root, 1, 2).unwrap();
+        assert!(matches!(
+            graph.set_input(root, 0, "3"),
+            Err(Error::TypeMismatch { index: 0, .. })
+        ));
+        assert!(matches!(
+            graph.set_input(root, 2, 3),
+            Err(Error::OutOfRange(2))
+        ));
+        assert!(graph.dependencies(root).is_empty());
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<i32>(root).unwrap(), 1);
+        assert!(matches!(
+            graph.set_input(root, 0, 3),
+            Err(Error::HasStarted(node)) if node == root
+        ));
+    }
+
+    #[test]
+    fn test_missing_inputs() {
+        let mut graph = Graph::new();
+        let root = graph.add_task(|a: i32, b: String, c: bool| async move { (a, b, c) });
+        let one = graph.add_task(|| async { 1 });
+        assert_eq!(graph.missing_inputs(one), []);
+        graph.update_dependency(one, root, 0).unwrap();
+        graph.set_input(root, 2, true).unwrap();
+        assert_eq!(graph.missing_inputs(root), [(1, TypeInfo::of::<String>())]);
+        graph.set_input(root, 1, "b".to_owned()).unwrap();
+        assert_eq!(graph.missing_inputs(root), []);
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_struct_task() {
+        use crate::Task;
+
+        #[derive(Task)]
+        #[task(ok = String)]
+        struct Repeat {
+            text: String,
+            times: usize,
+        }
+
+        impl Repeat {
+            async fn run(self) -> Result<String, std::convert::Infallible> {
+                Ok(self.text.repeat(self.times))
+            }
+        }
+
+        let mut graph = Graph::new();
+        let root = graph.add_struct_task::<Repeat>();
+        let times = graph.add_task(|| async { 2usize });
+        let text = graph.add_task(|| async { "ab".to_owned() });
+        graph.update_dependency_named(times, root, "times").unwrap();
+        graph.update_dependency_named(text, root, "text").unwrap();
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<String>(root).unwrap(), "abab");
+    }
+}
@@ -0,0 +1,273 @@
+use super::dot::DotGraph;
+use super::render::State;
+use super::states::NodeStates;
+use super::NodeIndex;
+use super::TryGraph;
+use crate::curry::TaskFuture;
+use futures::FutureExt;
+use std::fmt::Write as _;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write as _;
+use std::net::SocketAddr;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::net::ToSocketAddrs;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+// How long a client may take to send a request or receive a response.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Escapes `text` within a quoted JSON string.
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", u32::from(c));
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[derive(Debug, Default)]
+struct Snapshot {
+    // Counts published snapshots, so nodes of earlier runs are not tracked into later ones.
+    run: u64,
+    graph: DotGraph,
+    states: NodeStates,
+}
+
+impl Snapshot {
+    fn json(&self) -> String {
+        let nodes = (0..self.states.len())
+            .map(|index| {
+                let name = match self.states.name(index) {
+                    Some(name) => format!("\"{}\"", escape(name)),
+                    None => "null".to_owned(),
+                };
+                format!(
+                    "{{\"index\":{},\"name\":{},\"state\":\"{}\"}}",
+                    index,
+                    name,
+                    self.states.get(index).name()
+                )
+            })
+            .collect::<Vec<_>>();
+        format!("{{\"nodes\":[{}]}}", nodes.join(","))
+    }
+}
+
+/// An embedded HTTP server showing the state of runs with [`RunOptions::inspector`](super::RunOptions::inspector).
+///
+/// Serves `GET /` as a plain text progress summary, `GET /dot` as [`TryGraph::to_dot`] and `GET /json` as
+/// `{"nodes":[{"index":0,"name":"load","state":"running"}]}`, all reflecting the latest run.
+/// Requests are handled one by one on a dedicated thread, which stops when the inspector is dropped.
+#[derive(Debug)]
+pub struct Inspector {
+    snapshot: Arc<Mutex<Snapshot>>,
+    address: SocketAddr,
+    stopped: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Inspector {
+    /// Starts serving on `address`, e.g. `"127.0.0.1:0"` for any free port.
+    pub fn bind(address: impl ToSocketAddrs) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        let address = listener.local_addr()?;
+        let snapshot = Arc::new(Mutex::new(Snapshot::default()));
+        let stopped = Arc::new(AtomicBool::new(false));
+        let thread = std::thread::spawn({
+            let snapshot = snapshot.clone();
+            let stopped = stopped.clone();
+            move || {
+                for stream in listener.incoming() {
+                    if stopped.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        let _ = serve(stream, &snapshot);
+                    }
+                }
+            }
+        });
+        Ok(Self {
+            snapshot,
+            address,
+            stopped,
+            thread: Some(thread),
+        })
+    }
+
+    /// The address being served on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Shows the current states of `graph`, returning the run to [track](Inspector::track) nodes into.
+    pub(super) fn publish<'a, Err: 'a, D>(&self, graph: &TryGraph<'a, Err, D>) -> u64 {
+        let mut snapshot = self.snapshot.lock().unwrap();
+        let run = snapshot.run + 1;
+        *snapshot = Snapshot {
+            run,
+            graph: graph.dot_graph(),
+            states: NodeStates::new(graph),
+        };
+        run
+    }
+
+    /// Marks `node` running from when `future` is first polled until it completes,
+    /// unless another run has been published since `run`.
+    pub(super) fn track<'a, Err: 'a>(
+        &self,
+        run: u64,
+        node: NodeIndex,
+        future: TaskFuture<'a, Err>,
+    ) -> TaskFuture<'a, Err> {
+        let snapshot = self.snapshot.clone();
+        let set = move |state| {
+            let mut snapshot = snapshot.lock().unwrap();
+            if snapshot.run == run {
+                snapshot.states.set(node, state);
+            }
+        };
+        async move {
+            set(State::Running);
+            let result = future.await;
+            set(if result.is_ok() {
+                State::Value
+            } else {
+                State::Failed
+            });
+            result
+        }
+        .boxed()
+    }
+}
+
+impl Drop for Inspector {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        // Wakes the server thread up from accepting.
+        let _ = TcpStream::connect(self.address);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn serve(mut stream: TcpStream, snapshot: &Mutex<Snapshot>) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let mut request = String::new();
+    let _ = reader.read_line(&mut request)?;
+    // Skips the headers.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let path = request.split_whitespace().nth(1).unwrap_or_default();
+    let (status, content_type, body) = {
+        let snapshot = snapshot.lock().unwrap();
+        match path {
+            "/" => ("200 OK", "text/plain", snapshot.states.progress()),
+            "/dot" => (
+                "200 OK",
+                "text/vnd.graphviz",
+                snapshot
+                    .graph
+                    .render(|index| (snapshot.states.get(index), None)),
+            ),
+            "/json" => ("200 OK", "application/json", snapshot.json()),
+            _ => ("404 Not Found", "text/plain", "Not found\n".to_owned()),
+        }
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Inspector;
+    use crate::any::DynAny;
+    use crate::curry::TaskFuture;
+    use crate::Graph;
+    use crate::RunOptions;
+    use futures::executor::block_on;
+    use std::io::Read;
+    use std::io::Write;
+    use std::net::SocketAddr;
+    use std::net::TcpStream;
+    use std::sync::Arc;
+
+    fn get(address: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+        let mut response = String::new();
+        let _ = stream.read_to_string(&mut response).unwrap();
+        response.split_once("\r\n\r\n").unwrap().1.to_owned()
+    }
+
+    #[test]
+    fn test_inspector() {
+        let inspector = Arc::new(Inspector::bind("127.0.0.1:0").unwrap());
+        let address = inspector.local_addr();
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        let probe = graph
+            .add_child_task(one, move |_: i32| async move { get(address, "/") }, 0)
+            .unwrap();
+        graph.set_name(probe, "probe");
+        block_on(graph.run_with(&RunOptions::new().inspector(inspector.clone())));
+
+        assert_eq!(
+            graph.get_value::<String>(probe).unwrap(),
+            "1/2 completed, 1 running, 0 failed, 0 skipped\nrunning: 1 'probe'\n"
+        );
+        assert_eq!(
+            get(address, "/json"),
+            r#"{"nodes":[{"index":0,"name":null,"state":"value"},{"index":1,"name":"probe","state":"value"}]}"#
+        );
+        assert!(get(address, "/dot").contains("fillcolor=palegreen"));
+        assert_eq!(get(address, "/missing"), "Not found\n");
+    }
+
+    #[test]
+    fn test_inspector_shared() {
+        let inspector = Inspector::bind("127.0.0.1:0").unwrap();
+        let mut large = Graph::new();
+        let _ = large.add_task(|| async {});
+        let last = large.add_task(|| async {});
+        let mut small = Graph::new();
+        let _ = small.add_task(|| async {});
+
+        let run = inspector.publish(&large);
+        let _ = inspector.publish(&small);
+        // The earlier run's node doesn't exist in the latest snapshot, and is ignored.
+        let output: DynAny = DynAny::new(());
+        let future: TaskFuture<'_, ()> = Box::pin(async { Ok(output) });
+        let _ = block_on(inspector.track(run, last, future)).unwrap();
+        assert_eq!(
+            get(inspector.local_addr(), "/"),
+            "0/1 completed, 0 running, 0 failed, 0 skipped\n"
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use super::error::Error;
+use super::NodeIndex;
+use super::TryGraph;
+use crate::task::IntoInfallibleTask;
+use crate::task::IntoTryTask;
+use crate::task::TryTask;
+use std::convert::Infallible;
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Adds a task with `parents` as its dependencies in input order,
+    /// unless a task with the same `key` and `parents` has been added by this method,
+    /// in which case that node is returned and `task` is dropped.
+    ///
+    /// `key` identifies what the task computes, so identical subexpressions in generated graphs are computed once.
+    /// Interned nodes shouldn't be rewired afterwards.
+    ///
+    /// Nothing is added if setting any dependency fails.
+    pub fn add_interned_try_task<Args, Ok, T: IntoTryTask<'a, Args, Ok, Err>>(
+        &mut self,
+        key: impl Into<String>,
+        task: T,
+        parents: &[NodeIndex],
+    ) -> Result<NodeIndex, Error> {
+        self.add_interned_task_impl(key.into(), task.into_task(), parents)
+    }
+
+    fn add_interned_task_impl<T: TryTask<'a, Err = Err> + 'a>(
+        &mut self,
+        key: String,
+        task: T,
+        parents: &[NodeIndex],
+    ) -> Result<NodeIndex, Error> {
+        let key = (key, parents.to_vec());
+        if let Some(node) = self.interned.get(&key) {
+            return Ok(*node);
+        }
+        let node = self.batch(|graph| {
+            let node = graph.add_task_impl(task);
+            for (index, parent) in (0..).zip(parents) {
+                graph.update_dependency(*parent, node, index)?;
+            }
+            Ok(node)
+        })?;
+        let _ = self.interned.insert(key, node);
+        Ok(node)
+    }
+}
+
+impl<'a, D> TryGraph<'a, Infallible, D> {
+    /// Adds an infallible task unless an identical one exists.
+    ///
+    /// See [`TryGraph::add_interned_try_task`].
+    pub fn add_interned_task<Args, Ok, T: IntoInfallibleTask<'a, Args, Ok>>(
+        &mut self,
+        key: impl Into<String>,
+        task: T,
+        parents: &[NodeIndex],
+    ) -> Result<NodeIndex, Error> {
+        self.add_interned_task_impl(key.into(), task.into_task(), parents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Graph;
+    use futures::executor::block_on;
+
+    async fn sum(lhs: i32, rhs: i32) -> i32 {
+        lhs + rhs
+    }
+
+    #[test]
+    fn test_interned() {
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        let two = graph.add_task(|| async { 2 });
+        let first = graph.add_interned_task("sum", sum, &[one, two]).unwrap();
+        let second = graph.add_interned_task("sum", sum, &[one, two]).unwrap();
+        let swapped = graph.add_interned_task("sum", sum, &[two, one]).unwrap();
+        assert_eq!(first, second);
+        assert_ne!(first, swapped);
+
+        let text = graph.add_task(|| async { "text" });
+        assert!(graph.add_interned_task("sum", sum, &[one, text]).is_err());
+        assert_eq!(graph.as_dag().node_count(), 5);
+
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<i32>(first).unwrap(), 3);
+    }
+}
@@ -0,0 +1,115 @@
+use super::checkpoint::restore;
+use super::runner::Admission;
+use super::NodeIndex;
+use super::TryGraph;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// An executor lane a node's task runs on.
+///
+/// Lanes can have their own spawners and concurrency limits, see [`RunOptions`](super::RunOptions).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Lane {
+    /// Latency sensitive, IO bound tasks.
+    Io,
+    /// Compute heavy tasks.
+    Cpu,
+    /// A user defined lane.
+    Custom(String),
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Puts `node` on `lane`, replacing the previous one if any.
+    ///
+    /// **Panics** if `node` does not exist within the graph.
+    pub fn set_lane(&mut self, node: NodeIndex, lane: Lane) {
+        assert!(self.dag.node_weight(node).is_some());
+        let previous = self.schedule.lanes.insert(node, lane);
+        self.record(move |graph| restore(&mut graph.schedule.lanes, node, previous));
+    }
+
+    /// Gets the lane of `node` if it's put on one.
+    pub fn lane(&self, node: NodeIndex) -> Option<&Lane> {
+        self.schedule.lanes.get(&node)
+    }
+}
+
+/// Enforces concurrency limits per lane or group, whichever `K` is.
+pub(super) struct ConcurrencyLimits<'g, K> {
+    keys: &'g HashMap<NodeIndex, K>,
+    limits: &'g HashMap<K, usize>,
+    running: HashMap<&'g K, usize>,
+}
+
+impl<'g, K> ConcurrencyLimits<'g, K> {
+    pub(super) fn new(keys: &'g HashMap<NodeIndex, K>, limits: &'g HashMap<K, usize>) -> Self {
+        Self {
+            keys,
+            limits,
+            running: HashMap::new(),
+        }
+    }
+}
+
+impl<'g, K: Eq + Hash> Admission for ConcurrencyLimits<'g, K> {
+    fn admit(&mut self, node: NodeIndex) -> bool {
+        let (key, limit) = match self
+            .keys
+            .get(&node)
+            .and_then(|key| self.limits.get_key_value(key))
+        {
+            Some(key_and_limit) => key_and_limit,
+            None => return true,
+        };
+        let running = self.running.entry(key).or_default();
+        if *running < *limit {
+            *running += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn release(&mut self, node: NodeIndex) {
+        if let Some(running) = self
+            .keys
+            .get(&node)
+            .and_then(|key| self.running.get_mut(key))
+        {
+            *running -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::fixtures::Concurrency;
+    use crate::Graph;
+    use crate::RunOptions;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_lane_limit() {
+        let concurrency = Concurrency::default();
+        let mut graph = Graph::new();
+        for _ in 0..3 {
+            let node = graph.add_task(|| concurrency.clone().track());
+            graph.set_lane(node, Lane::Cpu);
+        }
+        block_on(graph.run_with(&RunOptions::new().lane_limit(Lane::Cpu, 1)));
+        assert_eq!(concurrency.max(), 1);
+    }
+    #[test]
+    fn test_lane_spawner() {
+        let mut graph = Graph::new();
+        let main = std::thread::current().id();
+        let node = graph.add_task(move || async move { std::thread::current().id() != main });
+        graph.set_lane(node, Lane::Custom("thread".to_owned()));
+        let options = RunOptions::new().lane_spawner(Lane::Custom("thread".to_owned()), |future| {
+            let _ = std::thread::spawn(move || block_on(future));
+        });
+        block_on(graph.run_with(&options));
+        assert!(graph.get_value::<bool>(node).unwrap());
+    }
+}
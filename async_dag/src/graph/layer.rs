@@ -0,0 +1,135 @@
+#[cfg(feature = "tracing")]
+use super::spans::SpanFactory;
+use super::NodeIndex;
+use super::TryGraph;
+use crate::curry::TaskFuture;
+#[cfg(feature = "opentelemetry")]
+use opentelemetry::global::BoxedTracer;
+use std::sync::Arc;
+
+/// Middleware around task futures, akin to `tower::Layer`.
+///
+/// A layer is a single place for logging, timeouts, metrics or panic handling,
+/// instead of wrapping every task manually.
+pub trait TaskLayer<'a, Err>: Send + Sync {
+    /// Wraps `future`, which runs the task of `node`.
+    fn layer(&self, node: NodeIndex, future: TaskFuture<'a, Err>) -> TaskFuture<'a, Err>;
+}
+
+impl<'a, Err, F> TaskLayer<'a, Err> for F
+where
+    F: Fn(NodeIndex, TaskFuture<'a, Err>) -> TaskFuture<'a, Err> + Send + Sync,
+{
+    fn layer(&self, node: NodeIndex, future: TaskFuture<'a, Err>) -> TaskFuture<'a, Err> {
+        self(node, future)
+    }
+}
+
+/// The layers of a graph, from innermost to outermost, its span factory and its tracer.
+pub(super) struct Layers<'a, Err> {
+    layers: Vec<Arc<dyn TaskLayer<'a, Err> + 'a>>,
+    #[cfg(feature = "tracing")]
+    pub(super) span_factory: Option<SpanFactory<'a>>,
+    #[cfg(feature = "opentelemetry")]
+    pub(super) tracer: Option<Arc<BoxedTracer>>,
+}
+
+impl<'a, Err> Layers<'a, Err> {
+    /// Wraps `future` with all layers.
+    pub(super) fn apply(
+        &self,
+        node: NodeIndex,
+        future: TaskFuture<'a, Err>,
+    ) -> TaskFuture<'a, Err> {
+        self.layers
+            .iter()
+            .fold(future, |future, layer| layer.layer(node, future))
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub(super) fn truncate(&mut self, len: usize) {
+        self.layers.truncate(len)
+    }
+}
+
+impl<'a, Err> Default for Layers<'a, Err> {
+    fn default() -> Self {
+        Self {
+            layers: vec![],
+            #[cfg(feature = "tracing")]
+            span_factory: None,
+            #[cfg(feature = "opentelemetry")]
+            tracer: None,
+        }
+    }
+}
+
+impl<'a, Err> Clone for Layers<'a, Err> {
+    fn clone(&self) -> Self {
+        Self {
+            layers: self.layers.clone(),
+            #[cfg(feature = "tracing")]
+            span_factory: self.span_factory.clone(),
+            #[cfg(feature = "opentelemetry")]
+            tracer: self.tracer.clone(),
+        }
+    }
+}
+
+impl<'a, Err> std::fmt::Debug for Layers<'a, Err> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} layers", self.layers.len())
+    }
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Wraps the future of every task with `layer` when it starts running.
+    ///
+    /// Layers added later wrap the earlier ones.
+    pub fn layer(&mut self, layer: impl TaskLayer<'a, Err> + 'a) {
+        self.layers.layers.push(Arc::new(layer));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::curry::TaskFuture;
+    use crate::Graph;
+    use crate::NodeIndex;
+    use futures::executor::block_on;
+    use futures::FutureExt;
+    use std::convert::Infallible;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_layer() {
+        let log = Arc::new(Mutex::new(vec![]));
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        let _ = graph
+            .add_child_task(one, |v: i32| async move { v + 1 }, 0)
+            .unwrap();
+        for name in ["inner", "outer"] {
+            let log = log.clone();
+            graph.layer(
+                move |node: NodeIndex, future: TaskFuture<'static, Infallible>| {
+                    let log = log.clone();
+                    async move {
+                        log.lock().unwrap().push((name, node.index()));
+                        future.await
+                    }
+                    .boxed()
+                },
+            );
+        }
+        block_on(graph.run());
+        assert_eq!(
+            *log.lock().unwrap(),
+            [("outer", 0), ("inner", 0), ("outer", 1), ("inner", 1)]
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use super::NodeIndex;
+use super::TryGraph;
+use crate::any::DynAny;
+use crate::any::TypeInfo;
+use crate::curry::TaskFuture;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use std::sync::Arc;
+
+/// A persistent record of completed side effects, e.g. backed by a database table.
+///
+/// Set by [`RunOptions::ledger`](super::RunOptions::ledger).
+pub trait Ledger: Send + Sync {
+    /// Whether `key` has been marked complete.
+    fn is_complete(&self, key: &str) -> BoxFuture<'static, bool>;
+
+    /// Marks `key` complete.
+    fn mark_complete(&self, key: &str) -> BoxFuture<'static, ()>;
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Protects `node` from running again by idempotency key `key`,
+    /// recorded in the [`Ledger`] set by [`RunOptions::ledger`](super::RunOptions::ledger).
+    ///
+    /// When the ledger has `key` marked complete, e.g. by a run that crashed before its checkpoint was saved,
+    /// the task's future is dropped without being polled and the node outputs `()`.
+    /// Otherwise `key` is marked complete after the task succeeds.
+    ///
+    /// **Panics** if `node` does not exist within the graph or its output type is not `()`.
+    pub fn set_idempotency_key(&mut self, node: NodeIndex, key: impl Into<String>) {
+        assert_eq!(
+            self.output_type_info(node),
+            TypeInfo::of::<()>(),
+            "Idempotent node outputting a value"
+        );
+        let _ = self.idempotency_keys.insert(node, key.into());
+    }
+
+    /// Gets the idempotency key of `node` if it has one.
+    pub fn idempotency_key(&self, node: NodeIndex) -> Option<&str> {
+        self.idempotency_keys.get(&node).map(String::as_str)
+    }
+}
+
+/// Consults `ledger` before polling `future`.
+pub(super) fn idempotent<'a, Err: 'a>(
+    ledger: &Arc<dyn Ledger>,
+    key: &str,
+    future: TaskFuture<'a, Err>,
+) -> TaskFuture<'a, Err> {
+    let ledger = ledger.clone();
+    let key = key.to_owned();
+    async move {
+        if ledger.is_complete(&key).await {
+            let value: DynAny = DynAny::new(());
+            return Ok(value);
+        }
+        let value = future.await?;
+        ledger.mark_complete(&key).await;
+        Ok(value)
+    }
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Ledger;
+    use crate::RunOptions;
+    use crate::TryGraph;
+    use futures::executor::block_on;
+    use futures::future::BoxFuture;
+    use futures::FutureExt;
+    use std::collections::HashSet;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryLedger(Mutex<HashSet<String>>);
+
+    impl Ledger for MemoryLedger {
+        fn is_complete(&self, key: &str) -> BoxFuture<'static, bool> {
+            futures::future::ready(self.0.lock().unwrap().contains(key)).boxed()
+        }
+
+        fn mark_complete(&self, key: &str) -> BoxFuture<'static, ()> {
+            let _ = self.0.lock().unwrap().insert(key.to_owned());
+            futures::future::ready(()).boxed()
+        }
+    }
+
+    #[test]
+    fn test_idempotency_key() {
+        let ledger = Arc::new(MemoryLedger::default());
+        let charges = AtomicUsize::new(0);
+        let mut results = vec![];
+        for attempt in 0..2 {
+            let mut graph = TryGraph::<&str>::new();
+            let charge = graph.add_try_task(|| async {
+                let _ = charges.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            });
+            graph.set_idempotency_key(charge, "charge-order-7");
+            let _ = graph
+                .add_child_try_task(
+                    charge,
+                    move |_: ()| async move {
+                        match attempt {
+                            0 => Err("crashed"),
+                            _ => Ok(()),
+                        }
+                    },
+                    0,
+                )
+                .unwrap();
+            results.push(block_on(
+                graph.try_run_with(&RunOptions::new().ledger(ledger.clone())),
+            ));
+        }
+        assert_eq!(results, [Err("crashed"), Ok(())]);
+        assert_eq!(charges.load(Ordering::Relaxed), 1);
+    }
+}
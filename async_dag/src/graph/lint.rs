@@ -0,0 +1,227 @@
+use super::NodeIndex;
+use super::TryGraph;
+use crate::any::TypeInfo;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+/// The kinds of [`Lint`]s, to [allow](LintOptions::allow) them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintKind {
+    /// See [`Lint::OrphanSideEffect`].
+    OrphanSideEffect,
+    /// See [`Lint::Island`].
+    Island,
+    /// See [`Lint::DuplicateSources`].
+    DuplicateSources,
+    /// See [`Lint::FanOut`].
+    FanOut,
+}
+
+/// A suspicious structure found by [`TryGraph::lint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Lint {
+    /// A node outputs `()` without dependencies or children, so nothing is ordered around its side effect.
+    OrphanSideEffect(NodeIndex),
+    /// The nodes are disconnected from the largest part of the graph.
+    Island(Vec<NodeIndex>),
+    /// The nodes have no dependencies, and the same name and output type, so they likely do the same.
+    DuplicateSources(Vec<NodeIndex>),
+    /// The node has more children than [`LintOptions::max_fan_out`].
+    FanOut {
+        /// The node.
+        node: NodeIndex,
+        /// How many children it has.
+        children: usize,
+    },
+}
+
+impl Lint {
+    /// The kind of the lint.
+    pub fn kind(&self) -> LintKind {
+        match self {
+            Lint::OrphanSideEffect(_) => LintKind::OrphanSideEffect,
+            Lint::Island(_) => LintKind::Island,
+            Lint::DuplicateSources(_) => LintKind::DuplicateSources,
+            Lint::FanOut { .. } => LintKind::FanOut,
+        }
+    }
+}
+
+/// Options for [`TryGraph::lint_with`].
+#[derive(Debug, Clone)]
+pub struct LintOptions {
+    allowed: HashSet<LintKind>,
+    max_fan_out: usize,
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        Self {
+            allowed: HashSet::new(),
+            max_fan_out: 1000,
+        }
+    }
+}
+
+impl LintOptions {
+    /// Creates the default options, checking everything with a maximum fan-out of `1000`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skips checking `kind`.
+    pub fn allow(mut self, kind: LintKind) -> Self {
+        let _ = self.allowed.insert(kind);
+        self
+    }
+
+    /// Flags nodes with more than `max` children.
+    pub fn max_fan_out(mut self, max: usize) -> Self {
+        self.max_fan_out = max;
+        self
+    }
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Checks the graph for suspicious structures with the default [`LintOptions`],
+    /// e.g. to catch generator bugs before running.
+    pub fn lint(&self) -> Vec<Lint> {
+        self.lint_with(&LintOptions::default())
+    }
+
+    /// Checks the graph for suspicious structures, ordered by kind and then by node index.
+    pub fn lint_with(&self, options: &LintOptions) -> Vec<Lint> {
+        let graph = self.dag.graph();
+        let checks = |kind| !options.allowed.contains(&kind);
+        let mut lints = vec![];
+
+        if checks(LintKind::OrphanSideEffect) {
+            lints.extend(
+                graph
+                    .node_indices()
+                    .filter(|node| {
+                        self.output_type_info(*node) == TypeInfo::of::<()>()
+                            && graph.neighbors_undirected(*node).next().is_none()
+                    })
+                    .map(Lint::OrphanSideEffect),
+            );
+        }
+
+        if checks(LintKind::Island) {
+            let mut components = vec![];
+            let mut visited = HashSet::new();
+            for node in graph.node_indices() {
+                if !visited.insert(node) {
+                    continue;
+                }
+                let mut component = vec![node];
+                let mut stack = vec![node];
+                while let Some(node) = stack.pop() {
+                    for neighbor in graph.neighbors_undirected(node) {
+                        if visited.insert(neighbor) {
+                            component.push(neighbor);
+                            stack.push(neighbor);
+                        }
+                    }
+                }
+                component.sort();
+                components.push(component);
+            }
+            // The first largest component is the main one.
+            let main = components
+                .iter()
+                .enumerate()
+                .max_by_key(|(position, component)| (component.len(), std::cmp::Reverse(*position)))
+                .map(|(position, _)| position);
+            lints.extend(
+                components
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(position, _)| Some(*position) != main)
+                    .map(|(_, component)| Lint::Island(component)),
+            );
+        }
+
+        if checks(LintKind::DuplicateSources) {
+            let mut sources = HashMap::<_, Vec<_>>::new();
+            let mut keys = vec![];
+            for node in self.sources() {
+                if let Some(name) = self.name(node) {
+                    let key = (name, self.output_type_info(node));
+                    let nodes = sources.entry(key).or_default();
+                    if nodes.is_empty() {
+                        keys.push(key);
+                    }
+                    nodes.push(node);
+                }
+            }
+            lints.extend(
+                keys.into_iter()
+                    .map(|key| sources.remove(&key).unwrap())
+                    .filter(|nodes| nodes.len() > 1)
+                    .map(Lint::DuplicateSources),
+            );
+        }
+
+        if checks(LintKind::FanOut) {
+            lints.extend(graph.node_indices().filter_map(|node| {
+                let children = graph.neighbors(node).count();
+                (children > options.max_fan_out).then_some(Lint::FanOut { node, children })
+            }));
+        }
+
+        lints
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Lint;
+    use super::LintKind;
+    use super::LintOptions;
+    use crate::Graph;
+
+    #[test]
+    fn test_lint() {
+        let mut graph = Graph::new();
+        let load = graph.add_task(|| async { 1 });
+        graph.set_name(load, "load");
+        let again = graph.add_task(|| async { 1 });
+        graph.set_name(again, "load");
+        let sum = graph.add_task(|a: i32, b: i32| async move { a + b });
+        graph.update_dependency(load, sum, 0).unwrap();
+        graph.update_dependency(again, sum, 1).unwrap();
+        let notify = graph.add_task(|| async {});
+        let log = graph.add_task(|| async { "log" });
+        let print = graph
+            .add_child_task(log, |_: &'static str| async {}, 0)
+            .unwrap();
+
+        assert_eq!(
+            graph.lint_with(&LintOptions::new().max_fan_out(0)),
+            [
+                Lint::OrphanSideEffect(notify),
+                Lint::Island(vec![notify]),
+                Lint::Island(vec![log, print]),
+                Lint::DuplicateSources(vec![load, again]),
+                Lint::FanOut {
+                    node: load,
+                    children: 1
+                },
+                Lint::FanOut {
+                    node: again,
+                    children: 1
+                },
+                Lint::FanOut {
+                    node: log,
+                    children: 1
+                },
+            ]
+        );
+        let options = LintOptions::new()
+            .allow(LintKind::Island)
+            .allow(LintKind::DuplicateSources);
+        assert_eq!(graph.lint_with(&options), [Lint::OrphanSideEffect(notify)]);
+        assert_eq!(graph.lint()[0].kind(), LintKind::OrphanSideEffect);
+    }
+}
@@ -0,0 +1,160 @@
+use super::error::Error;
+use super::Edge;
+use super::NodeIndex;
+use super::TryGraph;
+use crate::task::IntoInfallibleTask;
+use crate::task::IntoLocalInfallibleTask;
+use crate::task::IntoLocalTryTask;
+use crate::task::IntoTryTask;
+use std::convert::Infallible;
+use std::marker::PhantomData;
+
+/// A graph bound to the thread it's created on, so tasks with non-[`Send`] functions and futures can be added.
+///
+/// The graph itself isn't [`Send`], and neither is the future running it,
+/// so its tasks are always run and dropped on the current thread, e.g. within a `LocalSet`:
+///
+/// ```compile_fail
+/// # use async_dag::LocalGraph;
+/// let graph = LocalGraph::new();
+/// std::thread::spawn(move || drop(graph));
+/// ```
+///
+/// The underlying [`TryGraph`] can be read by [`LocalTryGraph::graph`], but not taken out.
+#[derive(Debug)]
+pub struct LocalTryGraph<'a, Err> {
+    graph: TryGraph<'a, Err>,
+    // Local tasks only pass `TryGraph`'s `Send` bounds by deferring the thread check to runtime,
+    // so the graph holding them must not leave the thread.
+    local: PhantomData<*const ()>,
+}
+
+/// A [`LocalTryGraph`] with infallible tasks.
+pub type LocalGraph<'a> = LocalTryGraph<'a, Infallible>;
+
+impl<'a, Err: 'a> LocalTryGraph<'a, Err> {
+    /// Creates an empty graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a task, see [`TryGraph::add_try_task`].
+    pub fn add_try_task<Args, Ok, T: IntoTryTask<'a, Args, Ok, Err>>(
+        &mut self,
+        task: T,
+    ) -> NodeIndex {
+        self.graph.add_try_task(task)
+    }
+
+    /// Adds a task whose function and future don't have to be [`Send`].
+    ///
+    /// See [`TryGraph::add_try_task`].
+    pub fn add_local_try_task<Args, Ok, T: IntoLocalTryTask<'a, Args, Ok, Err>>(
+        &mut self,
+        task: T,
+    ) -> NodeIndex {
+        self.graph.add_task_impl(task.into_task())
+    }
+
+    /// Sets `parent` as `child`'s dependency at `index`, see [`TryGraph::update_dependency`].
+    pub fn update_dependency(
+        &mut self,
+        parent: NodeIndex,
+        child: NodeIndex,
+        index: Edge,
+    ) -> Result<(), Error> {
+        self.graph.update_dependency(parent, child, index)
+    }
+
+    /// Sets the name of `node`, see [`TryGraph::set_name`].
+    pub fn set_name(&mut self, node: NodeIndex, name: impl Into<String>) {
+        self.graph.set_name(node, name);
+    }
+
+    /// Runs the graph on the current thread, see [`TryGraph::try_run`].
+    pub async fn try_run_local(&mut self) -> Result<(), Err> {
+        self.graph.try_run().await
+    }
+
+    /// Gets the output value of `node`, see [`TryGraph::get_value`].
+    pub fn get_value<T: 'static>(&self, node: NodeIndex) -> Option<T> {
+        self.graph.get_value(node)
+    }
+
+    /// The underlying graph, e.g. to inspect its topology.
+    pub fn graph(&self) -> &TryGraph<'a, Err> {
+        &self.graph
+    }
+}
+
+impl<'a> LocalTryGraph<'a, Infallible> {
+    /// Adds an infallible task, see [`TryGraph::add_try_task`].
+    pub fn add_task<Args, Ok, T: IntoInfallibleTask<'a, Args, Ok>>(
+        &mut self,
+        task: T,
+    ) -> NodeIndex {
+        self.graph.add_task(task)
+    }
+
+    /// Adds an infallible task whose function and future don't have to be [`Send`].
+    ///
+    /// See [`LocalTryGraph::add_local_try_task`].
+    pub fn add_local_task<Args, Ok, T: IntoLocalInfallibleTask<'a, Args, Ok>>(
+        &mut self,
+        task: T,
+    ) -> NodeIndex {
+        self.graph.add_task_impl(task.into_task())
+    }
+
+    /// Infallible version of [`LocalTryGraph::try_run_local`].
+    pub async fn run_local(&mut self) {
+        self.try_run_local().await.unwrap();
+    }
+}
+
+impl<'a, Err: 'a> Default for LocalTryGraph<'a, Err> {
+    fn default() -> Self {
+        Self {
+            graph: TryGraph::default(),
+            local: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LocalGraph;
+    use super::LocalTryGraph;
+    use futures::executor::block_on;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_local_task() {
+        let mut graph = LocalGraph::new();
+        let two = Rc::new(2);
+        let one = graph.add_local_task(|| async {
+            let one = Rc::new(1);
+            futures::future::ready(()).await;
+            *one
+        });
+        let root = graph.add_local_task(move |v: i32| async move { v + *two });
+        graph.update_dependency(one, root, 0).unwrap();
+        block_on(graph.run_local());
+        assert_eq!(graph.get_value::<i32>(root).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_local_try_task() {
+        let mut graph = LocalTryGraph::new();
+        let one = graph.add_try_task(|| async { Ok(1) });
+        let fail = graph.add_local_try_task(|v: i32| async move {
+            let v = Rc::new(v);
+            futures::future::ready(()).await;
+            Err::<(), _>(*v)
+        });
+        graph.update_dependency(one, fail, 0).unwrap();
+        graph.set_name(fail, "fail");
+        assert_eq!(block_on(graph.try_run_local()), Err(1));
+        assert_eq!(graph.graph().name(fail), Some("fail"));
+    }
+}
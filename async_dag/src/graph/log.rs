@@ -0,0 +1,72 @@
+use super::NodeIndex;
+use crate::curry::TaskFuture;
+use futures::FutureExt;
+use std::collections::HashMap;
+
+/// Logs at debug level when `future` starts, and when it finishes or fails,
+/// with the node's index and name if any.
+pub(super) fn observe<'a, Err: 'a>(
+    names: &HashMap<NodeIndex, String>,
+    node: NodeIndex,
+    future: TaskFuture<'a, Err>,
+) -> TaskFuture<'a, Err> {
+    let label = match names.get(&node) {
+        Some(name) => format!("{} '{}'", node.index(), name),
+        None => node.index().to_string(),
+    };
+    async move {
+        ::log::debug!(target: "async_dag", "node {} started", label);
+        let output = future.await;
+        match output {
+            Ok(_) => ::log::debug!(target: "async_dag", "node {} finished", label),
+            Err(_) => ::log::debug!(target: "async_dag", "node {} failed", label),
+        }
+        output
+    }
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TryGraph;
+    use futures::executor::block_on;
+    use log::Log;
+    use log::Metadata;
+    use log::Record;
+    use std::sync::Mutex;
+
+    struct Collector(Mutex<Vec<String>>);
+
+    impl Log for Collector {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record<'_>) {
+            if record.target() == "async_dag" {
+                self.0.lock().unwrap().push(record.args().to_string());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static COLLECTOR: Collector = Collector(Mutex::new(vec![]));
+
+    #[test]
+    fn test_log() {
+        log::set_logger(&COLLECTOR).unwrap();
+        log::set_max_level(log::LevelFilter::Debug);
+        let mut graph = TryGraph::<()>::new();
+        let ok = graph.add_try_task(|| async { Ok(()) });
+        graph.set_name(ok, "ok");
+        let _ = graph.add_try_task(|| async { Err::<(), _>(()) });
+        let _ = block_on(graph.try_run());
+
+        let messages = COLLECTOR.0.lock().unwrap();
+        assert!(messages.contains(&"node 0 'ok' started".to_owned()));
+        assert!(messages.contains(&"node 0 'ok' finished".to_owned()));
+        assert!(messages.contains(&"node 1 started".to_owned()));
+        assert!(messages.contains(&"node 1 failed".to_owned()));
+    }
+}
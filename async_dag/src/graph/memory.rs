@@ -0,0 +1,87 @@
+use super::checkpoint::restore;
+use super::runner::Admission;
+use super::NodeIndex;
+use super::TryGraph;
+use std::collections::HashMap;
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Estimates `node` to use `bytes` of memory while running, replacing the previous estimate if any.
+    ///
+    /// Nodes without an estimate are assumed to use none.
+    ///
+    /// **Panics** if `node` does not exist within the graph.
+    pub fn set_memory_hint(&mut self, node: NodeIndex, bytes: usize) {
+        assert!(self.dag.node_weight(node).is_some());
+        let previous = self.schedule.memory_hints.insert(node, bytes);
+        self.record(move |graph| restore(&mut graph.schedule.memory_hints, node, previous));
+    }
+
+    /// Limits the sum of [memory hints](TryGraph::set_memory_hint) of running nodes to `bytes`.
+    ///
+    /// A ready node is delayed until starting it won't exceed the budget,
+    /// unless no other hinted node is running, so a node exceeding the budget alone still runs.
+    pub fn set_memory_budget(&mut self, bytes: usize) {
+        let previous = self.schedule.memory_budget.replace(bytes);
+        self.record(move |graph| graph.schedule.memory_budget = previous);
+    }
+}
+
+/// Enforces the memory budget.
+pub(super) struct MemoryBudget<'g> {
+    hints: &'g HashMap<NodeIndex, usize>,
+    budget: Option<usize>,
+    used: usize,
+}
+
+impl<'g> MemoryBudget<'g> {
+    pub(super) fn new(hints: &'g HashMap<NodeIndex, usize>, budget: Option<usize>) -> Self {
+        Self {
+            hints,
+            budget,
+            used: 0,
+        }
+    }
+}
+
+impl<'g> Admission for MemoryBudget<'g> {
+    fn admit(&mut self, node: NodeIndex) -> bool {
+        let (budget, hint) = match (self.budget, self.hints.get(&node)) {
+            (Some(budget), Some(hint)) => (budget, *hint),
+            _ => return true,
+        };
+        if self.used > 0 && self.used + hint > budget {
+            return false;
+        }
+        self.used += hint;
+        true
+    }
+
+    fn release(&mut self, node: NodeIndex) {
+        if self.budget.is_some() {
+            self.used -= self.hints.get(&node).copied().unwrap_or_default();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::fixtures::Concurrency;
+    use crate::Graph;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_memory_budget() {
+        let concurrency = Concurrency::default();
+        let mut graph = Graph::new();
+        graph.set_memory_budget(100);
+        for _ in 0..4 {
+            let node = graph.add_task(|| concurrency.clone().track());
+            graph.set_memory_hint(node, 40);
+        }
+        let huge = graph.add_task(|| async { vec![0u8; 16] });
+        graph.set_memory_hint(huge, 1000);
+        block_on(graph.run());
+        assert_eq!(concurrency.max(), 2);
+        assert!(graph.get_value::<Vec<u8>>(huge).is_some());
+    }
+}
@@ -0,0 +1,76 @@
+use super::NodeIndex;
+use crate::curry::TaskFuture;
+use futures::FutureExt;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Emits `tasks_started`, `tasks_failed` and `task_duration_seconds` for `future`,
+/// labeled by the node's name, or its index if unnamed.
+pub(super) fn observe<'a, Err: 'a>(
+    names: &HashMap<NodeIndex, String>,
+    node: NodeIndex,
+    future: TaskFuture<'a, Err>,
+) -> TaskFuture<'a, Err> {
+    let label = names
+        .get(&node)
+        .cloned()
+        .unwrap_or_else(|| node.index().to_string());
+    async move {
+        ::metrics::counter!("tasks_started", "node" => label.clone()).increment(1);
+        let start = Instant::now();
+        let output = future.await;
+        ::metrics::histogram!("task_duration_seconds", "node" => label.clone())
+            .record(start.elapsed().as_secs_f64());
+        if output.is_err() {
+            ::metrics::counter!("tasks_failed", "node" => label).increment(1);
+        }
+        output
+    }
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TryGraph;
+    use futures::executor::block_on;
+    use metrics_util::debugging::DebugValue;
+    use metrics_util::debugging::DebuggingRecorder;
+    use metrics_util::MetricKind;
+
+    #[test]
+    fn test_metrics() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let mut graph = TryGraph::<()>::new();
+        let ok = graph.add_try_task(|| async { Ok(()) });
+        graph.set_name(ok, "ok");
+        let _ = graph.add_try_task(|| async { Err::<(), _>(()) });
+        metrics::with_local_recorder(&recorder, || {
+            let _ = block_on(graph.try_run());
+        });
+
+        let snapshot = snapshotter.snapshot().into_vec();
+        let counter = |name: &str, node: &str| {
+            snapshot.iter().find_map(|(key, _, _, value)| {
+                let key = key.key();
+                match value {
+                    DebugValue::Counter(count)
+                        if key.name() == name
+                            && key.labels().any(|label| label.value() == node) =>
+                    {
+                        Some(*count)
+                    }
+                    _ => None,
+                }
+            })
+        };
+        assert_eq!(counter("tasks_started", "ok"), Some(1));
+        assert_eq!(counter("tasks_started", "1"), Some(1));
+        assert_eq!(counter("tasks_failed", "ok"), None);
+        assert_eq!(counter("tasks_failed", "1"), Some(1));
+        assert!(snapshot
+            .iter()
+            .any(|(key, _, _, _)| key.kind() == MetricKind::Histogram
+                && key.key().name() == "task_duration_seconds"));
+    }
+}
@@ -0,0 +1,415 @@
+use super::replay::Replay;
+use super::runner::Policy;
+use super::watchdog::Watchdog;
+use super::Clock;
+#[cfg(feature = "inspector")]
+use super::Inspector;
+use super::Lane;
+use super::Ledger;
+use super::NodeCache;
+use super::NodeIndex;
+use super::Recording;
+use super::RunHandle;
+use super::RunReport;
+use super::SystemClock;
+#[cfg(feature = "tui")]
+use super::TerminalProgress;
+use crate::any::downcast;
+use crate::any::DynAny;
+use crate::any::IntoAny;
+use crate::curry::TaskFuture;
+use futures::channel::oneshot;
+use futures::executor::block_on;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use futures::TryFutureExt;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A job that may block, to be run on a thread where blocking is allowed.
+pub type BlockingJob<'a> = Box<dyn FnOnce() + Send + 'a>;
+
+/// A function that runs [`BlockingJob`]s, e.g. by `tokio::task::spawn_blocking`.
+pub type BlockingSpawner<'a> = Box<dyn Fn(BlockingJob<'a>) + Send + Sync + 'a>;
+
+/// A function that drives futures to completion elsewhere, e.g. by `tokio::spawn`.
+pub type Spawner<'a> = Box<dyn Fn(BoxFuture<'a, ()>) + Send + Sync + 'a>;
+
+/// A function observing or replacing every value produced by a node, see [`RunOptions::on_value`].
+pub type ValueHook<'a> = Arc<dyn Fn(NodeIndex, DynAny) -> DynAny + Send + Sync + 'a>;
+
+/// Options for [`TryGraph::try_run_with`](super::TryGraph::try_run_with).
+pub struct RunOptions<'a> {
+    pub(super) blocking_spawner: Option<BlockingSpawner<'a>>,
+    pub(super) lane_spawners: HashMap<Lane, Spawner<'a>>,
+    pub(super) lane_limits: HashMap<Lane, usize>,
+    pub(super) concurrency_limit: Option<usize>,
+    pub(super) clock: Arc<dyn Clock>,
+    pub(super) report: Option<RunReport>,
+    pub(super) handle: Option<RunHandle>,
+    pub(super) policy: Policy,
+    pub(super) cache: Option<Arc<dyn NodeCache>>,
+    pub(super) ledger: Option<Arc<dyn Ledger>>,
+    #[cfg(feature = "inspector")]
+    pub(super) inspector: Option<Arc<Inspector>>,
+    #[cfg(feature = "tui")]
+    pub(super) terminal_progress: Option<Arc<TerminalProgress>>,
+    pub(super) record: Option<Recording>,
+    pub(super) replay: Option<Replay>,
+    pub(super) watchdog: Option<Watchdog<'a>>,
+    pub(super) on_value: Option<ValueHook<'a>>,
+}
+
+impl<'a> Default for RunOptions<'a> {
+    fn default() -> Self {
+        Self {
+            blocking_spawner: None,
+            lane_spawners: HashMap::new(),
+            lane_limits: HashMap::new(),
+            concurrency_limit: None,
+            clock: Arc::new(SystemClock),
+            report: None,
+            handle: None,
+            policy: Policy::default(),
+            cache: None,
+            ledger: None,
+            #[cfg(feature = "inspector")]
+            inspector: None,
+            #[cfg(feature = "tui")]
+            terminal_progress: None,
+            record: None,
+            replay: None,
+            watchdog: None,
+            on_value: None,
+        }
+    }
+}
+
+impl<'a> RunOptions<'a> {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the spawner for nodes marked by [`TryGraph::mark_blocking`](super::TryGraph::mark_blocking).
+    ///
+    /// Without a spawner, blocking nodes are run with the other nodes.
+    pub fn blocking_spawner(
+        mut self,
+        spawner: impl Fn(BlockingJob<'a>) + Send + Sync + 'a,
+    ) -> Self {
+        self.blocking_spawner = Some(Box::new(spawner));
+        self
+    }
+
+    /// Sets the spawner for nodes on `lane`.
+    ///
+    /// Without a spawner, nodes on the lane are run with the other nodes.
+    pub fn lane_spawner(
+        mut self,
+        lane: Lane,
+        spawner: impl Fn(BoxFuture<'a, ()>) + Send + Sync + 'a,
+    ) -> Self {
+        let _ = self.lane_spawners.insert(lane, Box::new(spawner));
+        self
+    }
+
+    /// Limits how many nodes on `lane` can run at the same time.
+    ///
+    /// **Panics** if `limit` is `0`.
+    pub fn lane_limit(mut self, lane: Lane, limit: usize) -> Self {
+        assert!(limit > 0, "Lane limit must be positive");
+        let _ = self.lane_limits.insert(lane, limit);
+        self
+    }
+
+    /// Limits how many nodes can run at the same time.
+    ///
+    /// Free slots are shared between groups in proportion to their [weights](super::TryGraph::set_group_weight).
+    ///
+    /// **Panics** if `limit` is `0`.
+    pub fn concurrency_limit(mut self, limit: usize) -> Self {
+        assert!(limit > 0, "Concurrency limit must be positive");
+        self.concurrency_limit = Some(limit);
+        self
+    }
+
+    /// Sets the [`Clock`] rate limits are measured by, [`SystemClock`] by default.
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Collects per node poll and wake statistics into `report`.
+    ///
+    /// The statistics are of task futures, after [`TaskLayer`](super::TaskLayer)s but before spawning.
+    pub fn report(mut self, report: RunReport) -> Self {
+        self.report = Some(report);
+        self
+    }
+
+    /// Sets the [`NodeCache`] consulted for nodes marked by [`TryGraph::cache_output`](super::TryGraph::cache_output).
+    pub fn cache(mut self, cache: Arc<dyn NodeCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Sets the [`Ledger`] consulted for nodes marked by [`TryGraph::set_idempotency_key`](super::TryGraph::set_idempotency_key).
+    pub fn ledger(mut self, ledger: Arc<dyn Ledger>) -> Self {
+        self.ledger = Some(ledger);
+        self
+    }
+
+    /// Publishes the state of the run to `inspector` as it progresses.
+    #[cfg(feature = "inspector")]
+    pub fn inspector(mut self, inspector: Arc<Inspector>) -> Self {
+        self.inspector = Some(inspector);
+        self
+    }
+
+    /// Draws the progress of the run to `progress` as it goes.
+    #[cfg(feature = "tui")]
+    pub fn terminal_progress(mut self, progress: Arc<TerminalProgress>) -> Self {
+        self.terminal_progress = Some(progress);
+        self
+    }
+
+    /// Records the order nodes complete in, and their values, into `recording`.
+    pub fn record(mut self, recording: Recording) -> Self {
+        self.record = Some(recording);
+        self
+    }
+
+    /// Completes nodes in the order of `recording`, with the recorded values,
+    /// to reproduce scheduler dependent issues of the recorded run.
+    ///
+    /// Recorded nodes don't run their tasks, while the others run after all recorded nodes complete.
+    /// The graph should be built the same way as the recorded one, and run with the same limits.
+    pub fn replay(mut self, recording: &Recording) -> Self {
+        self.replay = Some(Replay::new(recording));
+        self
+    }
+
+    /// Calls `observer` with the index, name and running time of any node running longer than `threshold`,
+    /// again every `threshold` until it completes, to diagnose hung tasks.
+    ///
+    /// Time is measured by [`RunOptions::clock`]. With the `log` feature, stalls are also logged at warn level.
+    pub fn stall_watchdog(
+        mut self,
+        threshold: Duration,
+        observer: impl Fn(NodeIndex, Option<&str>, Duration) + Send + Sync + 'a,
+    ) -> Self {
+        self.watchdog = Some(Watchdog {
+            threshold,
+            observer: Arc::new(observer),
+        });
+        self
+    }
+
+    /// Calls `hook` with every value a node produces before it's handed to the node's children,
+    /// passing on the value `hook` returns instead, e.g. for audit logging, redaction or injecting faults.
+    ///
+    /// The returned value must have the same type, or the run fails with [`DependencyError::Output`](crate::error::DependencyError::Output),
+    /// see [`TryGraph::try_run_checked`](super::TryGraph::try_run_checked).
+    /// It's also what the node keeps, see [`TryGraph::get_value`](super::TryGraph::get_value),
+    /// and what [`RunOptions::record`] records. Use [`downcast`](crate::downcast) to inspect values,
+    /// or [`RunOptions::on_value_of`] to only see values of one type.
+    pub fn on_value(
+        mut self,
+        hook: impl Fn(NodeIndex, DynAny) -> DynAny + Send + Sync + 'a,
+    ) -> Self {
+        self.on_value = Some(Arc::new(hook));
+        self
+    }
+
+    /// Like [`RunOptions::on_value`], but only calls `hook` with values of type `T`, passing the others on unchanged.
+    ///
+    /// Replaces the hook set by [`RunOptions::on_value`].
+    pub fn on_value_of<T: IntoAny>(
+        self,
+        hook: impl Fn(NodeIndex, T) -> T + Send + Sync + 'a,
+    ) -> Self {
+        self.on_value(move |node, value| match downcast::<T>(value) {
+            Ok(value) => DynAny::new(hook(node, value)),
+            Err(value) => value,
+        })
+    }
+
+    /// Lets `handle` control the run.
+    pub fn handle(mut self, handle: RunHandle) -> Self {
+        self.handle = Some(handle);
+        self
+    }
+
+    /// Sets how long running nodes may keep running after [`RunHandle::shutdown`], zero by default.
+    pub fn grace_period(mut self, grace_period: Duration) -> Self {
+        self.policy.grace_period = grace_period;
+        self
+    }
+
+    /// Drops a node's value as soon as all its children have received it,
+    /// leaving the node [`Node::Dropped`](super::Node::Dropped).
+    ///
+    /// Values of nodes without children are kept.
+    /// This bounds memory for long and wide graphs passing large buffers.
+    pub fn drop_consumed_values(mut self) -> Self {
+        self.policy.drop_consumed = true;
+        self
+    }
+
+    /// Keeps running when a task fails, instead of aborting the run with its error.
+    ///
+    /// The failed node is left [`Node::Failed`](super::Node::Failed) with the error,
+    /// and its descendants [`Node::Skipped`](super::Node::Skipped) with it as the cause.
+    /// Independent nodes still run, and the run returns [`Ok`], see [`TryGraph::failures`](super::TryGraph::failures).
+    pub fn continue_on_error(mut self) -> Self {
+        self.policy.continue_on_error = true;
+        self
+    }
+
+    /// Yields to the executor after handing a completed node's value to every `budget` children,
+    /// so a hub node with thousands of children doesn't hold the executor thread for a single long poll.
+    ///
+    /// **Panics** if `budget` is `0`.
+    pub fn yield_budget(mut self, budget: usize) -> Self {
+        assert!(budget > 0, "Yield budget must be positive");
+        self.policy.yield_budget = Some(budget);
+        self
+    }
+
+    /// Rotates which running node is polled first on every step,
+    /// so completions of nodes added earlier aren't systematically handled first.
+    pub fn fair(mut self) -> Self {
+        self.policy.fair = true;
+        self
+    }
+}
+
+impl<'a> std::fmt::Debug for RunOptions<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RunOptions")
+            .field("blocking_spawner", &self.blocking_spawner.is_some())
+            .field(
+                "lane_spawners",
+                &self.lane_spawners.keys().collect::<Vec<_>>(),
+            )
+            .field("lane_limits", &self.lane_limits)
+            .field("concurrency_limit", &self.concurrency_limit)
+            .field("report", &self.report.is_some())
+            .field("handle", &self.handle.is_some())
+            .field("policy", &self.policy)
+            .field("cache", &self.cache.is_some())
+            .field("ledger", &self.ledger.is_some())
+            .field("record", &self.record.is_some())
+            .field("replay", &self.replay.is_some())
+            .field(
+                "stall_threshold",
+                &self.watchdog.as_ref().map(|watchdog| watchdog.threshold),
+            )
+            .field("on_value", &self.on_value.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Passes the value `future` outputs through `hook`.
+pub(super) fn intercept<'a, Err: 'a>(
+    hook: &ValueHook<'a>,
+    node: NodeIndex,
+    future: TaskFuture<'a, Err>,
+) -> TaskFuture<'a, Err> {
+    let hook = hook.clone();
+    future.map_ok(move |value| hook(node, value)).boxed()
+}
+
+/// Drives `future` to completion within a job passed to `spawner`.
+pub(super) fn spawn_blocking<'a, Err: Send + 'a>(
+    spawner: &BlockingSpawner<'a>,
+    future: TaskFuture<'a, Err>,
+) -> TaskFuture<'a, Err> {
+    let (sender, receiver) = oneshot::channel();
+    spawner(Box::new(move || {
+        let _ = sender.send(block_on(future));
+    }));
+    receiver
+        .map(|output| output.expect("Blocking job is dropped before completion"))
+        .boxed()
+}
+
+/// Drives `future` to completion within a future passed to `spawner`.
+pub(super) fn spawn<'a, Err: Send + 'a>(
+    spawner: &Spawner<'a>,
+    future: TaskFuture<'a, Err>,
+) -> TaskFuture<'a, Err> {
+    let (sender, receiver) = oneshot::channel();
+    spawner(
+        async move {
+            let _ = sender.send(future.await);
+        }
+        .boxed(),
+    );
+    receiver
+        .map(|output| output.expect("Spawned future is dropped before completion"))
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RunOptions;
+    use crate::any::downcast;
+    use crate::any::DynAny;
+    use crate::error::DependencyError;
+    use crate::error::RunError;
+    use crate::Graph;
+    use futures::executor::block_on;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_on_value() {
+        let mut graph = Graph::new();
+        let secret = graph.add_task(|| async { "secret".to_owned() });
+        let length = graph
+            .add_child_task(secret, |s: String| async move { s.len() }, 0)
+            .unwrap();
+        let seen = Arc::new(Mutex::new(vec![]));
+        let options = RunOptions::new().on_value({
+            let seen = seen.clone();
+            move |node, value| {
+                seen.lock().unwrap().push(node);
+                match downcast::<String>(value) {
+                    Ok(_) => DynAny::new("***".to_owned()),
+                    Err(value) => value,
+                }
+            }
+        });
+        block_on(graph.try_run_with(&options)).unwrap();
+        assert_eq!(*seen.lock().unwrap(), vec![secret, length]);
+        assert_eq!(graph.get_value::<String>(secret).unwrap(), "***");
+        assert_eq!(graph.get_value::<usize>(length).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_on_value_of() {
+        let mut graph = Graph::new();
+        let secret = graph.add_task(|| async { "secret".to_owned() });
+        let length = graph
+            .add_child_task(secret, |s: String| async move { s.len() }, 0)
+            .unwrap();
+        let options = RunOptions::new().on_value_of(|_, s: String| s.to_uppercase());
+        block_on(graph.try_run_with(&options)).unwrap();
+        assert_eq!(graph.get_value::<String>(secret).unwrap(), "SECRET");
+        assert_eq!(graph.get_value::<usize>(length).unwrap(), 6);
+    }
+
+    #[test]
+    fn test_on_value_type_changed() {
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        let options = RunOptions::new().on_value(|_, _| DynAny::new("one"));
+        assert!(matches!(
+            block_on(graph.try_run_checked(&options)),
+            Err(RunError::Dependency(DependencyError::Output { node, .. })) if node == one
+        ));
+    }
+}
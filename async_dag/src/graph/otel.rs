@@ -0,0 +1,169 @@
+use super::Edge;
+use super::Index;
+use super::NodeIndex;
+use super::TryGraph;
+use crate::curry::TaskFuture;
+use daggy::petgraph::visit::EdgeRef;
+use daggy::petgraph::Direction;
+use daggy::Dag;
+use futures::FutureExt;
+use opentelemetry::global;
+use opentelemetry::global::BoxedTracer;
+use opentelemetry::trace::Link;
+use opentelemetry::trace::Status;
+use opentelemetry::trace::TraceContextExt;
+use opentelemetry::trace::Tracer;
+use opentelemetry::Context;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+/// Creates an OpenTelemetry span per node through the graph's tracer, or the global tracer.
+///
+/// All node spans belong to a run span, a child of the current context.
+/// A node's span is a child of its first dependency's span, and links to the other dependencies' spans.
+pub(super) struct Tracing {
+    tracer: Arc<BoxedTracer>,
+    run: Context,
+    // Dependencies by input index.
+    dependencies: HashMap<NodeIndex, Vec<NodeIndex>>,
+    labels: HashMap<NodeIndex, String>,
+    spans: Arc<Mutex<HashMap<NodeIndex, Context>>>,
+}
+
+impl Tracing {
+    pub(super) fn new<N>(
+        tracer: Option<&Arc<BoxedTracer>>,
+        dag: &Dag<N, Edge, Index>,
+        names: &HashMap<NodeIndex, String>,
+    ) -> Self {
+        let tracer = tracer
+            .cloned()
+            .unwrap_or_else(|| Arc::new(global::tracer("async_dag")));
+        let run = Context::current().with_span(tracer.start("async_dag::run"));
+        let dependencies = (0..dag.node_count())
+            .map(|index| {
+                let node = NodeIndex::new(index);
+                let mut edges = dag
+                    .graph()
+                    .edges_directed(node, Direction::Incoming)
+                    .map(|edge| (*edge.weight(), edge.source()))
+                    .collect::<Vec<_>>();
+                edges.sort();
+                (node, edges.into_iter().map(|(_, parent)| parent).collect())
+            })
+            .collect();
+        Self {
+            tracer,
+            run,
+            dependencies,
+            labels: names.clone(),
+            spans: Default::default(),
+        }
+    }
+
+    /// Runs `future` within the span of `node`.
+    pub(super) fn trace<'a, Err: 'a>(
+        &self,
+        node: NodeIndex,
+        future: TaskFuture<'a, Err>,
+    ) -> TaskFuture<'a, Err> {
+        let mut spans = self.spans.lock().unwrap();
+        let mut parents = self
+            .dependencies
+            .get(&node)
+            .into_iter()
+            .flatten()
+            .filter_map(|parent| spans.get(parent));
+        let parent = parents.next().unwrap_or(&self.run);
+        let links = parents
+            .map(|cx| Link::with_context(cx.span().span_context().clone()))
+            .collect();
+        let name = self
+            .labels
+            .get(&node)
+            .cloned()
+            .unwrap_or_else(|| format!("node {}", node.index()));
+        let span = self
+            .tracer
+            .span_builder(name)
+            .with_links(links)
+            .start_with_context(&*self.tracer, parent);
+        let cx = parent.with_span(span);
+        let _ = spans.insert(node, cx.clone());
+        async move {
+            let output = future.await;
+            if output.is_err() {
+                cx.span().set_status(Status::error("task failed"));
+            }
+            cx.span().end();
+            output
+        }
+        .boxed()
+    }
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Creates the OpenTelemetry spans of runs through `tracer` instead of the global tracer,
+    /// e.g. one from a provider that isn't installed globally.
+    pub fn set_tracer<T>(&mut self, tracer: T)
+    where
+        T: Tracer + Send + Sync + 'static,
+        T::Span: Send + Sync + 'static,
+    {
+        self.layers.tracer = Some(Arc::new(BoxedTracer::new(Box::new(tracer))));
+    }
+}
+
+impl Drop for Tracing {
+    fn drop(&mut self) {
+        self.run.span().end();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Graph;
+    use futures::executor::block_on;
+    use opentelemetry::trace::TracerProvider;
+    use opentelemetry_sdk::trace::InMemorySpanExporter;
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+
+    #[test]
+    fn test_tracing() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter.clone())
+            .build();
+
+        let mut graph = Graph::new();
+        graph.set_tracer(provider.tracer("test"));
+        let root = graph.add_task(|lhs: i32, rhs: i32| async move { lhs + rhs });
+        let lhs = graph.add_parent_task(|| async { 1 }, root, 0).unwrap();
+        let _ = graph.add_parent_task(|| async { 2 }, root, 1).unwrap();
+        graph.set_name(root, "root");
+        graph.set_name(lhs, "lhs");
+        block_on(graph.run());
+        provider.force_flush().unwrap();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let span = |name: &str| spans.iter().find(|span| span.name == name).unwrap();
+        assert_eq!(spans.len(), 4);
+        assert!(spans
+            .iter()
+            .all(|s| s.span_context.trace_id() == span("async_dag::run").span_context.trace_id()));
+        assert_eq!(
+            span("lhs").parent_span_id,
+            span("async_dag::run").span_context.span_id()
+        );
+        assert_eq!(
+            span("root").parent_span_id,
+            span("lhs").span_context.span_id()
+        );
+        assert_eq!(span("root").links.len(), 1);
+        assert_eq!(
+            span("root").links[0].span_context,
+            span("node 2").span_context
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use super::NodeIndex;
+use super::TryGraph;
+use crate::any::downcast;
+use crate::any::DynAny;
+use crate::task::IntoTryTask;
+use crate::task::TryTask;
+use futures::channel::oneshot;
+use futures::FutureExt;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+
+/// A future of a node's output value, created along with the node by [`TryGraph::add_try_task_with_output`].
+///
+/// Resolves when the node completes in a later run, including when its output comes from a cache, a ledger or a replay,
+/// or with [`None`] if it never will, e.g. it failed, was skipped, or the graph was dropped.
+#[derive(Debug)]
+pub struct OutputFuture<T> {
+    receiver: oneshot::Receiver<T>,
+}
+
+impl<T> Future for OutputFuture<T> {
+    type Output = Option<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.receiver.poll_unpin(cx).map(Result::ok)
+    }
+}
+
+/// Sends a clone of a node's output to an [`OutputFuture`], called by the runner when the node completes.
+type Notify<'a> = Box<dyn FnOnce(&DynAny) + Send + Sync + 'a>;
+
+/// The [`Notify`]s of nodes with output futures.
+#[derive(Default)]
+pub(super) struct Outputs<'a>(HashMap<NodeIndex, Notify<'a>>);
+
+impl<'a> Outputs<'a> {
+    /// Sends `output` to `node`'s output future if any.
+    pub(super) fn notify(&mut self, node: NodeIndex, output: &DynAny) {
+        if let Some(notify) = self.0.remove(&node) {
+            notify(output);
+        }
+    }
+
+    /// Drops `node`'s output future sender if any, resolving the future to [`None`].
+    pub(super) fn abandon(&mut self, node: NodeIndex) {
+        let _ = self.0.remove(&node);
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    pub(super) fn shrink_to_fit(&mut self) {
+        self.0.shrink_to_fit();
+    }
+
+    /// Forgets the nodes from `nodes` on.
+    pub(super) fn truncate(&mut self, nodes: usize) {
+        self.0.retain(|node, _| node.index() < nodes);
+    }
+}
+
+impl<'a> std::fmt::Debug for Outputs<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// [`TryGraph::add_try_task`], also returning a future of the node's output value,
+    /// so library code can hand out typed results before the graph is run.
+    pub fn add_try_task_with_output<Args, Ok, T: IntoTryTask<'a, Args, Ok, Err>>(
+        &mut self,
+        task: T,
+    ) -> (NodeIndex, OutputFuture<Ok>) {
+        self.add_task_with_output_impl(task.into_task())
+    }
+
+    pub(super) fn add_task_with_output_impl<T: TryTask<'a, Err = Err> + 'a>(
+        &mut self,
+        task: T,
+    ) -> (NodeIndex, OutputFuture<T::Ok>) {
+        let (sender, receiver) = oneshot::channel();
+        let node = self.add_task_impl(task);
+        let notify: Notify<'a> = Box::new(move |output: &DynAny| {
+            // The runner has checked the output type.
+            let _ = sender.send(downcast::<T::Ok>(output.clone()).ok().unwrap());
+        });
+        let _ = self.outputs.0.insert(node, notify);
+        (node, OutputFuture { receiver })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Graph;
+    use crate::Recording;
+    use crate::RunOptions;
+    use crate::TryGraph;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_output_future() {
+        let mut graph = Graph::new();
+        let (one, output) = graph.add_task_with_output(|| async { 1 });
+        let (double, doubled) = graph.add_task_with_output(|v: i32| async move { v * 2 });
+        graph.update_dependency(one, double, 0).unwrap();
+        block_on(graph.run());
+        assert_eq!(block_on(output), Some(1));
+        assert_eq!(block_on(doubled), Some(2));
+
+        let mut graph = TryGraph::<()>::new();
+        let (_, failing) = graph.add_try_task_with_output(|| async { Err::<i32, _>(()) });
+        let _ = block_on(graph.try_run());
+        assert_eq!(block_on(failing), None);
+    }
+
+    #[test]
+    fn test_output_future_replayed() {
+        let recording = Recording::new();
+        let mut graph = Graph::new();
+        let _ = graph.add_task(|| async { 1 });
+        block_on(graph.run_with(&RunOptions::new().record(recording.clone())));
+
+        // The task isn't run, its output is the recorded one.
+        let mut graph = Graph::new();
+        let (_, output) = graph.add_task_with_output(|| async { 2 });
+        block_on(graph.run_with(&RunOptions::new().replay(&recording)));
+        assert_eq!(block_on(output), Some(1));
+    }
+}
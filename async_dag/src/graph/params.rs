@@ -0,0 +1,127 @@
+use super::NodeIndex;
+use super::TryGraph;
+use crate::error::ErrorWithTask;
+use crate::task::IntoInfallibleTask;
+use crate::task::IntoTryTask;
+use crate::task::TryTask;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// A node's parameters, given to its task as its first input, see [`TryGraph::add_params_try_task`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NodeParams(Arc<HashMap<String, String>>);
+
+impl NodeParams {
+    /// Creates parameters from `params`.
+    pub fn new(params: HashMap<String, String>) -> Self {
+        Self(Arc::new(params))
+    }
+
+    /// Gets the parameter `key` if any.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    /// Parses the parameter `key` as `T`, [`None`] if it's missing.
+    pub fn parse<T: FromStr>(&self, key: &str) -> Option<Result<T, T::Err>> {
+        self.get(key).map(str::parse)
+    }
+
+    /// Gets all parameters.
+    pub fn as_map(&self) -> &HashMap<String, String> {
+        &self.0
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for NodeParams {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self::new(
+            iter.into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        )
+    }
+}
+
+impl<'a, Err: Send + 'a, D> TryGraph<'a, Err, D> {
+    /// Adds a task whose first input is `params`, as a [`NodeParams`],
+    /// so one task can be instantiated many times with different settings.
+    ///
+    /// The parameters are the output of a node added as the task's parent.
+    /// The other inputs are left for dependencies as usual.
+    pub fn add_params_try_task<Args, Ok, T: IntoTryTask<'a, Args, Ok, Err>>(
+        &mut self,
+        params: NodeParams,
+        task: T,
+    ) -> Result<NodeIndex, ErrorWithTask<T::Task>> {
+        self.add_params_task_impl(params, task.into_task())
+    }
+
+    fn add_params_task_impl<T: TryTask<'a, Err = Err> + 'a>(
+        &mut self,
+        params: NodeParams,
+        task: T,
+    ) -> Result<NodeIndex, ErrorWithTask<T>> {
+        self.batch(|graph| {
+            let parent = graph.add_sync_try_task({
+                let params = params.clone();
+                move || Ok(params)
+            });
+            let node = graph.add_child_task_impl(parent, task, 0)?;
+            let _ = graph.params.insert(node, params);
+            Ok(node)
+        })
+    }
+
+    /// Gets the parameters of `node` if it's added with some.
+    pub fn params(&self, node: NodeIndex) -> Option<&NodeParams> {
+        self.params.get(&node)
+    }
+}
+
+impl<'a, D> TryGraph<'a, Infallible, D> {
+    /// Adds an infallible task whose first input is `params`.
+    ///
+    /// See [`TryGraph::add_params_try_task`].
+    pub fn add_params_task<Args, Ok, T: IntoInfallibleTask<'a, Args, Ok>>(
+        &mut self,
+        params: NodeParams,
+        task: T,
+    ) -> Result<NodeIndex, ErrorWithTask<T::Task>> {
+        self.add_params_task_impl(params, task.into_task())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NodeParams;
+    use crate::Graph;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_params() {
+        let mut graph = Graph::new();
+        let input = graph.add_task(|| async { 10 });
+        let scale = |params: NodeParams, v: i32| async move {
+            v * params.parse::<i32>("factor").unwrap().unwrap()
+        };
+        let double = graph
+            .add_params_task(NodeParams::from_iter([("factor", "2")]), scale)
+            .unwrap();
+        let triple = graph
+            .add_params_task(NodeParams::from_iter([("factor", "3")]), scale)
+            .unwrap();
+        graph.update_dependency(input, double, 1).unwrap();
+        graph.update_dependency(input, triple, 1).unwrap();
+        assert_eq!(graph.params(triple).unwrap().get("factor"), Some("3"));
+        assert!(graph
+            .add_params_task(NodeParams::default(), |_: i32| async {})
+            .is_err());
+        assert_eq!(graph.stats().node_count, 5);
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<i32>(double).unwrap(), 20);
+        assert_eq!(graph.get_value::<i32>(triple).unwrap(), 30);
+    }
+}
@@ -0,0 +1,90 @@
+use super::error::Error;
+use super::NodeIndex;
+use super::TryGraph;
+use crate::any::IntoAny;
+use crate::any::TypeInfo;
+use futures::Stream;
+use futures::StreamExt;
+
+impl<'a, Err: Send + 'a, D: Clone + Send + 'a> TryGraph<'a, Err, D> {
+    /// Executes a clone of the graph per item of `inputs`, with at most `in_flight` clones running at the same time.
+    ///
+    /// Every item is given to the first input of `source` by [`TryGraph::set_input`], which must not have a dependency,
+    /// and the value of `output` is taken. The graph itself is the template and is never run,
+    /// so it must be cloneable, see [`TryGraph::try_clone`].
+    ///
+    /// Outputs are yielded in item order, and `inputs` is not polled while `in_flight` clones are running,
+    /// so a slow consumer or slow graphs apply backpressure to the producer.
+    ///
+    /// Returns an error if the first input of `source` isn't of type `I`.
+    /// Every execution **panics** like [`TryGraph::execute`] does.
+    ///
+    /// **Panics** if `in_flight` is `0`, or the graph can't be cloned.
+    pub fn run_pipeline<I: IntoAny, T: Send + 'static>(
+        self,
+        inputs: impl Stream<Item = I> + Send + 'a,
+        source: NodeIndex,
+        output: NodeIndex,
+        in_flight: usize,
+    ) -> Result<impl Stream<Item = Result<T, Err>> + Send + 'a, Error> {
+        assert!(in_flight > 0, "Pipeline must allow some graphs in flight");
+        assert!(
+            self.try_clone().is_some(),
+            "Expecting the pipeline graph to be cloneable"
+        );
+        self.type_check(None, source, 0, TypeInfo::of::<I>())?;
+        Ok(inputs
+            .map(move |input| {
+                // Checked to be cloneable, and the template is never run.
+                let mut graph = self.try_clone().unwrap();
+                graph
+                    .set_input(source, 0, input)
+                    .expect("Input type has been checked");
+                graph.execute(output)
+            })
+            .buffered(in_flight))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::graph::fixtures::Concurrency;
+    use crate::Graph;
+    use futures::executor::block_on;
+    use futures::stream;
+    use futures::StreamExt;
+
+    #[test]
+    fn test_run_pipeline() {
+        let concurrency = Concurrency::default();
+        let mut graph = Graph::new();
+        let tracker = concurrency.clone();
+        let input = graph.add_cloneable_task(move |v: i32| {
+            let tracker = tracker.clone();
+            async move {
+                tracker.track().await;
+                v
+            }
+        });
+        let output = graph.add_cloneable_task(|v: i32| async move { v * 2 });
+        graph.update_dependency(input, output, 0).unwrap();
+        let outputs = graph
+            .run_pipeline::<_, i32>(stream::iter(1..=5), input, output, 2)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        assert_eq!(block_on(outputs), [2, 4, 6, 8, 10]);
+        assert_eq!(concurrency.max(), 2);
+    }
+
+    #[test]
+    fn test_run_pipeline_input_type() {
+        let mut graph = Graph::new();
+        let input = graph.add_cloneable_task(|v: i32| async move { v });
+        assert!(matches!(
+            graph.run_pipeline::<_, i32>(stream::iter(["one"]), input, input, 1),
+            Err(Error::TypeMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,250 @@
+use super::error::RegistryError;
+use super::NodeIndex;
+use super::TopologySpec;
+use super::TryGraph;
+use crate::any::IntoAny;
+use crate::any::TypeInfo;
+use crate::curry::CurriedTask;
+use crate::task::IntoTryTask;
+use crate::task::TryTask;
+use crate::tuple::Tuple;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+
+type Constructor<'a, Err> =
+    Box<dyn Fn(&Value) -> Result<super::DynCurry<'a, Err>, serde_json::Error> + Send + Sync + 'a>;
+
+/// The input and output types of a kind of task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    /// The input types by index.
+    pub inputs: Vec<TypeInfo>,
+    /// The output type.
+    pub output: TypeInfo,
+}
+
+struct Registered<'a, Err> {
+    signature: Signature,
+    constructor: Constructor<'a, Err>,
+}
+
+/// Kinds of tasks by name, each constructed from JSON parameters, so graphs can be assembled at runtime
+/// from kinds registered by any crate.
+pub struct TaskRegistry<'a, Err> {
+    kinds: HashMap<String, Registered<'a, Err>>,
+}
+
+impl<'a, Err> std::fmt::Debug for TaskRegistry<'a, Err> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(
+                self.kinds
+                    .iter()
+                    .map(|(kind, registered)| (kind, &registered.signature)),
+            )
+            .finish()
+    }
+}
+
+impl<'a, Err> Default for TaskRegistry<'a, Err> {
+    fn default() -> Self {
+        Self {
+            kinds: HashMap::new(),
+        }
+    }
+}
+
+impl<'a, Err: 'a> TaskRegistry<'a, Err> {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `kind` as tasks made by `constructor` from parameters deserialized as `P`,
+    /// replacing the previous registration if any.
+    ///
+    /// The signature is declared by the task type, so it's known without constructing any task.
+    pub fn register<P, Args, Ok, T>(
+        &mut self,
+        kind: impl Into<String>,
+        constructor: impl Fn(P) -> T + Send + Sync + 'a,
+    ) where
+        P: DeserializeOwned,
+        Ok: IntoAny,
+        T: IntoTryTask<'a, Args, Ok, Err>,
+    {
+        type Inputs<'a, T, Args, Ok, Err> =
+            <<T as IntoTryTask<'a, Args, Ok, Err>>::Task as TryTask<'a>>::Inputs;
+        let signature = Signature {
+            inputs: (0..Inputs::<T, Args, Ok, Err>::LEN)
+                .map(|index| Inputs::<T, Args, Ok, Err>::type_info(index).unwrap())
+                .collect(),
+            output: TypeInfo::of::<Ok>(),
+        };
+        let constructor: Constructor<'a, Err> = Box::new(move |params| {
+            let params = P::deserialize(params)?;
+            let curry: super::DynCurry<'a, Err> =
+                Box::new(CurriedTask::new(constructor(params).into_task()));
+            Ok(curry)
+        });
+        let _ = self.kinds.insert(
+            kind.into(),
+            Registered {
+                signature,
+                constructor,
+            },
+        );
+    }
+
+    /// Gets the signature of `kind` if it's registered.
+    pub fn signature(&self, kind: &str) -> Option<&Signature> {
+        self.kinds.get(kind).map(|registered| &registered.signature)
+    }
+
+    /// Iterates over registered kinds, in arbitrary order.
+    pub fn kinds(&self) -> impl Iterator<Item = &str> + use<'_, 'a, Err> {
+        self.kinds.keys().map(String::as_str)
+    }
+
+    /// Builds a graph from `spec`, constructing every node by its kind with its parameters in `params`
+    /// by node name, `null` if missing, and attaching edge metadata.
+    ///
+    /// Fails if a node has no kind or an unregistered one, its parameters are invalid,
+    /// its arity or output type disagrees with the kind's signature, or an edge is wired between mismatching types.
+    pub fn build(
+        &self,
+        spec: &TopologySpec,
+        params: &HashMap<String, Value>,
+    ) -> Result<TryGraph<'a, Err>, RegistryError> {
+        let mut graph = TryGraph::new();
+        for (position, node) in spec.nodes.iter().enumerate() {
+            let kind = node
+                .kind
+                .as_deref()
+                .ok_or(RegistryError::MissingKind(position))?;
+            let signature = self
+                .signature(kind)
+                .ok_or_else(|| RegistryError::UnknownKind(kind.to_owned()))?;
+            let arity_mismatch = usize::from(node.arity) > signature.inputs.len();
+            let output_mismatch = node
+                .output_type
+                .as_deref()
+                .is_some_and(|output_type| output_type != signature.output.name());
+            if arity_mismatch || output_mismatch {
+                return Err(RegistryError::Signature {
+                    node: position,
+                    signature: signature.clone(),
+                });
+            }
+            let null = Value::Null;
+            let node_params = node
+                .name
+                .as_ref()
+                .and_then(|name| params.get(name))
+                .unwrap_or(&null);
+            let index = graph.add_registered(self, kind, node_params)?;
+            if let Some(name) = &node.name {
+                graph.set_name(index, name.clone());
+            }
+        }
+        for edge in &spec.edges {
+            let child = NodeIndex::new(edge.child);
+            graph.update_dependency(NodeIndex::new(edge.parent), child, edge.input)?;
+            if let Some(metadata) = &edge.metadata {
+                graph.set_edge_metadata(child, edge.input, metadata.clone());
+            }
+        }
+        Ok(graph)
+    }
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Adds a task of `kind` constructed by `registry` from `params`.
+    ///
+    /// The kind is recorded in the node's [`NodeSpec`](super::NodeSpec), so the graph's topology can be built again.
+    pub fn add_registered(
+        &mut self,
+        registry: &TaskRegistry<'a, Err>,
+        kind: &str,
+        params: &Value,
+    ) -> Result<NodeIndex, RegistryError> {
+        let registered = registry
+            .kinds
+            .get(kind)
+            .ok_or_else(|| RegistryError::UnknownKind(kind.to_owned()))?;
+        let curry = (registered.constructor)(params).map_err(|error| RegistryError::Params {
+            kind: kind.to_owned(),
+            error,
+        })?;
+        let node = self.add_curry(curry);
+        let _ = self.kinds.insert(node, kind.to_owned());
+        Ok(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TaskRegistry;
+    use crate::error::Error;
+    use crate::error::RegistryError;
+    use crate::NodeIndex;
+    use crate::TopologySpec;
+    use crate::TypeInfo;
+    use futures::executor::block_on;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn registry() -> TaskRegistry<'static, String> {
+        let mut registry = TaskRegistry::new();
+        registry.register("constant", |value: i64| move || async move { Ok(value) });
+        registry.register("scale", |factor: i64| {
+            move |value: i64| async move { Ok(value * factor) }
+        });
+        registry.register("describe", |_: ()| {
+            |value: i64| async move { Ok(format!("value is {}", value)) }
+        });
+        registry
+    }
+
+    #[test]
+    fn test_task_registry() {
+        let registry = registry();
+        assert_eq!(
+            registry.signature("scale").unwrap().inputs,
+            [TypeInfo::of::<i64>()]
+        );
+        let spec = TopologySpec::from_dot(
+            "digraph { seed [kind=constant]; double [kind=scale]; seed -> double }",
+        )
+        .unwrap();
+        let params = HashMap::from([
+            ("seed".to_owned(), json!(21)),
+            ("double".to_owned(), json!(2)),
+        ]);
+        let mut graph = registry.build(&spec, &params).unwrap();
+        block_on(graph.try_run()).unwrap();
+        assert_eq!(graph.name(NodeIndex::new(1)), Some("double"));
+        assert_eq!(graph.get_value::<i64>(NodeIndex::new(1)).unwrap(), 42);
+        // Kinds round-trip, so the topology builds the same graph again.
+        let topology = graph.topology();
+        assert_eq!(topology.nodes[0].kind.as_deref(), Some("constant"));
+        assert_eq!(topology.nodes[1].kind.as_deref(), Some("scale"));
+        assert!(registry.build(&topology, &params).is_ok());
+        assert_eq!(registry.kinds().count(), 3);
+
+        assert!(matches!(
+            registry.build(&spec, &HashMap::new()),
+            Err(RegistryError::Params { kind, .. }) if kind == "constant"
+        ));
+        let spec = TopologySpec::from_dot(
+            "digraph { text [kind=describe]; double [kind=scale]; text -> double }",
+        )
+        .unwrap();
+        let params = HashMap::from([("double".to_owned(), json!(2))]);
+        assert!(matches!(
+            registry.build(&spec, &params),
+            Err(RegistryError::Graph(Error::TypeMismatch { .. }))
+        ));
+    }
+}
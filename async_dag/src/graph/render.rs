@@ -0,0 +1,151 @@
+use super::Node;
+use super::NodeIndex;
+use super::TryGraph;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// The state of a node as shown by diagnostics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum State {
+    Pending,
+    Running,
+    Value,
+    Dropped,
+    Failed,
+    Skipped,
+}
+
+impl State {
+    /// A short name, e.g. `"running"`.
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            State::Pending => "pending",
+            State::Running => "running",
+            State::Value => "value",
+            State::Dropped => "dropped",
+            State::Failed => "failed",
+            State::Skipped => "skipped",
+        }
+    }
+}
+
+impl<'a, Err> Node<'a, Err> {
+    pub(super) fn state(&self) -> State {
+        match self {
+            Node::Curry(_) => State::Pending,
+            Node::Running(_) => State::Running,
+            Node::Value { .. } => State::Value,
+            Node::Dropped(_) => State::Dropped,
+            Node::Failed { .. } => State::Failed,
+            Node::Skipped { .. } => State::Skipped,
+        }
+    }
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Renders a human readable listing of nodes by index, each followed by its indented inputs.
+    ///
+    /// A node is shown with its name, output type and state, and an input with its type and the parent wired to it.
+    ///
+    /// ```text
+    /// 0 'load': alloc::string::String [value]
+    /// 1: usize [pending]
+    ///     0: alloc::string::String <- 0 'load'
+    ///     1: usize <- (unwired)
+    /// ```
+    pub fn render_tree(&self) -> String {
+        RenderTree(self).to_string()
+    }
+
+    fn fmt_node(&self, f: &mut Formatter<'_>, node: NodeIndex) -> std::fmt::Result {
+        write!(f, "{}", node.index())?;
+        if let Some(name) = self.name(node) {
+            write!(f, " '{}'", name)?;
+        }
+        Ok(())
+    }
+}
+
+struct RenderTree<'g, 'a, Err, D>(&'g TryGraph<'a, Err, D>);
+
+impl<'g, 'a, Err: 'a, D> Display for RenderTree<'g, 'a, Err, D> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let graph = self.0;
+        for node in graph.dag.graph().node_indices() {
+            let weight = &graph.dag[node].node;
+            graph.fmt_node(f, node)?;
+            write!(
+                f,
+                ": {} [{}",
+                graph.output_type_info(node).name(),
+                weight.state().name()
+            )?;
+            if let Node::Skipped { cause, .. } = weight {
+                if *cause != node {
+                    f.write_str(" by ")?;
+                    graph.fmt_node(f, *cause)?;
+                }
+            }
+            writeln!(f, "]")?;
+
+            let mut dependencies = graph.dependencies(node).into_iter().peekable();
+            // Only pending nodes know their inputs, the others only have dependencies.
+            let num_inputs = match weight {
+                Node::Curry(curry) => curry.num_inputs(),
+                _ => dependencies
+                    .clone()
+                    .last()
+                    .map_or(0, |(index, ..)| index + 1),
+            };
+            for index in 0..num_inputs {
+                write!(f, "    {}", index)?;
+                if let Node::Curry(curry) = weight {
+                    if let Some(type_info) = curry.input_type_info(index) {
+                        write!(f, ": {}", type_info.name())?;
+                    }
+                }
+                f.write_str(" <- ")?;
+                match dependencies.next_if(|(dependency, ..)| *dependency == index) {
+                    Some((_, parent, _)) => graph.fmt_node(f, parent)?,
+                    None => f.write_str("(unwired)")?,
+                }
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Graph;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_render_tree() {
+        let mut graph = Graph::new();
+        let text = graph.add_task(|| async { "ab".to_owned() });
+        graph.set_name(text, "text");
+        let repeat = graph.add_task(|s: String, n: usize| async move { s.repeat(n) });
+        graph.update_dependency(text, repeat, 0).unwrap();
+        assert_eq!(
+            graph.render_tree(),
+            "0 'text': alloc::string::String [pending]\n\
+             1: alloc::string::String [pending]\n    \
+             0: alloc::string::String <- 0 'text'\n    \
+             1: usize <- (unwired)\n"
+        );
+
+        let n = graph.add_task(|| async { 2usize });
+        graph.update_dependency(n, repeat, 1).unwrap();
+        block_on(graph.run());
+        assert_eq!(
+            graph.render_tree(),
+            "0 'text': alloc::string::String [value]\n\
+             1: alloc::string::String [value]\n    \
+             0 <- 0 'text'\n    \
+             1 <- 2\n\
+             2: usize [value]\n"
+        );
+    }
+}
@@ -0,0 +1,204 @@
+use super::NodeIndex;
+use crate::any::DynAny;
+use crate::any::TypeInfo;
+use crate::curry::TaskFuture;
+use futures::future::poll_fn;
+use futures::FutureExt;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Poll;
+use std::task::Waker;
+
+#[derive(Debug)]
+struct Completion {
+    node: NodeIndex,
+    type_info: TypeInfo,
+    value: DynAny,
+}
+
+/// The order nodes complete in during runs with [`RunOptions::record`](super::RunOptions::record),
+/// along with their values, to be replayed by [`RunOptions::replay`](super::RunOptions::replay).
+///
+/// Clones share the same completions, so keep one to inspect or replay after running.
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    completions: Arc<Mutex<Vec<Completion>>>,
+}
+
+impl Recording {
+    /// Creates an empty recording.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the completed nodes and their output types, in completion order.
+    pub fn completions(&self) -> Vec<(NodeIndex, TypeInfo)> {
+        self.completions
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|completion| (completion.node, completion.type_info))
+            .collect()
+    }
+}
+
+/// Records the completion of `future` into `recording`.
+pub(super) fn record<'a, Err: 'a>(
+    recording: &Recording,
+    node: NodeIndex,
+    type_info: TypeInfo,
+    future: TaskFuture<'a, Err>,
+) -> TaskFuture<'a, Err> {
+    let recording = recording.clone();
+    async move {
+        let value = future.await?;
+        recording.completions.lock().unwrap().push(Completion {
+            node,
+            type_info,
+            value: value.clone(),
+        });
+        Ok(value)
+    }
+    .boxed()
+}
+
+#[derive(Debug, Default)]
+struct Cursor {
+    // How many completions have been replayed.
+    position: usize,
+    wakers: Vec<Waker>,
+}
+
+impl Cursor {
+    fn wait(&mut self, waker: &Waker) {
+        if !self.wakers.iter().any(|other| other.will_wake(waker)) {
+            self.wakers.push(waker.clone());
+        }
+    }
+}
+
+/// Drives a run in the order of a [`Recording`].
+#[derive(Debug)]
+pub(super) struct Replay {
+    positions: HashMap<NodeIndex, usize>,
+    // Taken by the stubs, in a mutex as `DynAny` isn't `Sync`.
+    values: Mutex<Vec<Option<DynAny>>>,
+    cursor: Arc<Mutex<Cursor>>,
+}
+
+impl Replay {
+    pub(super) fn new(recording: &Recording) -> Self {
+        let completions = recording.completions.lock().unwrap();
+        Self {
+            positions: completions
+                .iter()
+                .enumerate()
+                .map(|(position, completion)| (completion.node, position))
+                .collect(),
+            values: Mutex::new(
+                completions
+                    .iter()
+                    .map(|completion| Some(completion.value.clone()))
+                    .collect(),
+            ),
+            cursor: Default::default(),
+        }
+    }
+
+    /// Replaces `future` by a stub completing with the recorded value once it's `node`'s turn.
+    ///
+    /// Nodes not recorded run `future` after all recorded ones complete.
+    pub(super) fn stub<'a, Err: 'a>(
+        &self,
+        node: NodeIndex,
+        future: TaskFuture<'a, Err>,
+    ) -> TaskFuture<'a, Err> {
+        let cursor = self.cursor.clone();
+        match self.positions.get(&node) {
+            Some(&position) => {
+                let mut value = self.values.lock().unwrap()[position].take();
+                drop(future);
+                poll_fn(move |cx| {
+                    let mut cursor = cursor.lock().unwrap();
+                    if cursor.position == position {
+                        cursor.position += 1;
+                        for waker in cursor.wakers.drain(..) {
+                            waker.wake();
+                        }
+                        Poll::Ready(Ok(value.take().unwrap()))
+                    } else {
+                        cursor.wait(cx.waker());
+                        Poll::Pending
+                    }
+                })
+                .boxed()
+            }
+            None => {
+                let len = self.positions.len();
+                async move {
+                    poll_fn(|cx| {
+                        let mut cursor = cursor.lock().unwrap();
+                        if cursor.position == len {
+                            Poll::Ready(())
+                        } else {
+                            cursor.wait(cx.waker());
+                            Poll::Pending
+                        }
+                    })
+                    .await;
+                    future.await
+                }
+                .boxed()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Recording;
+    use crate::graph::fixtures::yield_now;
+    use crate::Graph;
+    use crate::NodeIndex;
+    use crate::RunOptions;
+    use futures::executor::block_on;
+
+    fn build(yields: [usize; 3], values: [i32; 3]) -> (Graph<'static>, Vec<NodeIndex>) {
+        let mut graph = Graph::new();
+        let nodes = (0..3)
+            .map(|i| {
+                graph.add_task(move || async move {
+                    for _ in 0..yields[i] {
+                        yield_now().await;
+                    }
+                    values[i]
+                })
+            })
+            .collect();
+        (graph, nodes)
+    }
+
+    #[test]
+    fn test_record_replay() {
+        let recording = Recording::new();
+        let (mut graph, nodes) = build([20, 0, 10], [1, 2, 3]);
+        block_on(graph.run_with(&RunOptions::new().record(recording.clone())));
+        let order = recording
+            .completions()
+            .into_iter()
+            .map(|(node, _)| node)
+            .collect::<Vec<_>>();
+        assert_eq!(order, vec![nodes[1], nodes[2], nodes[0]]);
+
+        // Different timing and values, replayed as recorded.
+        let rerecording = Recording::new();
+        let (mut graph, nodes) = build([0, 10, 20], [0, 0, 0]);
+        let options = RunOptions::new()
+            .replay(&recording)
+            .record(rerecording.clone());
+        block_on(graph.run_with(&options));
+        assert_eq!(rerecording.completions(), recording.completions());
+        assert_eq!(graph.get_value::<i32>(nodes[0]).unwrap(), 1);
+    }
+}
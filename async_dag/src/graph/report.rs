@@ -0,0 +1,251 @@
+use super::Clock;
+use super::NodeIndex;
+use crate::curry::TaskFuture;
+use futures::task::waker;
+use futures::task::ArcWake;
+use futures::FutureExt;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Poll and wake statistics of a node's task future.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NodeStats {
+    /// How many times the future was polled.
+    pub polls: usize,
+    /// How many times the future woke its task.
+    pub wakes: usize,
+    /// The shortest time between two consecutive wakes.
+    pub min_wake_interval: Option<Duration>,
+    /// The time from the first poll until the future completed.
+    pub duration: Option<Duration>,
+    total_wake_interval: Duration,
+    last_wake: Option<Instant>,
+    first_poll: Option<Instant>,
+}
+
+impl NodeStats {
+    /// The average time between two consecutive wakes.
+    pub fn mean_wake_interval(&self) -> Option<Duration> {
+        if self.wakes < 2 {
+            None
+        } else {
+            Some(self.total_wake_interval / (self.wakes - 1) as u32)
+        }
+    }
+
+    fn wake(&mut self, now: Instant) {
+        self.wakes += 1;
+        if let Some(last_wake) = self.last_wake {
+            let interval = now.saturating_duration_since(last_wake);
+            self.total_wake_interval += interval;
+            self.min_wake_interval = Some(
+                self.min_wake_interval
+                    .map_or(interval, |min| min.min(interval)),
+            );
+        }
+        self.last_wake = Some(now);
+    }
+}
+
+/// Work done by the runner itself rather than by tasks, summed over runs.
+#[cfg(feature = "perf-counters")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Overhead {
+    /// How many times the runner waited for a running node to complete or an interrupt.
+    pub iterations: usize,
+    /// How many values were handed to children.
+    pub curries: usize,
+    /// How many boxes the runner allocated, one per started task future and one per boxed value cloned for a child.
+    pub allocations: usize,
+}
+
+#[cfg(feature = "perf-counters")]
+impl std::ops::AddAssign for Overhead {
+    fn add_assign(&mut self, rhs: Self) {
+        self.iterations += rhs.iterations;
+        self.curries += rhs.curries;
+        self.allocations += rhs.allocations;
+    }
+}
+
+/// Per node statistics collected during runs with [`RunOptions::report`](super::RunOptions::report).
+///
+/// Clones share the same statistics, so keep one to inspect after running.
+#[derive(Debug, Clone, Default)]
+pub struct RunReport {
+    nodes: Arc<Mutex<HashMap<NodeIndex, NodeStats>>>,
+    #[cfg(feature = "perf-counters")]
+    overhead: Arc<Mutex<Overhead>>,
+}
+
+impl RunReport {
+    /// Creates an empty report.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the statistics of `node` if it has started running.
+    pub fn node(&self, node: NodeIndex) -> Option<NodeStats> {
+        self.nodes.lock().unwrap().get(&node).copied()
+    }
+
+    /// Gets the statistics of all started nodes.
+    pub fn nodes(&self) -> HashMap<NodeIndex, NodeStats> {
+        self.nodes.lock().unwrap().clone()
+    }
+
+    /// Gets the runner's own work, to compare with task time or detect regressions.
+    #[cfg(feature = "perf-counters")]
+    pub fn overhead(&self) -> Overhead {
+        *self.overhead.lock().unwrap()
+    }
+
+    #[cfg(feature = "perf-counters")]
+    pub(super) fn add_overhead(&self, overhead: Overhead) {
+        *self.overhead.lock().unwrap() += overhead;
+    }
+
+    fn update(&self, node: NodeIndex, f: impl FnOnce(&mut NodeStats)) {
+        f(self.nodes.lock().unwrap().entry(node).or_default())
+    }
+}
+
+/// Records statistics of `future` into `report`.
+pub(super) fn instrument<'a, Err: 'a>(
+    report: &RunReport,
+    clock: &Arc<dyn Clock>,
+    node: NodeIndex,
+    future: TaskFuture<'a, Err>,
+) -> TaskFuture<'a, Err> {
+    Instrumented {
+        future,
+        recorder: Arc::new(Recorder {
+            report: report.clone(),
+            clock: clock.clone(),
+            node,
+            waker: Mutex::new(None),
+        }),
+    }
+    .boxed()
+}
+
+struct Recorder {
+    report: RunReport,
+    clock: Arc<dyn Clock>,
+    node: NodeIndex,
+    // The waker of the latest poll.
+    waker: Mutex<Option<std::task::Waker>>,
+}
+
+impl ArcWake for Recorder {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        let now = arc_self.clock.now();
+        arc_self
+            .report
+            .update(arc_self.node, |stats| stats.wake(now));
+        if let Some(waker) = &*arc_self.waker.lock().unwrap() {
+            waker.wake_by_ref();
+        }
+    }
+}
+
+struct Instrumented<'a, Err> {
+    future: TaskFuture<'a, Err>,
+    recorder: Arc<Recorder>,
+}
+
+impl<'a, Err> Future for Instrumented<'a, Err> {
+    type Output = <TaskFuture<'a, Err> as Future>::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let now = self.recorder.clock.now();
+        self.recorder.report.update(self.recorder.node, |stats| {
+            stats.polls += 1;
+            let _ = stats.first_poll.get_or_insert(now);
+        });
+        *self.recorder.waker.lock().unwrap() = Some(cx.waker().clone());
+        let waker = waker(self.recorder.clone());
+        let poll = self.future.poll_unpin(&mut Context::from_waker(&waker));
+        if poll.is_ready() {
+            let now = self.recorder.clock.now();
+            self.recorder.report.update(self.recorder.node, |stats| {
+                stats.duration = stats
+                    .first_poll
+                    .map(|first_poll| now.saturating_duration_since(first_poll));
+            });
+        }
+        poll
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::fixtures::yield_now;
+    use crate::Graph;
+    use crate::RunOptions;
+    use crate::RunReport;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_run_report() {
+        let mut graph = Graph::new();
+        let ready = graph.add_task(|| async {});
+        let yielding = graph.add_task(|| async {
+            for _ in 0..3 {
+                yield_now().await;
+            }
+        });
+        let report = RunReport::new();
+        block_on(graph.run_with(&RunOptions::new().report(report.clone())));
+
+        let stats = report.node(ready).unwrap();
+        assert_eq!((stats.polls, stats.wakes), (1, 0));
+        assert_eq!(stats.mean_wake_interval(), None);
+        let stats = report.node(yielding).unwrap();
+        assert_eq!((stats.polls, stats.wakes), (4, 3));
+        assert!(stats.min_wake_interval.is_some());
+        assert!(stats.duration.is_some());
+    }
+
+    #[cfg(feature = "perf-counters")]
+    #[test]
+    fn test_overhead() {
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        let _ = graph.add_child_task(one, |_: i32| async {}, 0).unwrap();
+        let _ = graph.add_child_task(one, |_: i32| async {}, 0).unwrap();
+        let report = RunReport::new();
+        block_on(graph.run_with(&RunOptions::new().report(report.clone())));
+
+        let overhead = report.overhead();
+        // Both children complete in the same step.
+        assert_eq!(overhead.iterations, 2);
+        assert_eq!(overhead.curries, 2);
+        // One per task future, the `i32`s are stored inline.
+        assert_eq!(overhead.allocations, 3);
+    }
+
+    #[cfg(feature = "perf-counters")]
+    #[test]
+    fn test_overhead_drop_consumed() {
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { "one".to_owned() });
+        let _ = graph.add_child_task(one, |_: String| async {}, 0).unwrap();
+        let _ = graph.add_child_task(one, |_: String| async {}, 0).unwrap();
+        let report = RunReport::new();
+        let options = RunOptions::new()
+            .report(report.clone())
+            .drop_consumed_values();
+        block_on(graph.run_with(&options));
+
+        // The last child takes the value itself.
+        assert_eq!(report.overhead().allocations, 4);
+    }
+}
@@ -0,0 +1,161 @@
+use super::checkpoint::restore;
+use super::error::Error;
+use super::runner::Admission;
+use super::NodeIndex;
+use super::TryGraph;
+use std::collections::HashMap;
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Defines a resource pool named `name` with `capacity` units, replacing the previous one if any.
+    ///
+    /// A node requiring some units of the resource only starts running when they're available,
+    /// and gives them back on completion.
+    ///
+    /// Returns [`Error::ExceedsCapacity`] with the first node requiring more than `capacity` units if any,
+    /// leaving the previous capacity.
+    pub fn define_resource(
+        &mut self,
+        name: impl Into<String>,
+        capacity: usize,
+    ) -> Result<(), Error> {
+        let name = name.into();
+        for (node, requirements) in &self.schedule.requirements {
+            for (resource, amount) in requirements {
+                if *resource == name && *amount > capacity {
+                    return Err(Error::ExceedsCapacity {
+                        node: *node,
+                        resource: name,
+                        amount: *amount,
+                        capacity,
+                    });
+                }
+            }
+        }
+        let previous = self.schedule.resources.insert(name.clone(), capacity);
+        self.record(move |graph| restore(&mut graph.schedule.resources, name, previous));
+        Ok(())
+    }
+
+    /// Makes `node` require `amount` units of resource `name`, replacing the previous requirement if any.
+    ///
+    /// Returns [`Error::NoSuchResource`] if the resource is not defined,
+    /// or [`Error::ExceedsCapacity`] if `amount` exceeds its capacity.
+    ///
+    /// **Panics** if `node` does not exist within the graph.
+    pub fn require(&mut self, node: NodeIndex, name: &str, amount: usize) -> Result<(), Error> {
+        assert!(self.dag.node_weight(node).is_some());
+        let capacity = *self
+            .schedule
+            .resources
+            .get(name)
+            .ok_or_else(|| Error::NoSuchResource(name.to_owned()))?;
+        if amount > capacity {
+            return Err(Error::ExceedsCapacity {
+                node,
+                resource: name.to_owned(),
+                amount,
+                capacity,
+            });
+        }
+        let previous = self.schedule.requirements.get(&node).cloned();
+        let requirements = self.schedule.requirements.entry(node).or_default();
+        requirements.retain(|(resource, _)| resource != name);
+        requirements.push((name.to_owned(), amount));
+        self.record(move |graph| restore(&mut graph.schedule.requirements, node, previous));
+        Ok(())
+    }
+}
+
+/// Enforces resource requirements.
+pub(super) struct Resources<'g> {
+    requirements: &'g HashMap<NodeIndex, Vec<(String, usize)>>,
+    available: HashMap<&'g str, usize>,
+}
+
+impl<'g> Resources<'g> {
+    pub(super) fn new(
+        resources: &'g HashMap<String, usize>,
+        requirements: &'g HashMap<NodeIndex, Vec<(String, usize)>>,
+    ) -> Self {
+        Self {
+            requirements,
+            available: resources
+                .iter()
+                .map(|(name, capacity)| (name.as_str(), *capacity))
+                .collect(),
+        }
+    }
+}
+
+impl<'g> Admission for Resources<'g> {
+    fn admit(&mut self, node: NodeIndex) -> bool {
+        let requirements = match self.requirements.get(&node) {
+            Some(requirements) => requirements,
+            None => return true,
+        };
+        if requirements
+            .iter()
+            .any(|(name, amount)| self.available[name.as_str()] < *amount)
+        {
+            return false;
+        }
+        for (name, amount) in requirements {
+            *self.available.get_mut(name.as_str()).unwrap() -= amount;
+        }
+        true
+    }
+
+    fn release(&mut self, node: NodeIndex) {
+        for (name, amount) in self.requirements.get(&node).into_iter().flatten() {
+            *self.available.get_mut(name.as_str()).unwrap() += amount;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::graph::fixtures::Concurrency;
+    use crate::Graph;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_resource() {
+        let concurrency = Concurrency::default();
+        let mut graph = Graph::new();
+        graph.define_resource("db_conns", 4).unwrap();
+        for _ in 0..3 {
+            let node = graph.add_task(|| concurrency.clone().track());
+            graph.require(node, "db_conns", 2).unwrap();
+        }
+        block_on(graph.run());
+        assert_eq!(concurrency.max(), 2);
+    }
+
+    #[test]
+    fn test_resource_capacity() {
+        let mut graph = Graph::new();
+        let node = graph.add_task(|| async {});
+        assert!(matches!(
+            graph.require(node, "db_conns", 2),
+            Err(Error::NoSuchResource(name)) if name == "db_conns"
+        ));
+        graph.define_resource("db_conns", 1).unwrap();
+        assert!(matches!(
+            graph.require(node, "db_conns", 2),
+            Err(Error::ExceedsCapacity {
+                amount: 2,
+                capacity: 1,
+                ..
+            })
+        ));
+        graph.define_resource("db_conns", 2).unwrap();
+        graph.require(node, "db_conns", 2).unwrap();
+        assert!(matches!(
+            graph.define_resource("db_conns", 1),
+            Err(Error::ExceedsCapacity { node: n, amount: 2, capacity: 1, .. }) if n == node
+        ));
+        // The previous capacity is kept.
+        block_on(graph.run());
+    }
+}
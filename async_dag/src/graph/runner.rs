@@ -1,18 +1,37 @@
 use crate::any::DynAny;
 use crate::any::TypeInfo;
 use crate::curry::TaskFuture;
+use crate::graph::adapter::Adapters;
+use crate::graph::adapter::Catch;
+use crate::graph::adapter::CatchError;
+use crate::graph::error::DependencyError;
+use crate::graph::error::RunError;
+use crate::graph::output::Outputs;
+use crate::graph::Clock;
 use crate::graph::Edge;
+use crate::graph::Index;
 use crate::graph::Node;
 use crate::graph::NodeIndex;
+use crate::graph::NodeWeight;
+#[cfg(feature = "perf-counters")]
+use crate::graph::Overhead;
+use crate::graph::RunHandle;
+use crate::tuple::TakeError;
 use daggy::petgraph::visit::EdgeRef;
 use daggy::petgraph::visit::IntoEdgesDirected;
 use daggy::petgraph::Direction;
 use daggy::Dag;
-use futures::future::select_all;
+use futures::future::pending;
+use futures::future::poll_fn;
+use futures::future::select;
+use futures::future::Either;
 use futures::FutureExt;
+use std::collections::VecDeque;
 use std::future::Future;
 use std::mem::swap;
 use std::task::Poll;
+use std::time::Duration;
+use std::time::Instant;
 
 struct RunningNode<'a, Err> {
     index: NodeIndex,
@@ -33,8 +52,23 @@ impl<'a, Err> Future for RunningNode<'a, Err> {
     }
 }
 
+/// Yields to the executor once.
+pub(super) async fn yield_now() {
+    let mut yielded = false;
+    poll_fn(|cx| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await
+}
+
 // Puts `node` to running if it contains a ready [Curry], doesn't change it otherwise.
-fn call_node<'a, Err>(node: &mut Node<'a, Err>) -> Option<TaskFuture<'a, Err>> {
+fn call_node<'a, Err>(node: &mut Node<'a, Err>) -> Result<Option<TaskFuture<'a, Err>>, TakeError> {
     // Make a placeholder and swap `node` out.
     let mut owned_node = Node::Running(TypeInfo::of::<()>());
     swap(node, &mut owned_node);
@@ -42,108 +76,634 @@ fn call_node<'a, Err>(node: &mut Node<'a, Err>) -> Option<TaskFuture<'a, Err>> {
     if let Node::Curry(curry) = owned_node {
         if curry.ready() {
             *node = Node::Running(curry.output_type_info());
-            Some(curry.call().unwrap())
+            curry.call().map(Some)
         } else {
             *node = Node::Curry(curry);
-            None
+            Ok(None)
         }
     } else {
         *node = owned_node;
+        Ok(None)
+    }
+}
+
+/// A function applied to every task future before it's polled.
+pub type Wrap<'task, 'graph, Err> =
+    &'graph (dyn Fn(NodeIndex, TaskFuture<'task, Err>) -> TaskFuture<'task, Err> + Sync + 'graph);
+
+/// Decides when ready nodes may start running.
+pub trait Admission {
+    /// Returns `true` and takes what `node` needs if it may start now.
+    fn admit(&mut self, node: NodeIndex) -> bool;
+
+    /// Gives back what `node` has taken when it was admitted.
+    fn release(&mut self, node: NodeIndex);
+
+    /// When a node refused since the last call may be admitted without any release.
+    fn retry_at(&mut self) -> Option<Instant> {
         None
     }
+
+    /// Fills `order` with the order `ready` nodes should be offered in, as positions into `ready`,
+    /// returning `false` to keep it.
+    ///
+    /// `order` is reused between calls and is empty when given.
+    fn order(&mut self, _ready: &[NodeIndex], _order: &mut Vec<usize>) -> bool {
+        false
+    }
+}
+
+/// Admits a node only if both [`Admission`]s do.
+impl<A: Admission, B: Admission> Admission for (A, B) {
+    fn admit(&mut self, node: NodeIndex) -> bool {
+        if !self.0.admit(node) {
+            return false;
+        }
+        if !self.1.admit(node) {
+            self.0.release(node);
+            return false;
+        }
+        true
+    }
+
+    fn release(&mut self, node: NodeIndex) {
+        self.0.release(node);
+        self.1.release(node);
+    }
+
+    fn retry_at(&mut self) -> Option<Instant> {
+        match (self.0.retry_at(), self.1.retry_at()) {
+            (Some(lhs), Some(rhs)) => Some(lhs.min(rhs)),
+            (lhs, rhs) => lhs.or(rhs),
+        }
+    }
+
+    fn order(&mut self, ready: &[NodeIndex], order: &mut Vec<usize>) -> bool {
+        self.0.order(ready, order) || self.1.order(ready, order)
+    }
+}
+
+/// How the runner treats completed and running nodes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Policy {
+    /// Drop values once all children are curried.
+    pub drop_consumed: bool,
+    /// Rotate where polling starts on every step.
+    pub fair: bool,
+    /// Keep failed nodes and skip their descendants instead of aborting.
+    pub continue_on_error: bool,
+    /// Yield to the executor after currying this many children of a completed node.
+    pub yield_budget: Option<usize>,
+    /// How long running nodes may keep running after a shutdown.
+    pub grace_period: Duration,
+}
+
+/// Something other than a running node completing.
+enum Interrupt {
+    /// Pending nodes may be admitted now.
+    Retry,
+    /// Subtrees rooted at these nodes are cancelled.
+    Cancel(Vec<NodeIndex>),
+    /// No more nodes should start.
+    Shutdown,
+    /// Running nodes should be dropped after a shutdown.
+    GraceElapsed,
 }
 
 /// The async DAG driver algorithm.
-pub struct Runner<'task, 'graph, Err> {
+pub struct Runner<'task, 'graph, Err, D> {
     // We only modify node weights inside `node_graph`, don't change its structure.
-    node_graph: &'graph mut Dag<Node<'task, Err>, Edge>,
+    node_graph: &'graph mut Dag<NodeWeight<'task, Err, D>, Edge, Index>,
     // `edge_graph` has the same structure as `node_graph`,
     // so we can access connection information and modify node weights simutaneously.
-    edge_graph: Dag<(), Edge>,
+    edge_graph: Dag<(), Edge, Index>,
+    adapters: &'graph Adapters<'task>,
+    // How many parents each node is still waiting for, so readiness is only checked once they're all fed.
+    remaining: Vec<usize>,
+    // Ready nodes waiting to be admitted, in the order they became ready.
+    pending: VecDeque<RunningNode<'task, Err>>,
+    // Buffers for ordering pending nodes, reused by every admission.
+    ready: Vec<NodeIndex>,
+    order: Vec<usize>,
+    reordered: Vec<Option<RunningNode<'task, Err>>>,
     running: Vec<RunningNode<'task, Err>>,
+    wrap: Wrap<'task, 'graph, Err>,
+    admission: &'graph mut (dyn Admission + Send + 'graph),
+    clock: &'graph dyn Clock,
+    handle: Option<&'graph RunHandle>,
+    // When pending nodes should be offered to `admission` again.
+    retry_at: Option<Instant>,
+    policy: Policy,
+    // How far running nodes are rotated on the next step in fair mode.
+    rotation: usize,
+    // When running nodes are dropped, after a shutdown.
+    deadline: Option<Instant>,
+    // A ready node failing to be called on creation, returned when run.
+    error: Option<DependencyError>,
+    // Converts errors given to error and result dependencies.
+    catch_error: Option<&'graph CatchError<Err>>,
+    // Senders of output futures, taken when their nodes complete, fail or are skipped.
+    outputs: Option<&'graph mut Outputs<'task>>,
+    #[cfg(feature = "perf-counters")]
+    pub(super) overhead: Overhead,
 }
 
-impl<'task, 'graph, Err> Runner<'task, 'graph, Err> {
+impl<'task, 'graph, Err, D> Drop for Runner<'task, 'graph, Err, D> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle {
+            handle.finish_run();
+        }
+        // Nodes left running are forever lost.
+        if let Some(outputs) = &mut self.outputs {
+            for node in self.running.iter().chain(&self.pending) {
+                outputs.abandon(node.index);
+            }
+        }
+    }
+}
+
+impl<'task, 'graph, Err, D> Runner<'task, 'graph, Err, D> {
     /// Creates a new runner from a [Graph].
     ///
     /// The `graph` must have been type checked.
     /// If dropped before running completes, some tasks will be cancelled and forever lost.
-    pub fn new(graph: &'graph mut Dag<Node<'task, Err>, Edge>) -> Self {
-        let mut running = vec![];
+    pub fn new(
+        graph: &'graph mut Dag<NodeWeight<'task, Err, D>, Edge, Index>,
+        adapters: &'graph Adapters<'task>,
+        wrap: Wrap<'task, 'graph, Err>,
+        admission: &'graph mut (dyn Admission + Send + 'graph),
+        clock: &'graph dyn Clock,
+        handle: Option<&'graph RunHandle>,
+        policy: Policy,
+    ) -> Self {
+        let mut pending = VecDeque::new();
+        let mut error = None;
 
         for index in 0..graph.node_count() {
             let index = NodeIndex::new(index);
-            let node = graph.node_weight_mut(index).unwrap();
-            if let Some(future) = call_node(node) {
-                running.push(RunningNode { index, future });
+            let node = &mut graph[index].node;
+            match call_node(node) {
+                Ok(Some(future)) => pending.push_back(RunningNode { index, future }),
+                Ok(None) => {}
+                Err(take_error) => {
+                    error = Some(DependencyError::Call {
+                        node: index,
+                        error: take_error,
+                    });
+                    break;
+                }
             }
         }
 
+        #[cfg(feature = "perf-counters")]
+        let started = pending.len();
         let edge_graph = graph.map(|_, _| (), |_, edge| *edge);
+        // Completed parents never feed their children again.
+        let remaining = (0..graph.node_count())
+            .map(|index| {
+                edge_graph
+                    .edges_directed(NodeIndex::new(index), Direction::Incoming)
+                    .filter(|edge| {
+                        matches!(graph[edge.source()].node, Node::Curry(_) | Node::Running(_))
+                    })
+                    .count()
+            })
+            .collect();
 
-        Self {
+        let mut runner = Self {
             node_graph: graph,
             edge_graph,
-            running,
+            adapters,
+            remaining,
+            pending,
+            ready: vec![],
+            order: vec![],
+            reordered: vec![],
+            running: vec![],
+            wrap,
+            admission,
+            clock,
+            handle,
+            retry_at: None,
+            policy,
+            rotation: 0,
+            deadline: None,
+            error,
+            catch_error: None,
+            outputs: None,
+            #[cfg(feature = "perf-counters")]
+            overhead: Overhead {
+                allocations: started,
+                ..Default::default()
+            },
+        };
+        runner.admit();
+        runner
+    }
+
+    /// Gives the errors of nodes to their error and result dependencies, converted by `catch_error`.
+    pub(super) fn catch_error(&mut self, catch_error: &'graph CatchError<Err>) {
+        self.catch_error = Some(catch_error);
+    }
+
+    /// Sends the outputs of completed nodes to their [`OutputFuture`](super::OutputFuture)s by `outputs`.
+    pub(super) fn notify_outputs(&mut self, outputs: &'graph mut Outputs<'task>) {
+        self.outputs = Some(outputs);
+    }
+
+    /// Moves admitted pending nodes to running.
+    fn admit(&mut self) {
+        if self.deadline.is_some() {
+            // Shutting down, nothing starts anymore.
+            let pending = self
+                .pending
+                .iter()
+                .map(|node| node.index)
+                .collect::<Vec<_>>();
+            for node in pending {
+                self.cancel_subtree(node);
+            }
         }
+        self.ready.clear();
+        self.ready
+            .extend(self.pending.iter().map(|node| node.index));
+        self.order.clear();
+        if !self.ready.is_empty() && self.admission.order(&self.ready, &mut self.order) {
+            self.reordered.extend(self.pending.drain(..).map(Some));
+            self.pending.extend(
+                self.order
+                    .iter()
+                    .map(|position| self.reordered[*position].take().unwrap()),
+            );
+            self.reordered.clear();
+        }
+        let mut position = 0;
+        while position < self.pending.len() {
+            let index = self.pending[position].index;
+            if self.admission.admit(index) {
+                let node = self.pending.remove(position).unwrap();
+                self.running.push(RunningNode {
+                    index,
+                    future: (self.wrap)(index, node.future),
+                });
+            } else {
+                position += 1;
+            }
+        }
+        self.retry_at = self.admission.retry_at();
     }
 
     /// Runs the algorithm.
     ///
     /// If the returned future is dropped before completion or client error happens,
     /// some tasks will be cancelled and forever lost.
-    pub async fn run(&mut self) -> Result<(), Err> {
-        while !self.running.is_empty() {
-            self.step().await?;
+    pub async fn run(&mut self) -> Result<(), RunError<Err>> {
+        if let Some(error) = self.error.take() {
+            return Err(RunError::Dependency(error));
+        }
+        while !self.running.is_empty() || !self.pending.is_empty() {
+            #[cfg(feature = "perf-counters")]
+            {
+                self.overhead.iterations += 1;
+            }
+            if self.running.is_empty() {
+                assert!(self.retry_at.is_some(), "Ready nodes can never be admitted");
+                let interrupt = self.interrupt().await;
+                self.handle(interrupt);
+            } else {
+                self.step().await?;
+            }
         }
         Ok(())
     }
 
-    /// Polls until one running node is completed.
-    ///
-    /// Curries dependent nodes and returns early on error.
-    async fn step(&mut self) -> Result<(), Err> {
-        // Swap out `self.running` for `select_all`.
-        let mut running = vec![];
-        swap(&mut self.running, &mut running);
+    /// Completes when something other than a running node needs attention.
+    fn interrupt(&self) -> impl Future<Output = Interrupt> {
+        let retry = match self.retry_at {
+            Some(retry_at) => self
+                .clock
+                .sleep(retry_at.saturating_duration_since(self.clock.now())),
+            None => pending().boxed(),
+        };
+        let cancelled = match self.handle {
+            Some(handle) => handle.cancelled(),
+            None => pending().boxed(),
+        };
+        let shutdown = match (self.handle, self.deadline) {
+            (_, Some(deadline)) => self
+                .clock
+                .sleep(deadline.saturating_duration_since(self.clock.now()))
+                .map(|_| Interrupt::GraceElapsed)
+                .boxed(),
+            (Some(handle), None) => handle.on_shutdown().map(|_| Interrupt::Shutdown).boxed(),
+            (None, None) => pending().boxed(),
+        };
+        let interrupt = select(retry, cancelled).map(|either| match either {
+            Either::Left(_) => Interrupt::Retry,
+            Either::Right((cancelled, _)) => Interrupt::Cancel(cancelled),
+        });
+        select(interrupt, shutdown).map(|either| either.factor_first().0)
+    }
 
-        // If client error happens, return early and drop running futures.
-        let ((node_index, result), _, running) = select_all(running).await;
-        let output = result?;
+    fn handle(&mut self, interrupt: Interrupt) {
+        match interrupt {
+            Interrupt::Retry => {}
+            Interrupt::Cancel(nodes) => {
+                for node in nodes {
+                    self.cancel_subtree(node);
+                }
+            }
+            Interrupt::Shutdown => {
+                self.deadline = Some(self.clock.now() + self.policy.grace_period);
+                if self.policy.grace_period.is_zero() {
+                    self.drop_running();
+                }
+            }
+            Interrupt::GraceElapsed => self.drop_running(),
+        }
+        self.admit();
+    }
 
-        // Assign back to `self.running`.
-        self.running = running;
+    /// Cancels the subtrees of all running nodes.
+    fn drop_running(&mut self) {
+        let running = self
+            .running
+            .iter()
+            .map(|node| node.index)
+            .collect::<Vec<_>>();
+        for node in running {
+            self.cancel_subtree(node);
+        }
+    }
 
-        // Traverse outgoing edges of completed node.
-        for edge in self
+    /// Sets `node` and its descendants to skipped unless they're completed, dropping their futures.
+    fn cancel_subtree(&mut self, node: NodeIndex) {
+        self.skip(vec![node], node);
+    }
+
+    /// Sets `nodes` and their descendants to skipped by `cause` unless they're completed, dropping their futures.
+    fn skip(&mut self, nodes: Vec<NodeIndex>, cause: NodeIndex) {
+        let mut stack = nodes;
+        while let Some(index) = stack.pop() {
+            let node = &mut self.node_graph[index].node;
+            let type_info = match node {
+                Node::Curry(curry) => curry.output_type_info(),
+                Node::Running(type_info) => {
+                    if let Some(position) = self.running.iter().position(|node| node.index == index)
+                    {
+                        drop(self.running.swap_remove(position));
+                        self.admission.release(index);
+                    }
+                    self.pending.retain(|node| node.index != index);
+                    *type_info
+                }
+                Node::Value { .. }
+                | Node::Dropped(_)
+                | Node::Failed { .. }
+                | Node::Skipped { .. } => continue,
+            };
+            *node = Node::Skipped { type_info, cause };
+            if let Some(outputs) = &mut self.outputs {
+                outputs.abandon(index);
+            }
+            stack.extend(
+                self.edge_graph
+                    .edges_directed(index, Direction::Outgoing)
+                    .map(|edge| edge.target()),
+            );
+        }
+    }
+
+    /// Whether `node` has any error or result dependency.
+    fn catches(&self, node: NodeIndex) -> bool {
+        self.catch_error.is_some()
+            && self
+                .edge_graph
+                .edges_directed(node, Direction::Outgoing)
+                .any(|edge| self.adapters.catch(edge.target(), *edge.weight()).is_some())
+    }
+
+    /// Sets running `node` to failed, gives `error` to its error and result dependencies
+    /// and skips its other descendants.
+    fn fail(&mut self, node: NodeIndex, error: Err) -> Result<(), RunError<Err>> {
+        let type_info = match &self.node_graph[node].node {
+            Node::Running(type_info) => *type_info,
+            _ => panic!("Expecting running state"),
+        };
+        let (handlers, children): (Vec<_>, Vec<_>) = self
             .edge_graph
-            .edges_directed(node_index, Direction::Outgoing)
-        {
-            let child_index = edge.target();
-            let child_node = self.node_graph.node_weight_mut(child_index).unwrap();
+            .edges_directed(node, Direction::Outgoing)
+            .map(|edge| (edge.target(), *edge.weight()))
+            .partition(|(child, index)| self.adapters.catch(*child, *index).is_some());
+        for (handler, index) in handlers {
+            let input_type_info = match &self.node_graph[handler].node {
+                Node::Curry(curry) => curry.input_type_info(index).unwrap(),
+                _ => continue,
+            };
+            // Catching dependencies are only set along with their conversions.
+            let into_any = self.catch_error.unwrap()[&input_type_info.id()];
+            #[cfg(feature = "perf-counters")]
+            {
+                self.overhead.allocations += 1;
+            }
+            self.feed(node, input_type_info, handler, index, into_any(&error))?;
+        }
+        self.node_graph[node].node = Node::Failed { error, type_info };
+        if let Some(outputs) = &mut self.outputs {
+            outputs.abandon(node);
+        }
+        self.skip(children.into_iter().map(|(child, _)| child).collect(), node);
+        Ok(())
+    }
 
-            if let Node::Curry(curry) = child_node {
-                let input_index = *edge.weight();
-                curry.curry(input_index, output.clone()).unwrap();
+    /// Curries `value` from `parent` into `child` at `index`, and queues `child` if it becomes ready.
+    fn feed(
+        &mut self,
+        parent: NodeIndex,
+        type_info: TypeInfo,
+        child: NodeIndex,
+        index: Edge,
+        value: DynAny,
+    ) -> Result<(), RunError<Err>> {
+        let child_node = &mut self.node_graph[child].node;
+        if let Node::Curry(curry) = child_node {
+            if let Err(error) = curry.curry(index, value) {
+                return Err(RunError::Dependency(DependencyError::Curry {
+                    parent,
+                    child,
+                    index,
+                    output: type_info,
+                    kind: error.kind,
+                }));
             }
+            #[cfg(feature = "perf-counters")]
+            {
+                self.overhead.curries += 1;
+            }
+        }
 
-            if let Some(future) = call_node(child_node) {
-                self.running.push(RunningNode {
-                    index: child_index,
-                    future,
-                });
+        let remaining = &mut self.remaining[child.index()];
+        debug_assert!(*remaining > 0);
+        *remaining -= 1;
+        if *remaining > 0 {
+            return Ok(());
+        }
+        let called = call_node(child_node)
+            .map_err(|error| RunError::Dependency(DependencyError::Call { node: child, error }))?;
+        if let Some(future) = called {
+            #[cfg(feature = "perf-counters")]
+            {
+                self.overhead.allocations += 1;
+            }
+            self.pending.push_back(RunningNode {
+                index: child,
+                future,
+            });
+        }
+        Ok(())
+    }
+
+    /// Polls until running nodes are completed or an interrupt is handled.
+    ///
+    /// All nodes completed by the time of polling are processed in one step,
+    /// currying dependent nodes, and returns early on error.
+    async fn step(&mut self) -> Result<(), RunError<Err>> {
+        // Swap out `self.running` to poll it while handling interrupts.
+        let mut running = vec![];
+        swap(&mut self.running, &mut running);
+        if self.policy.fair {
+            // Running nodes are polled in order, favoring the front.
+            let start = self.rotation % running.len();
+            running.rotate_left(start);
+            self.rotation = self.rotation.wrapping_add(1);
+        }
+
+        let completed = poll_fn(|cx| {
+            let mut completed = vec![];
+            running.retain_mut(|node| match node.poll_unpin(cx) {
+                Poll::Ready(output) => {
+                    completed.push(output);
+                    false
+                }
+                Poll::Pending => true,
+            });
+            if completed.is_empty() {
+                Poll::Pending
+            } else {
+                Poll::Ready(completed)
+            }
+        });
+        let completed = if self.retry_at.is_none() && self.handle.is_none() {
+            Ok(completed.await)
+        } else {
+            match select(completed, self.interrupt()).await {
+                Either::Left((completed, _)) => Ok(completed),
+                Either::Right((interrupt, _)) => Err(interrupt),
             }
+        };
+        // Assign back to `self.running`.
+        self.running = running;
+        let completed = match completed {
+            Ok(completed) => completed,
+            Err(interrupt) => {
+                self.handle(interrupt);
+                return Ok(());
+            }
+        };
+        for (node_index, result) in completed {
+            // If client error happens, return early and drop running futures.
+            self.complete(node_index, result).await?;
         }
+        self.admit();
+        Ok(())
+    }
 
-        let node = self.node_graph.node_weight_mut(node_index).unwrap();
+    /// Sets completed `node_index` to its output or error and curries dependent nodes.
+    async fn complete(
+        &mut self,
+        node_index: NodeIndex,
+        result: Result<DynAny, Err>,
+    ) -> Result<(), RunError<Err>> {
+        let output = match result {
+            Ok(output) => output,
+            Err(error) if self.policy.continue_on_error || self.catches(node_index) => {
+                self.admission.release(node_index);
+                return self.fail(node_index, error);
+            }
+            Err(error) => {
+                if let Some(outputs) = &mut self.outputs {
+                    outputs.abandon(node_index);
+                }
+                return Err(RunError::Task(error));
+            }
+        };
+
+        self.admission.release(node_index);
         // It must be `Running`.
-        let type_info = match node {
+        let type_info = match &self.node_graph[node_index].node {
             Node::Running(type_info) => *type_info,
             _ => panic!("Expecting running state"),
         };
-        *self.node_graph.node_weight_mut(node_index).unwrap() = Node::Value {
-            value: output,
-            type_info,
+        if output.type_id() != type_info.id() {
+            if let Some(outputs) = &mut self.outputs {
+                outputs.abandon(node_index);
+            }
+            return Err(RunError::Dependency(DependencyError::Output {
+                node: node_index,
+                expected: type_info,
+            }));
+        }
+        if let Some(outputs) = &mut self.outputs {
+            outputs.notify(node_index, &output);
+        }
+
+        // Traverse outgoing edges of completed node.
+        let (handlers, mut children): (Vec<_>, Vec<_>) = self
+            .edge_graph
+            .edges_directed(node_index, Direction::Outgoing)
+            .map(|edge| (edge.target(), *edge.weight()))
+            .partition(|(child, index)| self.adapters.catch(*child, *index) == Some(Catch::Error));
+        // Only pending children consume the value, they go last so the last one can take it.
+        let consumed = {
+            let pending = |child: NodeIndex| matches!(self.node_graph[child].node, Node::Curry(_));
+            children.sort_by_key(|(child, _)| pending(*child));
+            self.policy.drop_consumed && children.last().is_some_and(|(child, _)| pending(*child))
+        };
+        let last = children.len();
+        let mut output = Some(output);
+        let mut budget = self.policy.yield_budget;
+        for (position, (child_index, input_index)) in children.into_iter().enumerate() {
+            let value = if position + 1 == last && consumed {
+                // The value isn't kept, the last child takes it instead of a clone.
+                output.take().unwrap()
+            } else {
+                #[cfg(feature = "perf-counters")]
+                if output.as_ref().unwrap().is_boxed() {
+                    self.overhead.allocations += 1;
+                }
+                output.clone().unwrap()
+            };
+            let value = self.adapters.apply(child_index, input_index, value);
+            self.feed(node_index, type_info, child_index, input_index, value)?;
+
+            if let Some(remaining) = &mut budget {
+                *remaining -= 1;
+                if *remaining == 0 {
+                    // Long fan-outs would otherwise hold the executor thread in one poll.
+                    yield_now().await;
+                    budget = self.policy.yield_budget;
+                }
+            }
+        }
+        // Error dependencies never run after a success.
+        self.skip(
+            handlers.into_iter().map(|(handler, _)| handler).collect(),
+            node_index,
+        );
+
+        self.node_graph[node_index].node = match output {
+            Some(value) => Node::Value { value, type_info },
+            None => Node::Dropped(type_info),
         };
 
         Ok(())
@@ -0,0 +1,100 @@
+use super::group::FairShare;
+use super::group::RateLimit;
+use super::group::RateLimits;
+use super::lane::ConcurrencyLimits;
+use super::memory::MemoryBudget;
+use super::options::spawn;
+use super::options::spawn_blocking;
+use super::resource::Resources;
+use super::runner::Admission;
+use super::Lane;
+use super::NodeIndex;
+use super::RunOptions;
+use crate::curry::TaskFuture;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+/// Per node scheduling attributes.
+#[derive(Debug, Default, Clone)]
+pub(super) struct Schedule {
+    pub(super) blocking: HashSet<NodeIndex>,
+    pub(super) lanes: HashMap<NodeIndex, Lane>,
+    pub(super) resources: HashMap<String, usize>,
+    pub(super) requirements: HashMap<NodeIndex, Vec<(String, usize)>>,
+    pub(super) groups: HashMap<NodeIndex, String>,
+    pub(super) group_limits: HashMap<String, usize>,
+    pub(super) group_weights: HashMap<String, u32>,
+    pub(super) rate_limits: HashMap<String, RateLimit>,
+    pub(super) memory_hints: HashMap<NodeIndex, usize>,
+    pub(super) memory_budget: Option<usize>,
+}
+
+impl Schedule {
+    /// Releases unused storage.
+    pub(super) fn shrink_to_fit(&mut self) {
+        self.blocking.shrink_to_fit();
+        self.lanes.shrink_to_fit();
+        self.resources.shrink_to_fit();
+        self.requirements.shrink_to_fit();
+        self.groups.shrink_to_fit();
+        self.group_limits.shrink_to_fit();
+        self.group_weights.shrink_to_fit();
+        self.rate_limits.shrink_to_fit();
+        self.memory_hints.shrink_to_fit();
+    }
+
+    /// Forgets the nodes' settings, keeping the graph wide ones.
+    pub(super) fn clear_nodes(&mut self) {
+        self.blocking.clear();
+        self.lanes.clear();
+        self.requirements.clear();
+        self.groups.clear();
+        self.memory_hints.clear();
+    }
+
+    /// Decides when ready nodes may start running.
+    pub(super) fn admission<'g>(
+        &'g self,
+        options: &'g RunOptions<'_>,
+    ) -> impl Admission + Send + 'g {
+        (
+            (
+                ConcurrencyLimits::new(&self.lanes, &options.lane_limits),
+                ConcurrencyLimits::new(&self.groups, &self.group_limits),
+            ),
+            (
+                (
+                    Resources::new(&self.resources, &self.requirements),
+                    MemoryBudget::new(&self.memory_hints, self.memory_budget),
+                ),
+                (
+                    FairShare::new(&self.groups, &self.group_weights, options.concurrency_limit),
+                    // Last, as tokens taken are not given back.
+                    RateLimits::new(&self.groups, &self.rate_limits, &*options.clock),
+                ),
+            ),
+        )
+    }
+
+    /// Hands `future` to the spawner configured for `node`, if any.
+    pub(super) fn spawn<'a, Err: Send + 'a>(
+        &self,
+        options: &RunOptions<'a>,
+        node: NodeIndex,
+        future: TaskFuture<'a, Err>,
+    ) -> TaskFuture<'a, Err> {
+        if let Some(spawner) = &options.blocking_spawner {
+            if self.blocking.contains(&node) {
+                return spawn_blocking(spawner, future);
+            }
+        }
+        match self
+            .lanes
+            .get(&node)
+            .and_then(|lane| options.lane_spawners.get(lane))
+        {
+            Some(spawner) => spawn(spawner, future),
+            None => future,
+        }
+    }
+}
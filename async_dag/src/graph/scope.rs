@@ -0,0 +1,44 @@
+use super::NodeIndex;
+use super::TryGraph;
+
+impl<'a, Err: 'a> TryGraph<'a, Err> {
+    /// Builds a graph with `build`, runs it and takes the output value of the returned node.
+    ///
+    /// Tasks may borrow data living for `'a` instead of sharing it by [`std::sync::Arc`].
+    /// The graph, including every task future, is dropped before the returned future completes,
+    /// so nothing borrowed outlives the scope.
+    ///
+    /// **Panics** like [`TryGraph::execute`] does.
+    pub async fn scope<T: 'static>(build: impl FnOnce(&mut Self) -> NodeIndex) -> Result<T, Err> {
+        let mut graph = Self::new();
+        let root = build(&mut graph);
+        graph.execute(root).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Graph;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_scope() {
+        let numbers = String::from("123");
+        let factor = String::from("10");
+        let sum: i32 = block_on(Graph::scope(|graph| {
+            let sum = graph
+                .add_task(|| async { numbers.bytes().map(|b| (b - b'0') as i32).sum::<i32>() });
+            let factor = &factor;
+            graph
+                .add_child_task(
+                    sum,
+                    move |sum: i32| async move { sum * factor.parse::<i32>().unwrap() },
+                    0,
+                )
+                .unwrap()
+        }))
+        .unwrap();
+        assert_eq!(sum, 60);
+        assert_eq!(numbers.len(), 3);
+    }
+}
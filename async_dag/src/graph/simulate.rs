@@ -0,0 +1,210 @@
+use super::Node;
+use super::NodeIndex;
+use super::TryGraph;
+use daggy::petgraph::visit::EdgeRef;
+use daggy::petgraph::Direction;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// The cost model for [`TryGraph::simulate`].
+#[derive(Debug, Clone, Default)]
+pub struct SimOptions {
+    durations: HashMap<NodeIndex, Duration>,
+    default_duration: Duration,
+    concurrency: Option<usize>,
+}
+
+impl SimOptions {
+    /// Creates options where every node takes no time and concurrency is unlimited.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Estimates how long `node` runs.
+    pub fn duration(mut self, node: NodeIndex, duration: Duration) -> Self {
+        let _ = self.durations.insert(node, duration);
+        self
+    }
+
+    /// Estimates how long nodes without their own estimates run.
+    pub fn default_duration(mut self, duration: Duration) -> Self {
+        self.default_duration = duration;
+        self
+    }
+
+    /// Limits how many nodes can run at the same time.
+    ///
+    /// **Panics** if `limit` is `0`.
+    pub fn concurrency(mut self, limit: usize) -> Self {
+        assert!(limit > 0, "Concurrency limit must be positive");
+        self.concurrency = Some(limit);
+        self
+    }
+}
+
+/// The predicted schedule of a run, see [`TryGraph::simulate`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimReport {
+    /// The total runtime.
+    pub makespan: Duration,
+    /// When each node starts, relative to the start of the run.
+    pub starts: HashMap<NodeIndex, Duration>,
+    /// The fraction of the available concurrency spent running nodes.
+    ///
+    /// Without a limit, the available concurrency is the most nodes running at the same time.
+    pub utilization: f64,
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Predicts the schedule of running the graph with `options`, without running any task.
+    ///
+    /// Ready nodes start in the order they become ready, ties broken by node index, as the runner does.
+    /// Completed nodes take no time, and other nodes not yet started are assumed to run.
+    pub fn simulate(&self, options: &SimOptions) -> SimReport {
+        let mut waiting = HashMap::new();
+        // Ready nodes and running nodes, by the time they become ready or complete.
+        let mut ready = BinaryHeap::new();
+        let mut running = BinaryHeap::new();
+        for node in self.dag.graph().node_indices() {
+            if matches!(self.dag[node].node, Node::Curry(_)) {
+                let parents = self
+                    .dag
+                    .graph()
+                    .edges_directed(node, Direction::Incoming)
+                    .filter(|edge| matches!(self.dag[edge.source()].node, Node::Curry(_)))
+                    .count();
+                if parents == 0 {
+                    ready.push(Reverse((Duration::ZERO, node)));
+                } else {
+                    let _ = waiting.insert(node, parents);
+                }
+            }
+        }
+
+        let mut report = SimReport::default();
+        let mut now = Duration::ZERO;
+        let mut busy = Duration::ZERO;
+        let mut peak = 0;
+        loop {
+            while match options.concurrency {
+                Some(limit) => running.len() < limit,
+                None => true,
+            } {
+                let Some(Reverse((_, node))) = ready.pop() else {
+                    break;
+                };
+                let duration = options
+                    .durations
+                    .get(&node)
+                    .copied()
+                    .unwrap_or(options.default_duration);
+                busy += duration;
+                let _ = report.starts.insert(node, now);
+                running.push(Reverse((now + duration, node)));
+            }
+            peak = peak.max(running.len());
+
+            let Some(Reverse((completed_at, node))) = running.pop() else {
+                break;
+            };
+            now = completed_at;
+            for edge in self.dag.graph().edges_directed(node, Direction::Outgoing) {
+                let child = edge.target();
+                // Children no longer pending, e.g. cancelled ones, never start.
+                let Some(parents) = waiting.get_mut(&child) else {
+                    continue;
+                };
+                *parents -= 1;
+                if *parents == 0 {
+                    let _ = waiting.remove(&child);
+                    ready.push(Reverse((now, child)));
+                }
+            }
+        }
+
+        report.makespan = now;
+        let available = now * options.concurrency.unwrap_or(peak) as u32;
+        if !available.is_zero() {
+            report.utilization = busy.as_secs_f64() / available.as_secs_f64();
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SimOptions;
+    use crate::graph::fixtures::yield_now;
+    use crate::Graph;
+    use crate::Node;
+    use crate::RunHandle;
+    use crate::RunOptions;
+    use futures::executor::block_on;
+    use futures::future::pending;
+    use futures::future::select;
+    use std::time::Duration;
+
+    #[test]
+    fn test_simulate() {
+        let mut graph = Graph::new();
+        let a = graph.add_task(|| async { 1 });
+        let b = graph.add_task(|| async { 2 });
+        let c = graph.add_task(|| async { 3 });
+        let sum = graph.add_task(|a: i32, b: i32, c: i32| async move { a + b + c });
+        for (index, parent) in (0..).zip([a, b, c]) {
+            graph.update_dependency(parent, sum, index).unwrap();
+        }
+        let seconds = Duration::from_secs;
+        let options = SimOptions::new()
+            .default_duration(seconds(2))
+            .duration(sum, seconds(1));
+
+        let report = graph.simulate(&options);
+        assert_eq!(report.makespan, seconds(3));
+        assert_eq!(report.starts[&sum], seconds(2));
+        assert_eq!(report.utilization, 7.0 / 9.0);
+
+        let report = graph.simulate(&options.concurrency(2));
+        assert_eq!(report.makespan, seconds(5));
+        assert_eq!(report.starts[&c], seconds(2));
+        assert_eq!(report.utilization, 0.7);
+
+        block_on(graph.run());
+        assert_eq!(graph.simulate(&SimOptions::new()).starts.len(), 0);
+    }
+
+    #[test]
+    fn test_simulate_skipped_child() {
+        let handle = RunHandle::new();
+        let mut graph = Graph::new();
+        let stuck = graph.add_task(pending::<i32>);
+        let child = graph
+            .add_child_task(stuck, |v: i32| async move { v }, 0)
+            .unwrap();
+        let cancelled = graph
+            .add_child_task(child, |v: i32| async move { v }, 0)
+            .unwrap();
+        let _ = graph.add_task({
+            let handle = handle.clone();
+            move || async move { handle.cancel_subtree(cancelled) }
+        });
+        // Aborts the run once the cancellation is processed, leaving `child` pending.
+        let _ = block_on(select(
+            Box::pin(graph.run_with(&RunOptions::new().handle(handle))),
+            Box::pin(async {
+                for _ in 0..3 {
+                    yield_now().await;
+                }
+            }),
+        ));
+        assert!(matches!(
+            graph.as_dag()[cancelled].node,
+            Node::Skipped { .. }
+        ));
+
+        let report = graph.simulate(&SimOptions::new());
+        assert_eq!(report.starts.keys().collect::<Vec<_>>(), [&child]);
+    }
+}
@@ -0,0 +1,112 @@
+use super::Edge;
+use super::Index;
+use super::NodeIndex;
+use super::NodeWeight;
+use super::TryGraph;
+use crate::curry::TaskFuture;
+use daggy::Dag;
+use futures::FutureExt;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::Instrument;
+use tracing::Span;
+
+/// Makes the span of a node from its index and name, see [`TryGraph::set_span_factory`].
+pub(super) type SpanFactory<'a> = Arc<dyn Fn(NodeIndex, Option<&str>) -> Span + Send + Sync + 'a>;
+
+/// Runs every task future within a `tracing` span.
+pub(super) struct Spans<'g, 'a> {
+    factory: Option<&'g SpanFactory<'a>>,
+    names: &'g HashMap<NodeIndex, String>,
+    // Output type names by node index, as the runner borrows the graph.
+    outputs: Vec<&'static str>,
+}
+
+impl<'g, 'a> Spans<'g, 'a> {
+    pub(super) fn new<Err, D>(
+        factory: Option<&'g SpanFactory<'a>>,
+        names: &'g HashMap<NodeIndex, String>,
+        dag: &Dag<NodeWeight<'a, Err, D>, Edge, Index>,
+    ) -> Self {
+        let outputs = match factory {
+            Some(_) => vec![],
+            None => dag
+                .raw_nodes()
+                .iter()
+                .map(|node| node.weight.node.output_type_info().name())
+                .collect(),
+        };
+        Self {
+            factory,
+            names,
+            outputs,
+        }
+    }
+
+    /// Runs `future` within the span of `node`.
+    pub(super) fn instrument<Err: 'a>(
+        &self,
+        node: NodeIndex,
+        future: TaskFuture<'a, Err>,
+    ) -> TaskFuture<'a, Err> {
+        let name = self.names.get(&node).map(String::as_str);
+        let span = match self.factory {
+            Some(factory) => factory(node, name),
+            None => tracing::debug_span!(
+                "async_dag::node",
+                index = node.index(),
+                name,
+                output = self.outputs[node.index()],
+            ),
+        };
+        future.instrument(span).boxed()
+    }
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Makes the `tracing` span of every node with `factory` from its index and name,
+    /// so spans can carry domain specific fields such as a tenant id or a file path.
+    ///
+    /// By default, a node's span is a debug level `async_dag::node` span with its index, name and output type.
+    pub fn set_span_factory(
+        &mut self,
+        factory: impl Fn(NodeIndex, Option<&str>) -> Span + Send + Sync + 'a,
+    ) {
+        self.layers.span_factory = Some(Arc::new(factory));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Graph;
+    use futures::executor::block_on;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use tracing::Span;
+
+    #[test]
+    fn test_span_factory() {
+        let spans = Arc::new(Mutex::new(vec![]));
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        graph.set_name(one, "one");
+        let two = graph
+            .add_child_task(one, |v: i32| async move { v + 1 }, 0)
+            .unwrap();
+        graph.set_span_factory({
+            let spans = spans.clone();
+            move |node, name| {
+                spans
+                    .lock()
+                    .unwrap()
+                    .push((node, name.map(ToOwned::to_owned)));
+                Span::none()
+            }
+        });
+        block_on(graph.run());
+        assert_eq!(
+            *spans.lock().unwrap(),
+            [(one, Some("one".to_owned())), (two, None)]
+        );
+    }
+}
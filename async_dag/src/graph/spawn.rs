@@ -0,0 +1,128 @@
+use super::RunOptions;
+use super::TryGraph;
+use futures::channel::oneshot;
+use futures::future::AbortHandle;
+use futures::future::Abortable;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+
+/// The output of a run spawned by [`TryGraph::spawn_on`]: the graph and the run's result.
+pub type SpawnedOutput<'a, Err, D> = (TryGraph<'a, Err, D>, Result<(), Err>);
+
+/// A run detached by [`TryGraph::spawn_on`], which can be monitored and aborted from elsewhere.
+///
+/// Awaiting it gives back the graph with the run's result, or [`None`] if the run was aborted.
+pub struct SpawnedRun<'a, Err, D = ()> {
+    output: oneshot::Receiver<SpawnedOutput<'a, Err, D>>,
+    abort: AbortHandle,
+    finished: Arc<AtomicBool>,
+}
+
+impl<'a, Err, D> std::fmt::Debug for SpawnedRun<'a, Err, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpawnedRun")
+            .field("finished", &self.is_finished())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, Err, D> SpawnedRun<'a, Err, D> {
+    /// Aborts the run, dropping running tasks the next time the spawned future is polled.
+    ///
+    /// The graph is dropped with the run. See [`RunHandle::shutdown`](super::RunHandle::shutdown)
+    /// to stop gracefully and keep the graph instead.
+    pub fn abort(&self) {
+        self.abort.abort();
+    }
+
+    /// Returns `true` if the run completed or has been aborted.
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Acquire)
+    }
+}
+
+impl<'a, Err, D> Future for SpawnedRun<'a, Err, D> {
+    type Output = Option<SpawnedOutput<'a, Err, D>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // The sender is dropped without sending if aborted.
+        self.output.poll_unpin(cx).map(Result::ok)
+    }
+}
+
+impl<'a, Err: Send + 'a, D: Send + 'a> TryGraph<'a, Err, D> {
+    /// Runs the graph with `options` on a future passed to `spawner`, e.g. by `tokio::spawn`,
+    /// returning a [`SpawnedRun`] to await or abort it like a `JoinHandle`.
+    pub fn spawn_on(
+        mut self,
+        options: RunOptions<'a>,
+        spawner: impl FnOnce(BoxFuture<'a, ()>),
+    ) -> SpawnedRun<'a, Err, D> {
+        let (sender, output) = oneshot::channel();
+        let (abort, registration) = AbortHandle::new_pair();
+        let finished = Arc::new(AtomicBool::new(false));
+        let run = async move {
+            let result = self.try_run_with(&options).await;
+            (self, result)
+        };
+        spawner({
+            let finished = finished.clone();
+            async move {
+                let output = Abortable::new(run, registration).await;
+                finished.store(true, Ordering::Release);
+                if let Ok(output) = output {
+                    let _ = sender.send(output);
+                }
+            }
+            .boxed()
+        });
+        SpawnedRun {
+            output,
+            abort,
+            finished,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Graph;
+    use crate::RunOptions;
+    use futures::executor::block_on;
+    use futures::future::pending;
+    use futures::future::BoxFuture;
+
+    fn spawn_thread(future: BoxFuture<'static, ()>) {
+        let _ = std::thread::spawn(move || block_on(future));
+    }
+
+    #[test]
+    fn test_spawn_on() {
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        let run = graph.spawn_on(RunOptions::new(), spawn_thread);
+        let (graph, result) = block_on(run).unwrap();
+        result.unwrap();
+        assert_eq!(graph.get_value::<i32>(one).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_abort() {
+        let mut graph = Graph::new();
+        let _ = graph.add_task(pending::<()>);
+        let run = graph.spawn_on(RunOptions::new(), spawn_thread);
+        assert!(!run.is_finished());
+        run.abort();
+        while !run.is_finished() {
+            std::thread::yield_now();
+        }
+        assert!(block_on(run).is_none());
+    }
+}
@@ -0,0 +1,351 @@
+use super::error::SpecError;
+use super::Edge;
+use super::EdgeMetadata;
+use super::Index;
+use super::NodeIndex;
+use super::TryGraph;
+use crate::tuple::TupleIndex;
+use daggy::petgraph::visit::EdgeRef;
+use daggy::Dag;
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+/// The structure of a graph without its tasks, e.g. for external tools to analyze, visualize or validate.
+///
+/// Nodes are identified by their positions, which are their indices in the graph it's taken from.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TopologySpec {
+    /// The nodes.
+    pub nodes: Vec<NodeSpec>,
+    /// The edges.
+    pub edges: Vec<EdgeSpec>,
+}
+
+/// A node of a [`TopologySpec`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeSpec {
+    /// The node's name.
+    pub name: Option<String>,
+    /// The kind of task the node runs, e.g. to look it up in a registry, [`None`] if unknown.
+    pub kind: Option<String>,
+    /// The name of the node's output type, [`None`] if unknown.
+    pub output_type: Option<String>,
+    /// How many inputs the node's task takes.
+    pub arity: TupleIndex,
+}
+
+/// An edge of a [`TopologySpec`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdgeSpec {
+    /// The parent's position.
+    pub parent: usize,
+    /// The child's position.
+    pub child: usize,
+    /// The child's input index the parent feeds.
+    pub input: TupleIndex,
+    /// The edge's metadata, see [`TryGraph::set_edge_metadata`].
+    pub metadata: Option<EdgeMetadata>,
+}
+
+impl TopologySpec {
+    /// Serializes as `{"nodes":[{"name":"load","kind":null,"output_type":"i32","arity":0}],"edges":[{"parent":0,"child":1,"input":0,"metadata":null}]}`.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Specs always serialize")
+    }
+
+    /// Deserializes from the format of [`TopologySpec::to_json`].
+    ///
+    /// Unknown fields are ignored, and a missing or `null` name, kind, output type or metadata is [`None`].
+    /// Fails if the JSON is invalid, an edge refers to a missing node or an input beyond the child's arity,
+    /// two edges feed the same input or an edge connects a node to itself.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, SpecError> {
+        let spec = serde_json::from_str::<Self>(json).map_err(|error| {
+            let message = error.to_string();
+            // The position is reported as an offset instead.
+            let message = match message.rfind(" at line ") {
+                Some(end) => message[..end].to_owned(),
+                None => message,
+            };
+            SpecError::Syntax {
+                message,
+                offset: offset(json, error.line(), error.column()),
+            }
+        })?;
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    /// Checks every edge refers to existing nodes and inputs, no input is fed twice and no node depends on itself.
+    pub(super) fn validate(&self) -> Result<(), SpecError> {
+        let mut inputs = HashSet::new();
+        for (position, edge) in self.edges.iter().enumerate() {
+            if edge.parent >= self.nodes.len() || edge.child >= self.nodes.len() {
+                return Err(SpecError::MissingNode(position));
+            }
+            if edge.input >= self.nodes[edge.child].arity {
+                return Err(SpecError::InputOutOfRange(position));
+            }
+            if !inputs.insert((edge.child, edge.input)) {
+                return Err(SpecError::DuplicateInput(position));
+            }
+            if edge.parent == edge.child {
+                return Err(SpecError::SelfLoop(position));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The byte offset of the 1-based `line` and `column` in `text`.
+#[cfg(feature = "serde")]
+fn offset(text: &str, line: usize, column: usize) -> usize {
+    let start = text
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum::<usize>();
+    (start + column.saturating_sub(1)).min(text.len())
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Captures the names, kinds, output type names, arities and edges of nodes, with edge metadata, separately from their values.
+    pub fn topology(&self) -> TopologySpec {
+        let nodes = self
+            .dag
+            .graph()
+            .node_indices()
+            .map(|node| NodeSpec {
+                name: self.name(node).map(ToOwned::to_owned),
+                kind: self.kinds.get(&node).cloned(),
+                output_type: Some(self.output_type_info(node).name().to_owned()),
+                arity: self.dag[node].inputs.len() as TupleIndex,
+            })
+            .collect();
+        let mut edges = self
+            .dag
+            .graph()
+            .edge_references()
+            .map(|edge| EdgeSpec {
+                parent: edge.source().index(),
+                child: edge.target().index(),
+                input: *edge.weight(),
+                metadata: self.edge_metadata(edge.target(), *edge.weight()).cloned(),
+            })
+            .collect::<Vec<_>>();
+        edges.sort_by_key(|edge| (edge.child, edge.input));
+        TopologySpec { nodes, edges }
+    }
+
+    /// Serializes [`TryGraph::topology`] with [`TopologySpec::to_json`].
+    #[cfg(feature = "serde")]
+    pub fn topology_to_json(&self) -> String {
+        self.topology().to_json()
+    }
+
+    /// Hashes the edges, names, output type names and input type names of nodes, so stored state such as cached outputs
+    /// can be checked to belong to this version of the pipeline before resuming from it.
+    ///
+    /// The fingerprint is the same before and after running, and across processes of the same build.
+    /// Type names may change across compiler versions.
+    pub fn fingerprint(&self) -> u64 {
+        let spec = self.topology();
+        let mut hasher = Fnv(0xcbf2_9ce4_8422_2325);
+        for (index, node) in spec.nodes.iter().enumerate() {
+            (&node.name, &node.kind, &node.output_type, node.arity).hash(&mut hasher);
+            for input in &self.dag[NodeIndex::new(index)].inputs {
+                input.name().hash(&mut hasher);
+            }
+        }
+        for edge in &spec.edges {
+            (edge.parent, edge.child, edge.input).hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Returns `true` if `other` has the same nodes, by index, name and output type,
+    /// and the same dependencies, by parent, child and input index, ignoring tasks and values.
+    ///
+    /// Useful for asserting code generating graphs produces an expected reference graph.
+    pub fn structurally_equal<Err2, D2>(&self, other: &TryGraph<'_, Err2, D2>) -> bool {
+        self.dag.node_count() == other.dag.node_count()
+            && self.dag.graph().node_indices().all(|node| {
+                self.name(node) == other.name(node)
+                    && self.output_type_info(node) == other.output_type_info(node)
+            })
+            && edges(&self.dag) == edges(&other.dag)
+    }
+}
+
+/// FNV-1a, which unlike `DefaultHasher` is specified.
+struct Fnv(u64);
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// The dependencies of `dag` as parents, children and input indices.
+fn edges<N>(dag: &Dag<N, Edge, Index>) -> HashSet<(NodeIndex, NodeIndex, Edge)> {
+    dag.graph()
+        .edge_references()
+        .map(|edge| (edge.source(), edge.target(), *edge.weight()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "serde")]
+    use super::EdgeSpec;
+    #[cfg(feature = "serde")]
+    use super::TopologySpec;
+    #[cfg(feature = "serde")]
+    use crate::error::SpecError;
+    use crate::Graph;
+    use futures::executor::block_on;
+
+    /// A source named `name` feeding the first of two inputs of its child.
+    fn build(name: &str) -> Graph<'static> {
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        graph.set_name(one, name);
+        let _ = graph
+            .add_child_task(one, |v: i32, _: i32| async move { v }, 0)
+            .unwrap();
+        graph
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_topology_json() {
+        let mut graph = Graph::new();
+        let text = graph.add_task(|| async { "a\"b".to_owned() });
+        graph.set_name(text, "te\"xt");
+        let _ = graph
+            .add_child_task(text, |s: String, n: usize| async move { s.repeat(n) }, 0)
+            .unwrap();
+        let json = graph.topology_to_json();
+        assert_eq!(
+            json,
+            r#"{"nodes":[{"name":"te\"xt","kind":null,"output_type":"alloc::string::String","arity":0},{"name":null,"kind":null,"output_type":"alloc::string::String","arity":2}],"edges":[{"parent":0,"child":1,"input":0,"metadata":null}]}"#
+        );
+        let spec = TopologySpec::from_json(&json).unwrap();
+        assert_eq!(spec, graph.topology());
+        assert_eq!(
+            spec.edges,
+            [EdgeSpec {
+                parent: 0,
+                child: 1,
+                input: 0,
+                metadata: None,
+            }]
+        );
+
+        assert_eq!(
+            TopologySpec::from_json(
+                r#"{"nodes":[{"arity":0}],"edges":[{"parent":0,"child":0,"input":0}]}"#,
+            ),
+            Err(SpecError::InputOutOfRange(0))
+        );
+        assert_eq!(
+            TopologySpec::from_json(r#"{"nodes":[],"edges":[{"parent":0,"child":1,"input":0}]}"#),
+            Err(SpecError::MissingNode(0))
+        );
+        assert!(matches!(
+            TopologySpec::from_json("{\"nodes\":[\n{\"arity\":-1}]}"),
+            Err(SpecError::Syntax { offset: 21, .. })
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_topology_json_edge_metadata() {
+        use crate::EdgeMetadata;
+
+        let mut graph = build("one");
+        let metadata = EdgeMetadata {
+            label: Some("count".to_owned()),
+            weight: Some(0.5),
+            provenance: None,
+        };
+        graph.set_edge_metadata(1.into(), 0, metadata.clone());
+        let json = graph.topology_to_json();
+        assert!(json.contains(r#""metadata":{"label":"count","weight":0.5,"provenance":null}"#));
+        let spec = TopologySpec::from_json(&json).unwrap();
+        assert_eq!(spec.edges[0].metadata, Some(metadata));
+        assert_eq!(spec, graph.topology());
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let mut graph = build("one");
+        let fingerprint = graph.fingerprint();
+        assert_eq!(build("one").fingerprint(), fingerprint);
+        assert_ne!(build("first").fingerprint(), fingerprint);
+
+        let two = graph.add_task(|| async { 2 });
+        assert_ne!(graph.fingerprint(), fingerprint);
+        let fingerprint = graph.fingerprint();
+        graph.update_dependency(two, 1.into(), 1).unwrap();
+        assert_ne!(graph.fingerprint(), fingerprint);
+        let fingerprint = graph.fingerprint();
+        block_on(graph.run());
+        assert_eq!(graph.fingerprint(), fingerprint);
+    }
+
+    #[test]
+    fn test_fingerprint_input_types() {
+        let mut graph = build("one");
+        let mut other = build("one");
+        let _ = graph.add_task(|_: i32| async {});
+        let _ = other.add_task(|_: u8| async {});
+        assert_ne!(graph.fingerprint(), other.fingerprint());
+
+        let mut other = build("one");
+        let _ = other.add_task(|_: i32, _: i32| async {});
+        assert_ne!(graph.fingerprint(), other.fingerprint());
+
+        // The input types of tasks already called are kept.
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        let _ = graph.add_child_task(one, |_: i32| async {}, 0).unwrap();
+        let fingerprint = graph.fingerprint();
+        block_on(graph.run());
+        assert_eq!(graph.fingerprint(), fingerprint);
+    }
+
+    #[test]
+    fn test_structurally_equal() {
+        let mut graph = build("one");
+        assert!(graph.structurally_equal(&build("one")));
+        assert!(!graph.structurally_equal(&build("first")));
+
+        let mut other = build("one");
+        let _ = other.add_task(|| async { 2 });
+        let _ = graph.add_task(|| async { 2u8 });
+        assert!(!graph.structurally_equal(&other));
+
+        let mut other = build("one");
+        let _ = other.add_task(|| async { 2 });
+        let mut graph = build("one");
+        let two = graph.add_task(|| async { 2 });
+        graph.update_dependency(two, 1.into(), 1).unwrap();
+        assert!(!graph.structurally_equal(&other));
+        other.update_dependency(two, 1.into(), 1).unwrap();
+        assert!(graph.structurally_equal(&other));
+        block_on(graph.run());
+        assert!(graph.structurally_equal(&other));
+    }
+}
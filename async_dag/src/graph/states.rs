@@ -0,0 +1,87 @@
+use super::render::State;
+use super::NodeIndex;
+use super::TryGraph;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// The state of every node, updated as a run progresses.
+#[derive(Debug, Default)]
+pub(super) struct NodeStates {
+    names: Vec<Option<String>>,
+    states: Vec<State>,
+    // How many nodes are in each state, by `State as usize`, so summaries don't rescan the nodes.
+    counts: [usize; 6],
+    running: BTreeSet<usize>,
+}
+
+impl NodeStates {
+    pub(super) fn new<'a, Err: 'a, D>(graph: &TryGraph<'a, Err, D>) -> Self {
+        let nodes = graph.dag.graph().node_indices();
+        let mut states = Self {
+            names: nodes
+                .clone()
+                .map(|node| graph.name(node).map(ToOwned::to_owned))
+                .collect(),
+            states: nodes
+                .clone()
+                .map(|node| graph.dag[node].node.state())
+                .collect(),
+            ..Default::default()
+        };
+        for (index, state) in states.states.iter().enumerate() {
+            states.counts[*state as usize] += 1;
+            if *state == State::Running {
+                let _ = states.running.insert(index);
+            }
+        }
+        states
+    }
+
+    pub(super) fn set(&mut self, node: NodeIndex, state: State) {
+        let index = node.index();
+        let previous = std::mem::replace(&mut self.states[index], state);
+        self.counts[previous as usize] -= 1;
+        self.counts[state as usize] += 1;
+        if state == State::Running {
+            let _ = self.running.insert(index);
+        } else {
+            let _ = self.running.remove(&index);
+        }
+    }
+
+    pub(super) fn get(&self, index: usize) -> State {
+        self.states[index]
+    }
+
+    pub(super) fn name(&self, index: usize) -> Option<&str> {
+        self.names[index].as_deref()
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    fn count(&self, state: State) -> usize {
+        self.counts[state as usize]
+    }
+
+    /// Summarizes how many nodes are in each state, followed by the running nodes one per line.
+    pub(super) fn progress(&self) -> String {
+        let mut progress = format!(
+            "{}/{} completed, {} running, {} failed, {} skipped\n",
+            self.count(State::Value) + self.count(State::Dropped),
+            self.states.len(),
+            self.count(State::Running),
+            self.count(State::Failed),
+            self.count(State::Skipped),
+        );
+        for index in &self.running {
+            let _ = write!(progress, "running: {}", index);
+            if let Some(name) = self.name(*index) {
+                let _ = write!(progress, " '{}'", name);
+            }
+            progress.push('\n');
+        }
+        progress
+    }
+}
@@ -0,0 +1,93 @@
+use super::NodeIndex;
+use super::TryGraph;
+use crate::any::IntoAny;
+use crate::task::IntoInfallibleTask;
+use crate::task::IntoTryTask;
+use crate::task::TryTask;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use std::any::type_name;
+use std::convert::Infallible;
+use std::marker::PhantomData;
+
+/// A [`TryTask`] that runs a whole [`TryGraph`] and outputs one of its nodes' value.
+///
+/// Created by [`TryGraph::into_task`].
+pub struct GraphTask<'a, Err, T> {
+    graph: TryGraph<'a, Err>,
+    output: NodeIndex,
+    ok: PhantomData<fn() -> T>,
+}
+
+impl<'a, Err, T> std::fmt::Debug for GraphTask<'a, Err, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(&format!("GraphTask<{}>", type_name::<T>()))
+            .field("output", &self.output)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, Err: 'a> TryGraph<'a, Err> {
+    /// Converts `self` into a task with no input, whose output is `output`'s value.
+    ///
+    /// The returned task can be added to another graph, so graphs compose like futures.
+    ///
+    /// Running the task **panics** like [`TryGraph::execute`] does.
+    pub fn into_task<T: IntoAny>(self, output: NodeIndex) -> GraphTask<'a, Err, T> {
+        GraphTask {
+            graph: self,
+            output,
+            ok: PhantomData,
+        }
+    }
+}
+
+impl<'a, Err: Send + 'a, T: IntoAny> TryTask<'a> for GraphTask<'a, Err, T> {
+    type Inputs = ();
+    type Ok = T;
+    type Err = Err;
+    type Future = BoxFuture<'a, Result<T, Err>>;
+
+    fn run(self, _: Self::Inputs) -> Self::Future {
+        self.graph.execute(self.output).boxed()
+    }
+}
+
+impl<'a, Err: Send + 'a, T: IntoAny> IntoTryTask<'a, (), T, Err> for GraphTask<'a, Err, T> {
+    type Task = Self;
+
+    fn into_task(self) -> Self::Task {
+        self
+    }
+}
+
+impl<'a, T: IntoAny> IntoInfallibleTask<'a, (), T> for GraphTask<'a, Infallible, T> {
+    type Task = Self;
+
+    fn into_task(self) -> Self::Task {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Graph;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_into_task() {
+        let mut inner = Graph::new();
+        let one = inner.add_task(|| async { 1 });
+        let output = inner
+            .add_child_task(one, |v: i32| async move { v + 1 }, 0)
+            .unwrap();
+
+        let mut graph = Graph::new();
+        let two = graph.add_task(inner.into_task::<i32>(output));
+        let root = graph
+            .add_child_task(two, |v: i32| async move { v * 2 }, 0)
+            .unwrap();
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<i32>(root).unwrap(), 4);
+    }
+}
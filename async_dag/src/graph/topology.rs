@@ -0,0 +1,331 @@
+use super::error::Error;
+use super::DynCurry;
+use super::Edge;
+use super::NodeIndex;
+use super::TryGraph;
+use crate::curry::CurriedTask;
+use crate::task::IntoInfallibleTask;
+use crate::task::IntoTryTask;
+use daggy::petgraph::algo::toposort;
+use daggy::petgraph::graph::DiGraph;
+use daggy::petgraph::graph::IndexType;
+use daggy::petgraph::visit::Dfs;
+use daggy::petgraph::visit::EdgeRef;
+use daggy::petgraph::Direction;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::convert::Infallible;
+
+/// A summary of a graph's shape, see [`TryGraph::stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GraphStats {
+    /// The number of nodes.
+    pub node_count: usize,
+    /// The number of dependencies.
+    pub edge_count: usize,
+    /// The number of [levels](TryGraph::levels), i.e. nodes on the longest dependency chain.
+    pub depth: usize,
+    /// The number of nodes on the largest level.
+    pub max_width: usize,
+    /// The number of nodes without dependencies.
+    pub source_count: usize,
+    /// The number of nodes without children.
+    pub sink_count: usize,
+}
+
+/// A task of any type, e.g. made by the factory of [`TryGraph::from_topology`] for each node.
+pub struct BoxedTask<'a, Err>(DynCurry<'a, Err>);
+
+impl<'a, Err: 'a> BoxedTask<'a, Err> {
+    /// Boxes a fallible task.
+    ///
+    /// See [`TryGraph::add_try_task`].
+    pub fn new_try<Args, Ok, T: IntoTryTask<'a, Args, Ok, Err>>(task: T) -> Self {
+        Self(Box::new(CurriedTask::new(task.into_task())))
+    }
+}
+
+impl<'a> BoxedTask<'a, Infallible> {
+    /// Boxes an infallible task.
+    ///
+    /// See [`TryGraph::add_task`].
+    pub fn new<Args, Ok, T: IntoInfallibleTask<'a, Args, Ok>>(task: T) -> Self {
+        Self(Box::new(CurriedTask::new(task.into_task())))
+    }
+}
+
+impl<'a, Err> std::fmt::Debug for BoxedTask<'a, Err> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("BoxedTask")
+            .field(&self.0.output_type_info().name())
+            .finish()
+    }
+}
+
+impl<'a, Err: 'a> TryGraph<'a, Err> {
+    /// Creates a [`TryGraph`] with the same structure as `topology`.
+    ///
+    /// `factory` is called once for every node of `topology`, in index order, to make its task.
+    /// So the returned graph's node indices are the same as `topology`'s.
+    ///
+    /// Every edge of `topology` then becomes a dependency, with the edge weight as the input index.
+    /// The dependencies are type checked as they are added, and a cycle is reported as [`Error::WouldCycle`].
+    pub fn from_topology<N, E, Ix>(
+        topology: &DiGraph<N, E, Ix>,
+        mut factory: impl FnMut(&N) -> BoxedTask<'a, Err>,
+    ) -> Result<Self, Error>
+    where
+        E: Copy + Into<Edge>,
+        Ix: IndexType,
+    {
+        let mut graph = Self::with_capacity(topology.node_count(), topology.edge_count());
+        for index in topology.node_indices() {
+            let _ = graph.add_curry(factory(&topology[index]).0);
+        }
+        for edge in topology.edge_references() {
+            let parent = NodeIndex::new(edge.source().index());
+            let child = NodeIndex::new(edge.target().index());
+            graph.update_dependency(parent, child, (*edge.weight()).into())?;
+        }
+        Ok(graph)
+    }
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Finds a path from `from` to `to`, both inclusive, following dependency edges.
+    ///
+    /// Returns [`None`] if `to` is not reachable from `from`.
+    pub(super) fn path(&self, from: NodeIndex, to: NodeIndex) -> Option<Vec<NodeIndex>> {
+        let graph = self.dag.graph();
+        let mut previous = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        while let Some(node) = queue.pop_front() {
+            if node == to {
+                let mut path = vec![to];
+                while let Some(&node) = previous.get(path.last().unwrap()) {
+                    path.push(node);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for child in graph.neighbors_directed(node, Direction::Outgoing) {
+                if child != from && !previous.contains_key(&child) {
+                    let _ = previous.insert(child, node);
+                    queue.push_back(child);
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns `true` if `descendant` depends on `ancestor`, directly or indirectly.
+    ///
+    /// A node is not its own ancestor.
+    pub fn is_ancestor(&self, ancestor: NodeIndex, descendant: NodeIndex) -> bool {
+        ancestor != descendant && self.path(ancestor, descendant).is_some()
+    }
+
+    /// Iterates over all descendants of `node` in depth first order, not including `node` itself,
+    /// i.e. the nodes whose outputs are affected if `node`'s output changes.
+    ///
+    /// **Panics** if `node` does not exist within the graph.
+    pub fn impacted_by(&self, node: NodeIndex) -> impl Iterator<Item = NodeIndex> {
+        let graph = self.dag.graph();
+        let mut dfs = Dfs::new(graph, node);
+        // Collected so the iterator doesn't borrow the graph.
+        std::iter::from_fn(|| dfs.next(graph))
+            .skip(1)
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Iterates over nodes without dependencies, in index order.
+    pub fn sources(&self) -> impl Iterator<Item = NodeIndex> {
+        self.nodes_without(Direction::Incoming).into_iter()
+    }
+
+    /// Iterates over nodes without children, in index order.
+    pub fn sinks(&self) -> impl Iterator<Item = NodeIndex> {
+        self.nodes_without(Direction::Outgoing).into_iter()
+    }
+
+    // Collected so iterators don't borrow the graph.
+    fn nodes_without(&self, direction: Direction) -> Vec<NodeIndex> {
+        let graph = self.dag.graph();
+        graph
+            .node_indices()
+            .filter(|node| graph.neighbors_directed(*node, direction).next().is_none())
+            .collect()
+    }
+
+    /// Groups the nodes by depth.
+    ///
+    /// Nodes without dependencies are at level `0`,
+    /// and every other node is one level deeper than its deepest dependency,
+    /// so all of a node's inputs come from earlier levels.
+    ///
+    /// Nodes within a level are sorted by [`NodeIndex`].
+    pub fn levels(&self) -> Vec<Vec<NodeIndex>> {
+        let graph = self.dag.graph();
+        // A `Dag` never contains cycles.
+        let order = toposort(graph, None).unwrap();
+
+        let mut depths = vec![0; graph.node_count()];
+        for node in order {
+            let depth = graph
+                .neighbors_directed(node, Direction::Incoming)
+                .map(|parent| depths[parent.index()] + 1)
+                .max()
+                .unwrap_or(0);
+            depths[node.index()] = depth;
+        }
+
+        let mut levels = vec![];
+        for (index, depth) in depths.into_iter().enumerate() {
+            if levels.len() <= depth {
+                levels.resize_with(depth + 1, Vec::new);
+            }
+            levels[depth].push(NodeIndex::new(index));
+        }
+        levels
+    }
+
+    /// Summarizes the graph's shape, computed on every call,
+    /// e.g. to sanity check generated graphs.
+    pub fn stats(&self) -> GraphStats {
+        let graph = self.dag.graph();
+        let levels = self.levels();
+        GraphStats {
+            node_count: graph.node_count(),
+            edge_count: graph.edge_count(),
+            depth: levels.len(),
+            max_width: levels.iter().map(Vec::len).max().unwrap_or(0),
+            source_count: self.sources().count(),
+            sink_count: self.sinks().count(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoxedTask;
+    use super::GraphStats;
+    use crate::error::Error;
+    use crate::Graph;
+    use daggy::petgraph::graph::DiGraph;
+
+    #[test]
+    fn test_levels() {
+        let mut graph = Graph::new();
+        assert!(graph.levels().is_empty());
+
+        let root = graph.add_task(|lhs: i32, rhs: i32| async move { lhs + rhs });
+        let lhs = graph
+            .add_parent_task(|v: i32| async move { v }, root, 0)
+            .unwrap();
+        let input = graph.add_parent_task(|| async { 1 }, lhs, 0).unwrap();
+        graph.update_dependency(input, root, 1).unwrap();
+
+        assert_eq!(graph.levels(), vec![vec![input], vec![lhs], vec![root]]);
+    }
+
+    #[test]
+    fn test_impacted_by() {
+        let mut graph = Graph::new();
+        let input = graph.add_task(|| async { 1 });
+        let double = graph
+            .add_child_task(input, |v: i32| async move { v * 2 }, 0)
+            .unwrap();
+        let sum = graph
+            .add_child_task(double, |lhs: i32, rhs: i32| async move { lhs + rhs }, 0)
+            .unwrap();
+        graph.update_dependency(input, sum, 1).unwrap();
+        let other = graph.add_task(|| async { 2 });
+
+        assert!(graph.is_ancestor(input, sum));
+        assert!(graph.is_ancestor(double, sum));
+        assert!(!graph.is_ancestor(sum, input));
+        assert!(!graph.is_ancestor(input, input));
+        assert!(!graph.is_ancestor(other, sum));
+
+        let mut impacted = graph.impacted_by(input).collect::<Vec<_>>();
+        impacted.sort();
+        assert_eq!(impacted, [double, sum]);
+        assert_eq!(graph.impacted_by(sum).count(), 0);
+    }
+
+    #[test]
+    fn test_sources_and_sinks() {
+        let mut graph = Graph::new();
+        let root = graph.add_task(|lhs: i32, rhs: i32| async move { lhs + rhs });
+        let lhs = graph.add_parent_task(|| async { 1 }, root, 0).unwrap();
+        let rhs = graph.add_parent_task(|| async { 2 }, root, 1).unwrap();
+        let alone = graph.add_task(|| async {});
+        assert_eq!(graph.sources().collect::<Vec<_>>(), [lhs, rhs, alone]);
+        assert_eq!(graph.sinks().collect::<Vec<_>>(), [root, alone]);
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut graph = Graph::new();
+        assert_eq!(graph.stats(), GraphStats::default());
+
+        let root = graph.add_task(|lhs: i32, rhs: i32| async move { lhs + rhs });
+        let lhs = graph
+            .add_parent_task(|v: i32| async move { v }, root, 0)
+            .unwrap();
+        let _ = graph.add_parent_task(|| async { 1 }, lhs, 0).unwrap();
+        let _ = graph.add_parent_task(|| async { 2 }, root, 1).unwrap();
+        assert_eq!(
+            graph.stats(),
+            GraphStats {
+                node_count: 4,
+                edge_count: 3,
+                depth: 3,
+                max_width: 2,
+                source_count: 2,
+                sink_count: 1,
+            }
+        );
+    }
+
+    #[test]
+    // The topology's indices are `u32`, the same type as the graph's unless the index width is changed.
+    #[cfg(not(any(feature = "small-index", feature = "large-index")))]
+    fn test_from_topology() {
+        async fn sum(lhs: i32, rhs: i32) -> i32 {
+            lhs + rhs
+        }
+
+        let mut topology = DiGraph::<&str, u8>::new();
+        let lhs = topology.add_node("one");
+        let rhs = topology.add_node("one");
+        let root = topology.add_node("sum");
+        let _ = topology.add_edge(lhs, root, 0);
+        let _ = topology.add_edge(rhs, root, 1);
+
+        let factory = |name: &&str| match *name {
+            "one" => BoxedTask::new(|| async { 1 }),
+            _ => BoxedTask::new(sum),
+        };
+        let mut graph = Graph::from_topology(&topology, factory).unwrap();
+        futures::executor::block_on(graph.run());
+        assert_eq!(graph.get_value::<i32>(root).unwrap(), 2);
+
+        let _ = topology.add_edge(root, lhs, 0);
+        let error = Graph::from_topology(&topology, factory).unwrap_err();
+        assert!(matches!(error, Error::OutOfRange(0)));
+    }
+
+    #[test]
+    fn test_from_topology_cycle() {
+        let mut topology = DiGraph::<(), u8>::new();
+        let first = topology.add_node(());
+        let second = topology.add_node(());
+        let _ = topology.add_edge(first, second, 0);
+        let _ = topology.add_edge(second, first, 0);
+        let error = Graph::from_topology(&topology, |_| BoxedTask::new(|v: i32| async move { v }))
+            .unwrap_err();
+        assert!(matches!(error, Error::WouldCycle { path } if path.len() == 2));
+    }
+}
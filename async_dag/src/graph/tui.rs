@@ -0,0 +1,216 @@
+use super::render::State;
+use super::states::NodeStates;
+use super::NodeIndex;
+use super::TryGraph;
+use crate::curry::TaskFuture;
+use futures::FutureExt;
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+/// How often the view is redrawn at most while nodes start and complete, by default.
+const DEFAULT_INTERVAL: Duration = Duration::from_millis(100);
+
+struct View {
+    states: NodeStates,
+    drawn_at: Option<Instant>,
+}
+
+/// Where frames are drawn, locked apart from the states so tasks don't wait on the terminal to update them.
+struct Screen {
+    out: Box<dyn Write + Send>,
+    // How many lines the last frame took, to be redrawn over.
+    lines: usize,
+}
+
+impl Screen {
+    fn draw(&mut self, progress: &str) {
+        let mut frame = String::new();
+        if self.lines > 0 {
+            // Moves to the start of the last frame and clears below.
+            frame.push_str(&format!("\x1b[{}F", self.lines));
+        }
+        frame.push_str("\x1b[J");
+        frame.push_str(progress);
+        self.lines = progress.lines().count();
+        let _ = self.out.write_all(frame.as_bytes());
+        let _ = self.out.flush();
+    }
+}
+
+/// A live terminal view of runs with [`RunOptions::terminal_progress`](super::RunOptions::terminal_progress).
+///
+/// Shows how many nodes are completed, running, failed and skipped, followed by the running nodes,
+/// redrawn in place with ANSI escape codes as nodes start and complete, at most every 100ms by default.
+pub struct TerminalProgress {
+    view: Arc<Mutex<View>>,
+    screen: Arc<Mutex<Screen>>,
+    interval: Duration,
+}
+
+impl std::fmt::Debug for TerminalProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TerminalProgress")
+            .field("interval", &self.interval)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for TerminalProgress {
+    fn default() -> Self {
+        Self::to_writer(std::io::stderr())
+    }
+}
+
+impl TerminalProgress {
+    /// Draws to stderr.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Draws to `out`, which should be a terminal.
+    pub fn to_writer(out: impl Write + Send + 'static) -> Self {
+        Self {
+            view: Arc::new(Mutex::new(View {
+                states: NodeStates::default(),
+                drawn_at: None,
+            })),
+            screen: Arc::new(Mutex::new(Screen {
+                out: Box::new(out),
+                lines: 0,
+            })),
+            interval: DEFAULT_INTERVAL,
+        }
+    }
+
+    /// Redraws at most every `interval` while running, instead of every 100ms.
+    ///
+    /// The view is always drawn when a run starts and finishes.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Starts a new view below the previous one.
+    pub(super) fn start<'a, Err: 'a, D>(&self, graph: &TryGraph<'a, Err, D>) {
+        let progress = {
+            let mut view = self.view.lock().unwrap();
+            view.states = NodeStates::new(graph);
+            view.drawn_at = Some(Instant::now());
+            view.states.progress()
+        };
+        let mut screen = self.screen.lock().unwrap();
+        screen.lines = 0;
+        screen.draw(&progress);
+    }
+
+    /// Redraws with the final states.
+    pub(super) fn finish<'a, Err: 'a, D>(&self, graph: &TryGraph<'a, Err, D>) {
+        let progress = {
+            let mut view = self.view.lock().unwrap();
+            view.states = NodeStates::new(graph);
+            view.states.progress()
+        };
+        self.screen.lock().unwrap().draw(&progress);
+    }
+
+    /// Shows `node` running from when `future` is first polled until it completes.
+    pub(super) fn track<'a, Err: 'a>(
+        &self,
+        node: NodeIndex,
+        future: TaskFuture<'a, Err>,
+    ) -> TaskFuture<'a, Err> {
+        let (view, screen, interval) = (self.view.clone(), self.screen.clone(), self.interval);
+        let set = move |state| {
+            let progress = {
+                let mut view = view.lock().unwrap();
+                view.states.set(node, state);
+                let now = Instant::now();
+                if view
+                    .drawn_at
+                    .is_some_and(|drawn_at| now.duration_since(drawn_at) < interval)
+                {
+                    return;
+                }
+                view.drawn_at = Some(now);
+                view.states.progress()
+            };
+            screen.lock().unwrap().draw(&progress);
+        };
+        async move {
+            set(State::Running);
+            let result = future.await;
+            set(if result.is_ok() {
+                State::Value
+            } else {
+                State::Failed
+            });
+            result
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TerminalProgress;
+    use crate::Graph;
+    use crate::RunOptions;
+    use futures::executor::block_on;
+    use std::io::Write;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_terminal_progress() {
+        let buffer = Buffer::default();
+        let progress =
+            Arc::new(TerminalProgress::to_writer(buffer.clone()).interval(Duration::ZERO));
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        graph.set_name(one, "one");
+        let _ = graph
+            .add_child_task(one, |v: i32| async move { v }, 0)
+            .unwrap();
+        block_on(graph.run_with(&RunOptions::new().terminal_progress(progress)));
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(output.starts_with("\x1b[J0/2 completed, 0 running, 0 failed, 0 skipped\n"));
+        assert!(output
+            .contains("\x1b[J0/2 completed, 1 running, 0 failed, 0 skipped\nrunning: 0 'one'\n"));
+        assert!(output.ends_with("\x1b[1F\x1b[J2/2 completed, 0 running, 0 failed, 0 skipped\n"));
+    }
+
+    #[test]
+    fn test_terminal_progress_interval() {
+        let buffer = Buffer::default();
+        let progress =
+            TerminalProgress::to_writer(buffer.clone()).interval(Duration::from_secs(3600));
+        let mut graph = Graph::new();
+        for _ in 0..10 {
+            let _ = graph.add_task(|| async {});
+        }
+        block_on(graph.run_with(&RunOptions::new().terminal_progress(Arc::new(progress))));
+
+        // Only the first and final frames are drawn.
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(output.matches("\x1b[J").count(), 2);
+        assert!(output.ends_with("10/10 completed, 0 running, 0 failed, 0 skipped\n"));
+    }
+}
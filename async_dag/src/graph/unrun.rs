@@ -0,0 +1,153 @@
+use super::Node;
+use super::NodeIndex;
+use super::TryGraph;
+use crate::any::TypeInfo;
+use crate::tuple::TupleIndex;
+use std::collections::HashMap;
+
+/// The state of an input of a node that didn't run, see [`TryGraph::unrun_report`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(variant_size_differences)]
+pub enum InputState {
+    /// The input was given by [`TryGraph::set_input`].
+    Given,
+    /// The parent produced the input.
+    Received(NodeIndex),
+    /// The parent never produced a value, because it didn't run, failed or had its value dropped before.
+    NotProduced(NodeIndex),
+    /// The input has neither a dependency nor a given value.
+    Missing(TypeInfo),
+}
+
+/// A node that didn't run and the states of its inputs, see [`TryGraph::unrun_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnrunNode {
+    /// The node.
+    pub node: NodeIndex,
+    /// The failed or cancelled node the node was skipped because of, [`None`] if it's still pending.
+    pub skipped_by: Option<NodeIndex>,
+    /// The states of the inputs by index.
+    ///
+    /// Only wired inputs of skipped nodes are known.
+    pub inputs: Vec<(TupleIndex, InputState)>,
+}
+
+impl UnrunNode {
+    /// The first input that kept the node from running, [`None`] if the node was skipped with all inputs received.
+    pub fn first_unsatisfied(&self) -> Option<(TupleIndex, InputState)> {
+        self.inputs
+            .iter()
+            .find(|(_, state)| matches!(state, InputState::NotProduced(_) | InputState::Missing(_)))
+            .copied()
+    }
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Lists the nodes that didn't run, pending or skipped, with which inputs were satisfied
+    /// and which parents never produced a value, e.g. after a run completed with leftovers.
+    pub fn unrun_report(&self) -> Vec<UnrunNode> {
+        self.dag
+            .graph()
+            .node_indices()
+            .filter_map(|node| {
+                let (arity, skipped_by) = match &self.dag[node].node {
+                    Node::Curry(curry) => (Some(curry.num_inputs()), None),
+                    Node::Skipped { cause, .. } => (None, Some(*cause)),
+                    _ => return None,
+                };
+                let parents = self
+                    .dependencies(node)
+                    .into_iter()
+                    .map(|(index, parent, _)| (index, parent))
+                    .collect::<HashMap<_, _>>();
+                let state = |index| match parents.get(&index) {
+                    Some(&parent) => match &self.dag[parent].node {
+                        Node::Value { .. } | Node::Dropped(_) => InputState::Received(parent),
+                        _ => InputState::NotProduced(parent),
+                    },
+                    None if self.given_inputs.contains(&(node, index)) => InputState::Given,
+                    None => match &self.dag[node].node {
+                        Node::Curry(curry) => {
+                            InputState::Missing(curry.input_type_info(index).unwrap())
+                        }
+                        _ => unreachable!("Only pending nodes have unwired inputs listed"),
+                    },
+                };
+                let inputs = match arity {
+                    Some(arity) => (0..arity).map(|index| (index, state(index))).collect(),
+                    None => {
+                        let mut indices = parents.keys().copied().collect::<Vec<_>>();
+                        indices.sort_unstable();
+                        indices
+                            .into_iter()
+                            .map(|index| (index, state(index)))
+                            .collect()
+                    }
+                };
+                Some(UnrunNode {
+                    node,
+                    skipped_by,
+                    inputs,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InputState;
+    use crate::any::TypeInfo;
+    use crate::Graph;
+    use crate::RunOptions;
+    use crate::TryGraph;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_unrun_report() {
+        let mut graph = Graph::new();
+        let one = graph.add_task(|| async { 1 });
+        let sum = graph
+            .add_child_task(one, |a: i32, b: i32, c: i32| async move { a + b + c }, 0)
+            .unwrap();
+        graph.set_input(sum, 1, 2).unwrap();
+        block_on(graph.run());
+
+        let report = graph.unrun_report();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].node, sum);
+        assert_eq!(report[0].skipped_by, None);
+        assert_eq!(
+            report[0].inputs,
+            vec![
+                (0, InputState::Received(one)),
+                (1, InputState::Given),
+                (2, InputState::Missing(TypeInfo::of::<i32>())),
+            ]
+        );
+        assert_eq!(
+            report[0].first_unsatisfied(),
+            Some((2, InputState::Missing(TypeInfo::of::<i32>())))
+        );
+    }
+
+    #[test]
+    fn test_unrun_report_skipped() {
+        let mut graph = TryGraph::new();
+        let fail = graph.add_try_task(|| async { Err::<i32, _>(()) });
+        let one = graph.add_try_task(|| async { Ok(1) });
+        let sum = graph
+            .add_child_try_task(one, |a: i32, b: i32| async move { Ok(a + b) }, 0)
+            .unwrap();
+        graph.update_dependency(fail, sum, 1).unwrap();
+        block_on(graph.try_run_with(&RunOptions::new().continue_on_error())).unwrap();
+
+        let report = graph.unrun_report();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].skipped_by, Some(fail));
+        assert_eq!(
+            report[0].first_unsatisfied(),
+            Some((1, InputState::NotProduced(fail)))
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use super::adapter::Catch;
+use super::Node;
+use super::NodeIndex;
+use super::TryGraph;
+use crate::any::DynAny;
+use crate::any::IntoAny;
+use crate::any::TypeInfo;
+use crate::task::IntoSyncTryTask;
+use daggy::Walker;
+use futures::Stream;
+use futures::StreamExt;
+
+/// A new value of a node, applied by [`TryGraph::try_watch`].
+#[derive(Debug)]
+pub struct Update {
+    node: NodeIndex,
+    value: DynAny,
+    type_info: TypeInfo,
+}
+
+impl Update {
+    /// Creates an update setting `node`'s value to `value`.
+    pub fn new<T: IntoAny>(node: NodeIndex, value: T) -> Self {
+        Self {
+            node,
+            value: DynAny::new(value),
+            type_info: TypeInfo::of::<T>(),
+        }
+    }
+}
+
+impl<'a, Err: 'a, D> TryGraph<'a, Err, D> {
+    /// Adds a node outputting `value`, e.g. an external input to be changed by [`TryGraph::update_value`].
+    pub fn add_value<T: IntoAny + Clone>(&mut self, value: T) -> NodeIndex
+    where
+        Err: Send,
+    {
+        self.add_cloneable_task_impl(IntoSyncTryTask::into_task(move || Ok(value)))
+    }
+
+    /// Replaces `node`'s value and resets all its descendants, so the next run recomputes them.
+    ///
+    /// Descendants must be added by [`TryGraph::add_cloneable_try_task`], as their tasks are run again.
+    ///
+    /// **Panics** if `node` does not exist within the graph or its output type is not `T`.
+    ///
+    /// **Panics** if `node` or any descendant is running, or any descendant is not cloneable.
+    pub fn update_value<T: IntoAny>(&mut self, node: NodeIndex, value: T) {
+        self.apply_update(Update::new(node, value));
+    }
+
+    fn apply_update(&mut self, update: Update) {
+        let Update {
+            node,
+            value,
+            type_info,
+        } = update;
+        assert_eq!(
+            self.output_type_info(node),
+            type_info,
+            "Updating with a value of another type"
+        );
+        assert!(
+            !matches!(self.dag[node].node, Node::Running(_)),
+            "Updating a running node"
+        );
+        self.dag[node].node = Node::Value { value, type_info };
+
+        let dirty = self.impacted_by(node).collect::<Vec<_>>();
+        for child in &dirty {
+            assert!(
+                !matches!(self.dag[*child].node, Node::Running(_)),
+                "Resetting a running node"
+            );
+            let template = self
+                .templates
+                .get(child)
+                .expect("Resetting a node which is not cloneable");
+            self.dag[*child].node = Node::Curry(template.clone_curry().unwrap());
+        }
+        // Clean parents' values are given again, as if they just completed.
+        for child in &dirty {
+            let inputs = self
+                .dag
+                .parents(*child)
+                .iter(&self.dag)
+                .filter(|(_, parent)| !dirty.contains(parent))
+                .filter_map(|(edge, parent)| match &self.dag[parent].node {
+                    Node::Value { value, .. } => Some((self.dag[edge], value.clone())),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            for (index, value) in inputs {
+                if self.adapters.catch(*child, index) == Some(Catch::Error) {
+                    continue;
+                }
+                let value = self.adapters.apply(*child, index, value);
+                if let Node::Curry(curry) = &mut self.dag[*child].node {
+                    curry.curry(index, value).unwrap();
+                }
+            }
+        }
+    }
+
+    /// Runs the graph, then applies `updates` one by one as they arrive, recomputing affected nodes after each.
+    ///
+    /// `on_change` is called after the first run and after every recomputation.
+    /// Completes when `updates` ends, or aborts on first error.
+    ///
+    /// **Panics** like [`TryGraph::update_value`] does.
+    pub async fn try_watch(
+        &mut self,
+        updates: impl Stream<Item = Update>,
+        mut on_change: impl FnMut(&Self),
+    ) -> Result<(), Err> {
+        self.try_run().await?;
+        on_change(self);
+        let mut updates = std::pin::pin!(updates);
+        while let Some(update) = updates.next().await {
+            self.apply_update(update);
+            self.try_run().await?;
+            on_change(self);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Update;
+    use crate::Graph;
+    use futures::executor::block_on;
+    use futures::stream;
+
+    #[test]
+    fn test_update_value() {
+        let mut graph = Graph::new();
+        let input = graph.add_value(1);
+        let constant = graph.add_task(|| async { 10 });
+        let sum = graph.add_cloneable_task(|a: i32, b: i32| async move { a + b });
+        let double = graph.add_cloneable_task(|v: i32| async move { v * 2 });
+        graph.update_dependency(input, sum, 0).unwrap();
+        graph.update_dependency(constant, sum, 1).unwrap();
+        graph.update_dependency(sum, double, 0).unwrap();
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<i32>(double).unwrap(), 22);
+
+        graph.update_value(input, 2);
+        assert_eq!(graph.get_value::<i32>(double), None);
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<i32>(double).unwrap(), 24);
+    }
+
+    #[test]
+    fn test_watch() {
+        let mut graph = Graph::new();
+        let input = graph.add_value(1);
+        let double = graph.add_cloneable_task(|v: i32| async move { v * 2 });
+        graph.update_dependency(input, double, 0).unwrap();
+
+        let mut outputs = vec![];
+        let updates = stream::iter([Update::new(input, 2), Update::new(input, 3)]);
+        block_on(graph.watch(updates, |graph| {
+            outputs.push(graph.get_value::<i32>(double).unwrap())
+        }));
+        assert_eq!(outputs, vec![2, 4, 6]);
+    }
+}
@@ -0,0 +1,103 @@
+use super::Clock;
+use super::NodeIndex;
+use crate::curry::TaskFuture;
+use futures::future::select;
+use futures::future::Either;
+use futures::FutureExt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Called with a node's index, name and how long it has been running.
+pub(super) type StallObserver<'a> =
+    Arc<dyn Fn(NodeIndex, Option<&str>, Duration) + Send + Sync + 'a>;
+
+/// Reports nodes running longer than a threshold.
+#[derive(Clone)]
+pub(super) struct Watchdog<'a> {
+    pub(super) threshold: Duration,
+    pub(super) observer: StallObserver<'a>,
+}
+
+impl<'a> Watchdog<'a> {
+    /// Reports `node` every `threshold` until `future` completes.
+    pub(super) fn watch<Err: 'a>(
+        &self,
+        clock: &Arc<dyn Clock>,
+        node: NodeIndex,
+        name: Option<&str>,
+        future: TaskFuture<'a, Err>,
+    ) -> TaskFuture<'a, Err> {
+        let Self {
+            threshold,
+            observer,
+        } = self.clone();
+        let clock = clock.clone();
+        let name = name.map(ToOwned::to_owned);
+        async move {
+            let start = clock.now();
+            let mut future = future;
+            loop {
+                match select(future, clock.sleep(threshold)).await {
+                    Either::Left((output, _)) => return output,
+                    Either::Right(((), running)) => {
+                        let elapsed = clock.now().saturating_duration_since(start);
+                        #[cfg(feature = "log")]
+                        ::log::warn!(
+                            target: "async_dag",
+                            "node {}{} has been running for {:?}",
+                            node.index(),
+                            name.as_ref().map(|name| format!(" '{}'", name)).unwrap_or_default(),
+                            elapsed
+                        );
+                        observer(node, name.as_deref(), elapsed);
+                        future = running;
+                    }
+                }
+            }
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util;
+    use crate::test_util::MockClock;
+    use crate::Clock;
+    use crate::Graph;
+    use crate::RunOptions;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    #[test]
+    fn test_stall_watchdog() {
+        let clock = MockClock::new();
+        let stalls = Arc::new(Mutex::new(vec![]));
+        let mut graph = Graph::new();
+        let slow = graph.add_task({
+            let clock = clock.clone();
+            move || clock.sleep(Duration::from_millis(50))
+        });
+        graph.set_name(slow, "slow");
+        let _ = graph.add_task(|| async {});
+        let options =
+            RunOptions::new()
+                .clock(clock.clone())
+                .stall_watchdog(Duration::from_millis(10), {
+                    let stalls = stalls.clone();
+                    move |node, name, elapsed| {
+                        stalls
+                            .lock()
+                            .unwrap()
+                            .push((node, name.map(ToOwned::to_owned), elapsed));
+                    }
+                });
+        test_util::block_on(&clock, graph.run_with(&options));
+
+        let stalls = stalls.lock().unwrap();
+        let expected = [10, 20, 30, 40]
+            .map(|millis| (slow, Some("slow".to_owned()), Duration::from_millis(millis)));
+        assert_eq!(*stalls, expected);
+    }
+}
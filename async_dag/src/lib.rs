@@ -102,6 +102,56 @@
 //! `TryGraph` can be used if the user wants a fail-fast strategy with fallible tasks.
 //!
 //! It aborts running futures when any one of them completes with a `Err`.
+//!
+//! # Streaming
+//!
+//! A task can output an `ItemStream`, and a child `ForEachTask` runs a function per item as items arrive,
+//! gathering the outputs into a `Vec` for further children.
+//!
+//! # Static graphs
+//!
+//! When the whole graph is known at compile time, `static_dag!` wires it into a single future
+//! with the same scheduling, but without boxing tasks or checking types at runtime.
+//!
+//! # Sharing large values
+//!
+//! A node's output is cloned for every child.
+//! Wrap large buffers in `SharedValue` so they are shared by reference counting instead.
+//! `benches/fanout.rs` measures the difference.
+//!
+//! # Sending graphs
+//!
+//! Tasks, their inputs and their outputs are all required to be `Send`,
+//! so a graph can be built on one thread and run on another, e.g. inside `tokio::spawn`.
+//!
+//! Tasks with non-`Send` functions or futures can be added to a `LocalGraph` by `add_local_task`.
+//! A `LocalGraph` isn't `Send` itself, so it's built and run by `run_local` on the current thread.
+//!
+//! # WebAssembly
+//!
+//! The crate doesn't spawn threads or depend on a runtime, so it works on `wasm32-unknown-unknown`.
+//! Pass the `run` or `run_local` future to `wasm_bindgen_futures::spawn_local`, see `examples/wasm.rs`.
+//!
+//! # Features
+//!
+//! - `derive`: `#[derive(Task)]` makes a task of a struct whose fields are its named inputs, see `FieldsTask`.
+//! - `inspector`: an `Inspector` serving the progress and state of runs over HTTP, as text, DOT or JSON.
+//! - `log`: logs node starts, completions and failures at debug level, by node index and name,
+//!   through the `log` facade.
+//! - `perf-counters`: `RunReport::overhead` counts the runner's own iterations, curries and allocations.
+//! - `process`: a `WorkerPool` of supervised processes running serialized tasks, restarted on crash, timeout or oversized reply.
+//! - `serde`: JSON export and import of `TopologySpec`s, a `TaskRegistry` of task kinds constructed from JSON parameters,
+//!   building graphs from a `TopologySpec`, and a `DynamicGraph` whose edges all carry `serde_json::Value`s.
+//! - `small-index`, `large-index`: `u16` or `usize` node indices instead of `u32`, see `Index`. `large-index` wins if both are enabled.
+//! - `tower`: `ServiceTask` turns a `tower::Service` into a task, one call per node.
+//! - `metrics`: emits `tasks_started`, `tasks_failed` and `task_duration_seconds`, labeled by node name,
+//!   through the `metrics` facade.
+//! - `opentelemetry`: creates a span per node through the global tracer or the graph's own, within a span per run.
+//!   A node's span is a child of its first dependency's span and links to the other dependencies' spans.
+//! - `tracing`: runs every task within a `tracing` span, customizable by `TryGraph::set_span_factory`.
+//! - `tui`: a `TerminalProgress` view redrawing node counts and running node names in the terminal during runs.
+//! - `test-util`: a `MockClock` with an executor that skips sleeps, a `Trace` layer recording run order,
+//!   `MockTask`s recording how they're run, and a `RandomDag` generator for property testing.
 
 #![deny(warnings)]
 #![warn(
@@ -115,7 +165,6 @@
     missing_debug_implementations,
     non_ascii_idents,
     noop_method_call,
-    pointer_structural_match,
     trivial_casts,
     trivial_numeric_casts,
     unsafe_code,
@@ -130,11 +179,44 @@
 mod any;
 mod curry;
 mod graph;
+#[cfg(feature = "test-util")]
+pub mod mock;
+#[cfg(feature = "process")]
+pub mod process;
+mod shared;
+mod static_dag;
 mod task;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
 mod tuple;
 
+pub use daggy;
+
+#[cfg(feature = "derive")]
+pub use async_dag_derive::Task;
+
+// Lets derived code refer to `::async_dag` in tests.
+#[cfg(all(test, feature = "derive"))]
+extern crate self as async_dag;
+
+pub use any::downcast;
+pub use any::DynAny;
 pub use any::IntoAny;
+pub use any::SmallAny;
 pub use any::TypeInfo;
 pub use curry::Curry;
+pub use curry::TaskFuture;
 pub use graph::*;
-pub use task::{IntoInfallibleTask, IntoTryTask, TryTask};
+pub use shared::SharedValue;
+#[doc(hidden)]
+pub use static_dag::__private;
+#[cfg(feature = "tower")]
+pub use task::ServiceTask;
+pub use task::{
+    FieldsTask, ForEachTask, IntoInfallibleTask, IntoLocalInfallibleTask, IntoLocalTryTask,
+    IntoSyncInfallibleTask, IntoSyncTryTask, IntoTryTask, ItemStream, StructTask, TryForEachTask,
+    TryTask, DEFAULT_FOR_EACH_LIMIT,
+};
+pub use tuple::{
+    In, InputIndex, InsertError, InsertErrorKind, InsertResult, TakeError, TupleIndex,
+};
@@ -0,0 +1,237 @@
+//! Tasks that record how they're run, for testing graph wiring without side effects.
+
+use crate::any::IntoAny;
+use crate::task::IntoInfallibleTask;
+use crate::task::IntoTryTask;
+use crate::task::TryTask;
+use crate::tuple::Tuple;
+use std::any::type_name;
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
+
+// Orders events across all mock tasks.
+static SEQUENCE: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug)]
+struct Record<I> {
+    inputs: Vec<I>,
+    started: Vec<usize>,
+    finished: Vec<usize>,
+}
+
+/// A task with inputs `I` that outputs a clone of a fixed result.
+///
+/// Clones share their records, so one mock can be added as several nodes and inspected once.
+/// Records are only available while a clone is around, so keep one before adding it to a graph.
+pub struct MockTask<I, T, Err = Infallible> {
+    result: Result<T, Err>,
+    record: Arc<Mutex<Record<I>>>,
+}
+
+impl<I, T, Err> MockTask<I, T, Err> {
+    fn new(result: Result<T, Err>) -> Self {
+        Self {
+            result,
+            record: Arc::new(Mutex::new(Record {
+                inputs: vec![],
+                started: vec![],
+                finished: vec![],
+            })),
+        }
+    }
+
+    /// A task that succeeds with `value`.
+    pub fn returning(value: T) -> Self {
+        Self::new(Ok(value))
+    }
+
+    /// A task that fails with `err`.
+    pub fn failing(err: Err) -> Self {
+        Self::new(Err(err))
+    }
+
+    /// How many times the task has been run.
+    pub fn calls(&self) -> usize {
+        self.record.lock().unwrap().started.len()
+    }
+
+    /// Whether every run has finished.
+    pub fn finished(&self) -> bool {
+        let record = self.record.lock().unwrap();
+        record.started.len() == record.finished.len()
+    }
+
+    /// Inputs of every run, in run order.
+    pub fn inputs(&self) -> Vec<I>
+    where
+        I: Clone,
+    {
+        self.record.lock().unwrap().inputs.clone()
+    }
+
+    /// Whether every run of `self` finished before any run of `other` started.
+    ///
+    /// Both tasks must have been run.
+    pub fn finished_before<J, U, E>(&self, other: &MockTask<J, U, E>) -> bool {
+        let finished = self.record.lock().unwrap().finished.iter().max().copied();
+        let started = other.record.lock().unwrap().started.iter().min().copied();
+        matches!((finished, started), (Some(finished), Some(started)) if finished < started)
+    }
+}
+
+impl<I, T: Clone, Err: Clone> Clone for MockTask<I, T, Err> {
+    fn clone(&self) -> Self {
+        Self {
+            result: self.result.clone(),
+            record: self.record.clone(),
+        }
+    }
+}
+
+impl<I, T, Err> std::fmt::Debug for MockTask<I, T, Err> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(&format!(
+            "MockTask{} -> Result<{}, {}>",
+            type_name::<I>(),
+            type_name::<T>(),
+            type_name::<Err>(),
+        ))
+        .field("calls", &self.calls())
+        .finish_non_exhaustive()
+    }
+}
+
+impl<'a, I, T, Err> TryTask<'a> for MockTask<I, T, Err>
+where
+    I: Tuple + Send + 'static,
+    T: IntoAny,
+    Err: Send + 'a,
+{
+    type Inputs = I;
+    type Ok = T;
+    type Err = Err;
+    type Future = MockFuture<I, T, Err>;
+
+    fn run(self, inputs: Self::Inputs) -> Self::Future {
+        let mut record = self.record.lock().unwrap();
+        record.inputs.push(inputs);
+        record.started.push(SEQUENCE.fetch_add(1, Ordering::SeqCst));
+        drop(record);
+        MockFuture {
+            result: Some(self.result),
+            record: self.record,
+        }
+    }
+}
+
+/// The future of a [`MockTask`] run, recording when it completes.
+pub struct MockFuture<I, T, Err> {
+    result: Option<Result<T, Err>>,
+    record: Arc<Mutex<Record<I>>>,
+}
+
+// The result is never pinned.
+impl<I, T, Err> Unpin for MockFuture<I, T, Err> {}
+
+impl<I, T, Err> Future for MockFuture<I, T, Err> {
+    type Output = Result<T, Err>;
+
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let result = this
+            .result
+            .take()
+            .expect("MockFuture polled after completion");
+        this.record
+            .lock()
+            .unwrap()
+            .finished
+            .push(SEQUENCE.fetch_add(1, Ordering::SeqCst));
+        Poll::Ready(result)
+    }
+}
+
+impl<I, T, Err> std::fmt::Debug for MockFuture<I, T, Err> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockFuture")
+            .field("completed", &self.result.is_none())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, I, T, Err> IntoTryTask<'a, I, T, Err> for MockTask<I, T, Err>
+where
+    I: Tuple + Send + 'static,
+    T: IntoAny,
+    Err: Send + 'a,
+{
+    type Task = Self;
+
+    fn into_task(self) -> Self::Task {
+        self
+    }
+}
+
+impl<'a, I, T> IntoInfallibleTask<'a, I, T> for MockTask<I, T>
+where
+    I: Tuple + Send + 'static,
+    T: IntoAny,
+{
+    type Task = Self;
+
+    fn into_task(self) -> Self::Task {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MockTask;
+    use crate::task::TryTask;
+    use crate::Graph;
+    use crate::TryGraph;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_mock_task() {
+        let source = MockTask::<(), i32>::returning(1);
+        let double = MockTask::<(i32,), i32>::returning(2);
+        let mut graph = Graph::new();
+        let lhs = graph.add_task(source.clone());
+        let rhs = graph.add_task(source.clone());
+        let _ = graph.add_child_task(lhs, double.clone(), 0).unwrap();
+        let _ = graph.add_child_task(rhs, double.clone(), 0).unwrap();
+        assert_eq!(source.calls(), 0);
+
+        block_on(graph.run());
+        assert_eq!(source.calls(), 2);
+        assert_eq!(double.inputs(), [(1,), (1,)]);
+        assert!(double.finished());
+        assert!(source.finished_before(&double));
+        assert!(!double.finished_before(&source));
+    }
+
+    #[test]
+    fn test_mock_task_finished() {
+        let mock = MockTask::<(), i32>::returning(1);
+        let future = TryTask::run(mock.clone(), ());
+        assert_eq!(mock.calls(), 1);
+        assert!(!mock.finished());
+        assert_eq!(block_on(future), Ok(1));
+        assert!(mock.finished());
+    }
+
+    #[test]
+    fn test_failing_mock_task() {
+        let mut graph = TryGraph::new();
+        let _ = graph.add_try_task(MockTask::<(), (), _>::failing("error"));
+        assert_eq!(block_on(graph.try_run()).unwrap_err(), "error");
+    }
+}
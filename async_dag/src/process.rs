@@ -0,0 +1,319 @@
+//! A pool of worker processes running serialized tasks, isolating crashy native libraries from the graph.
+//!
+//! A request is written to a worker's stdin as a frame, and the worker writes one frame back to its stdout.
+//! A frame is its payload's length as a little endian `u64` followed by the payload.
+//!
+//! A worker is restarted if it fails to reply, and the request fails with [`WorkerError::Crashed`].
+//! It's also restarted if its reply is larger than [`WorkerOptions::max_frame`] or takes longer than [`WorkerOptions::timeout`].
+//! Use [`WorkerPool::call`] in a task:
+//!
+//! ```ignore
+//! let pool = Arc::new(WorkerPool::new(4, || Command::new("worker")));
+//! let node = graph.add_try_task(move |request: Vec<u8>| pool.call(request));
+//! ```
+
+use futures::channel::oneshot;
+use futures::Future;
+use futures::FutureExt;
+use std::io::Read;
+use std::io::Write;
+use std::process::Child;
+use std::process::ChildStdin;
+use std::process::ChildStdout;
+use std::process::Command;
+use std::process::Stdio;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Why a [`WorkerPool::call`] failed.
+#[derive(Debug)]
+pub enum WorkerError {
+    /// The worker couldn't be started, or exited or misbehaved before replying.
+    Crashed(std::io::Error),
+    /// The request or the reply is larger than [`WorkerOptions::max_frame`], by its length in bytes.
+    TooLarge(u64),
+    /// The worker didn't reply within [`WorkerOptions::timeout`].
+    TimedOut,
+    /// The pool has been dropped.
+    Stopped,
+}
+
+impl std::fmt::Display for WorkerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkerError::Crashed(error) => write!(f, "Worker crashed: {}", error),
+            WorkerError::TooLarge(len) => write!(f, "Frame of {} bytes is too large", len),
+            WorkerError::TimedOut => f.write_str("Worker timed out"),
+            WorkerError::Stopped => f.write_str("Worker pool stopped"),
+        }
+    }
+}
+
+impl std::error::Error for WorkerError {}
+
+type Reply = oneshot::Sender<Result<Vec<u8>, WorkerError>>;
+
+/// The default of [`WorkerOptions::max_frame`], 64 MiB.
+pub const DEFAULT_MAX_FRAME: u64 = 64 << 20;
+
+/// Limits on the workers of a [`WorkerPool`].
+#[derive(Debug, Clone)]
+pub struct WorkerOptions {
+    max_frame: u64,
+    timeout: Option<Duration>,
+}
+
+impl Default for WorkerOptions {
+    fn default() -> Self {
+        Self {
+            max_frame: DEFAULT_MAX_FRAME,
+            timeout: None,
+        }
+    }
+}
+
+impl WorkerOptions {
+    /// Creates default options, with frames up to [`DEFAULT_MAX_FRAME`] and no timeout.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the largest payload in bytes of a request or a reply.
+    ///
+    /// Larger requests fail without being sent. A worker announcing a larger reply is restarted instead of allocating it.
+    pub fn max_frame(mut self, bytes: u64) -> Self {
+        self.max_frame = bytes;
+        self
+    }
+
+    /// Sets how long a worker may take to reply to a request before it's killed and restarted.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+/// A fixed number of supervised worker processes.
+///
+/// Every worker is driven by a thread, so the pool works with any executor.
+/// Dropping the pool kills the workers once their current requests are done.
+pub struct WorkerPool {
+    sender: Option<mpsc::Sender<(Vec<u8>, Reply)>>,
+    threads: Vec<JoinHandle<()>>,
+    restarts: Arc<AtomicUsize>,
+}
+
+impl std::fmt::Debug for WorkerPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkerPool")
+            .field("workers", &self.threads.len())
+            .field("restarts", &self.restarts())
+            .finish()
+    }
+}
+
+impl WorkerPool {
+    /// Starts `size` workers, each a process spawned from the [`Command`] made by `command`.
+    ///
+    /// Stdin and stdout of the command are overridden to talk to the worker.
+    ///
+    /// **Panics** if `size` is zero.
+    pub fn new(size: usize, command: impl Fn() -> Command + Send + Sync + 'static) -> Self {
+        Self::with_options(size, command, WorkerOptions::new())
+    }
+
+    /// The same as [`WorkerPool::new`] with limits on the workers.
+    pub fn with_options(
+        size: usize,
+        command: impl Fn() -> Command + Send + Sync + 'static,
+        options: WorkerOptions,
+    ) -> Self {
+        assert!(size > 0, "Worker pool must not be empty");
+        let (sender, receiver) = mpsc::channel();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let command = Arc::new(command);
+        let restarts = Arc::new(AtomicUsize::new(0));
+        let threads = (0..size)
+            .map(|_| {
+                let receiver = receiver.clone();
+                let command = command.clone();
+                let restarts = restarts.clone();
+                let options = options.clone();
+                std::thread::spawn(move || supervise(&receiver, &*command, &options, &restarts))
+            })
+            .collect();
+        Self {
+            sender: Some(sender),
+            threads,
+            restarts,
+        }
+    }
+
+    /// Sends `request` to the first idle worker and resolves to its reply.
+    pub fn call(
+        &self,
+        request: Vec<u8>,
+    ) -> impl Future<Output = Result<Vec<u8>, WorkerError>> + Send + 'static {
+        let (reply, receiver) = oneshot::channel();
+        // Threads only stop once the sender is dropped.
+        self.sender
+            .as_ref()
+            .unwrap()
+            .send((request, reply))
+            .unwrap();
+        receiver.map(|result| result.unwrap_or(Err(WorkerError::Stopped)))
+    }
+
+    /// How many times workers have been restarted after crashing.
+    pub fn restarts(&self) -> usize {
+        self.restarts.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for WorkerPool {
+    fn drop(&mut self) {
+        drop(self.sender.take());
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+struct Worker {
+    // Shared with the watchdog killing it on timeout.
+    child: Mutex<Child>,
+    stdin: ChildStdin,
+    stdout: ChildStdout,
+}
+
+fn spawn(command: &dyn Fn() -> Command) -> std::io::Result<Worker> {
+    let mut child = command()
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let stdin = child.stdin.take().unwrap();
+    let stdout = child.stdout.take().unwrap();
+    Ok(Worker {
+        child: Mutex::new(child),
+        stdin,
+        stdout,
+    })
+}
+
+fn exchange(
+    stdin: &mut ChildStdin,
+    stdout: &mut ChildStdout,
+    request: &[u8],
+    max_frame: u64,
+) -> Result<Vec<u8>, WorkerError> {
+    let mut write = || {
+        stdin.write_all(&(request.len() as u64).to_le_bytes())?;
+        stdin.write_all(request)?;
+        stdin.flush()
+    };
+    write().map_err(WorkerError::Crashed)?;
+
+    let mut len = [0; 8];
+    stdout.read_exact(&mut len).map_err(WorkerError::Crashed)?;
+    let len = u64::from_le_bytes(len);
+    if len > max_frame {
+        return Err(WorkerError::TooLarge(len));
+    }
+    let mut reply = vec![0; len as usize];
+    stdout
+        .read_exact(&mut reply)
+        .map_err(WorkerError::Crashed)?;
+    Ok(reply)
+}
+
+// Kills the worker if it doesn't reply within `timeout`, failing the exchange with `WorkerError::TimedOut`.
+fn exchange_within(
+    worker: &mut Worker,
+    request: &[u8],
+    options: &WorkerOptions,
+) -> Result<Vec<u8>, WorkerError> {
+    let Worker {
+        child,
+        stdin,
+        stdout,
+    } = worker;
+    let timeout = match options.timeout {
+        Some(timeout) => timeout,
+        None => return exchange(stdin, stdout, request, options.max_frame),
+    };
+    let (done, finished) = mpsc::channel::<()>();
+    std::thread::scope(|scope| {
+        let child = &*child;
+        let watchdog = scope.spawn(move || match finished.recv_timeout(timeout) {
+            Err(RecvTimeoutError::Timeout) => {
+                let _ = child.lock().unwrap().kill();
+                true
+            }
+            _ => false,
+        });
+        let result = exchange(stdin, stdout, request, options.max_frame);
+        drop(done);
+        if watchdog.join().unwrap() {
+            Err(WorkerError::TimedOut)
+        } else {
+            result
+        }
+    })
+}
+
+fn kill(worker: Worker) {
+    let mut child = worker.child.into_inner().unwrap();
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+// Runs one worker until the pool is dropped, restarting it whenever an exchange fails.
+fn supervise(
+    receiver: &Mutex<mpsc::Receiver<(Vec<u8>, Reply)>>,
+    command: &dyn Fn() -> Command,
+    options: &WorkerOptions,
+    restarts: &AtomicUsize,
+) {
+    let mut worker = spawn(command).ok();
+    loop {
+        let received = receiver.lock().unwrap().recv();
+        let (request, reply) = match received {
+            Ok(job) => job,
+            Err(_) => break,
+        };
+        if request.len() as u64 > options.max_frame {
+            let _ = reply.send(Err(WorkerError::TooLarge(request.len() as u64)));
+            continue;
+        }
+        let mut current = match worker.take() {
+            Some(worker) => worker,
+            None => match spawn(command) {
+                Ok(worker) => worker,
+                Err(error) => {
+                    let _ = reply.send(Err(WorkerError::Crashed(error)));
+                    continue;
+                }
+            },
+        };
+        match exchange_within(&mut current, &request, options) {
+            Ok(output) => {
+                worker = Some(current);
+                let _ = reply.send(Ok(output));
+            }
+            Err(error) => {
+                kill(current);
+                let _ = restarts.fetch_add(1, Ordering::Relaxed);
+                let _ = reply.send(Err(error));
+            }
+        }
+    }
+    if let Some(worker) = worker {
+        kill(worker);
+    }
+}
@@ -0,0 +1,89 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// A value shared by reference counting instead of deep cloning.
+///
+/// A node's output is cloned for every child, so a large buffer fanning out to many children
+/// is copied many times. Outputting a [`SharedValue`] instead hands every child a pointer to the same buffer.
+#[derive(Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SharedValue<T>(Arc<T>);
+
+impl<T> SharedValue<T> {
+    /// Wraps `value`.
+    pub fn new(value: T) -> Self {
+        Self(Arc::new(value))
+    }
+
+    /// Returns `true` if `self` and `other` share the same value.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Takes the value out if it's not shared, clones it otherwise.
+    pub fn into_inner(self) -> T
+    where
+        T: Clone,
+    {
+        Arc::try_unwrap(self.0).unwrap_or_else(|shared| (*shared).clone())
+    }
+}
+
+impl<T> Clone for SharedValue<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Deref for SharedValue<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> AsRef<T> for SharedValue<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> From<T> for SharedValue<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T> From<Arc<T>> for SharedValue<T> {
+    fn from(value: Arc<T>) -> Self {
+        Self(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SharedValue;
+    use crate::Graph;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_shared_value() {
+        let mut graph = Graph::new();
+        let buffer = graph.add_task(|| async { SharedValue::new(vec![1u8; 1024]) });
+        let children = (0..3)
+            .map(|_| {
+                graph
+                    .add_child_task(buffer, |v: SharedValue<Vec<u8>>| async move { v }, 0)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        block_on(graph.run());
+
+        let buffer = graph.get_value::<SharedValue<Vec<u8>>>(buffer).unwrap();
+        for child in children {
+            let value = graph.get_value::<SharedValue<Vec<u8>>>(child).unwrap();
+            assert!(value.ptr_eq(&buffer));
+        }
+        assert_eq!(buffer.len(), 1024);
+    }
+}
@@ -0,0 +1,173 @@
+//! A macro wiring fully statically known graphs at compile time.
+
+// Items the expansion of `static_dag!` refers to.
+#[doc(hidden)]
+pub mod __private {
+    pub use futures::future::maybe_done;
+    pub use std::future::poll_fn;
+    pub use std::pin::pin;
+    pub use std::task::Poll;
+}
+
+/// Builds a future running a fully statically known graph, with no boxing and no runtime type checks.
+///
+/// Each `name = task(parents...);` line adds a node calling the async function `task` with the named parents' outputs,
+/// which must be declared by earlier lines. The future resolves to the output of the node after `=>`,
+/// or to a tuple of outputs, once all the nodes have completed.
+///
+/// Like [`Graph`](crate::Graph), a node starts as soon as all its parents have completed, and outputs are cloned for every child.
+/// Tasks are called by reference, so they must be `Fn`s, e.g. async functions or closures capturing by reference.
+///
+/// Use [`Graph`](crate::Graph) instead when the topology is only known at runtime.
+///
+/// ```
+/// use async_dag::static_dag;
+///
+/// async fn one() -> i32 { 1 }
+/// async fn sum(lhs: i32, rhs: i32) -> i32 { lhs + rhs }
+///
+/// let seven = static_dag! {
+///     _1 = one();
+///     _2 = sum(_1, _1);
+///     _3 = sum(_1, _2);
+///     _4 = sum(_2, _2);
+///     _7 = sum(_3, _4);
+///     => _7
+/// };
+/// assert_eq!(futures::executor::block_on(seven), 7);
+/// ```
+///
+/// Outputs must be distinct, as they are moved out rather than cloned:
+///
+/// ```compile_fail
+/// # use async_dag::static_dag;
+/// # async fn one() -> i32 { 1 }
+/// let _ = static_dag! {
+///     _1 = one();
+///     => (_1, _1)
+/// };
+/// ```
+#[macro_export]
+macro_rules! static_dag {
+    ($($name:ident = $($task:ident)::+ ($($parent:ident),* $(,)?);)+ => ($($output:ident),+ $(,)?)) => {
+        {
+            // Rejects duplicate outputs, as each output is taken once.
+            #[allow(dead_code)]
+            struct Outputs {
+                $($output: (),)+
+            }
+            $crate::static_dag!(@run [$($name = $($task)::+ ($($parent),*);)+] ($($crate::static_dag!(@take $output),)+))
+        }
+    };
+    ($($name:ident = $($task:ident)::+ ($($parent:ident),* $(,)?);)+ => $output:ident) => {
+        $crate::static_dag!(@run [$($name = $($task)::+ ($($parent),*);)+] $crate::static_dag!(@take $output))
+    };
+    (@take $output:ident) => {
+        $output.as_mut().as_pin_mut().unwrap().take_output().unwrap()
+    };
+    (@run [$($name:ident = $($task:ident)::+ ($($parent:ident),*);)+] $output:expr) => {
+        async {
+            // Rejects parents that are used before being declared, which also rules out cycles.
+            #[allow(unused_variables, clippy::let_unit_value)]
+            {
+                $(
+                    let $name = { $(let _ = &$parent;)* };
+                )+
+            }
+            $(
+                let mut $name = $crate::__private::pin!(::std::option::Option::None);
+            )+
+            $crate::__private::poll_fn(|cx| {
+                let mut pending = false;
+                // Nodes are visited in topological order, so children start in the same pass their parents complete.
+                $(
+                    if $name.is_none()
+                        $(&& $parent.as_mut().as_pin_mut().unwrap().output_mut().is_some())*
+                    {
+                        let future = $($task)::+($(::std::clone::Clone::clone(
+                            $parent.as_mut().as_pin_mut().unwrap().output_mut().unwrap()
+                        )),*);
+                        $name.set(::std::option::Option::Some($crate::__private::maybe_done(future)));
+                    }
+                    match $name.as_mut().as_pin_mut() {
+                        ::std::option::Option::Some(node) => {
+                            if ::std::future::Future::poll(node, cx).is_pending() {
+                                pending = true;
+                            }
+                        }
+                        ::std::option::Option::None => pending = true,
+                    }
+                )+
+                if pending {
+                    $crate::__private::Poll::Pending
+                } else {
+                    $crate::__private::Poll::Ready($output)
+                }
+            })
+            .await
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::channel::oneshot;
+    use futures::executor::block_on;
+    use futures::future::pending;
+    use std::sync::Mutex;
+
+    async fn one() -> i32 {
+        1
+    }
+
+    async fn sum(lhs: i32, rhs: i32) -> i32 {
+        lhs + rhs
+    }
+
+    #[test]
+    fn test_static_dag() {
+        let output = block_on(static_dag! {
+            _1 = one();
+            _2 = one();
+            _3 = sum(_1, _2);
+            _4 = sum(_3, _1);
+            => (_3, _4)
+        });
+        assert_eq!(output, (2, 3));
+    }
+
+    #[test]
+    fn test_static_dag_parallelism() {
+        let (sender, receiver) = oneshot::channel();
+        let sender = &Mutex::new(Some(sender));
+        let receiver = &Mutex::new(Some(receiver));
+        // `slow` completes only after `fast` has run.
+        let slow = move || async move {
+            let receiver = receiver.lock().unwrap().take().unwrap();
+            receiver.await.unwrap()
+        };
+        let fast = move |v: i32| async move {
+            sender.lock().unwrap().take().unwrap().send(v).unwrap();
+            v
+        };
+        let output = block_on(static_dag! {
+            a = one();
+            c = slow();
+            b = fast(a);
+            d = sum(b, c);
+            => d
+        });
+        assert_eq!(output, 2);
+    }
+
+    #[test]
+    fn test_static_dag_waits_for_all() {
+        let never = || pending::<()>();
+        let dag = static_dag! {
+            a = one();
+            _b = never();
+            => a
+        };
+        assert!(futures::FutureExt::now_or_never(dag).is_none());
+    }
+}
@@ -6,7 +6,7 @@ use std::future::Future;
 use std::marker::PhantomData;
 
 /// An async task.
-pub trait TryTask<'a>: std::fmt::Debug {
+pub trait TryTask<'a>: std::fmt::Debug + Send {
     /// Tuple of inputs.
     type Inputs: Tuple;
 
@@ -36,7 +36,7 @@ pub trait IntoTryTask<'a, Args, Ok, Err> {
 pub struct FnOnceTask<Fn, Ok, Err, Fut, Args> {
     function: Fn,
     ok: PhantomData<Ok>,
-    err: PhantomData<Err>,
+    err: PhantomData<fn() -> Err>,
     fut: PhantomData<Fut>,
     args: PhantomData<Args>,
 }
@@ -64,12 +64,18 @@ impl<Fn, Ok, Err, Fut, Args> std::fmt::Debug for FnOnceTask<Fn, Ok, Err, Fut, Ar
     }
 }
 
+impl<Fn: Clone, Ok, Err, Fut, Args> Clone for FnOnceTask<Fn, Ok, Err, Fut, Args> {
+    fn clone(&self) -> Self {
+        FnOnceTask::new(self.function.clone())
+    }
+}
+
 macro_rules! task_impl {
     ($N:literal) => {
         seq!(i in 0..$N {
             impl<'a, Fn, Ok, Err, Fut, #(I~i,)*> IntoTryTask<'a, (#(I~i,)*), Ok, Err> for Fn
             where
-                Fn: FnOnce(#(I~i,)*) -> Fut + 'a,
+                Fn: FnOnce(#(I~i,)*) -> Fut + Send + 'a,
                 Ok: IntoAny,
                 Err: 'a,
                 Fut: Future<Output = Result<Ok, Err>> + Send + 'a,
@@ -86,7 +92,7 @@ macro_rules! task_impl {
 
             impl<'a, Fn, Ok, Err, Fut, #(I~i,)*> TryTask<'a> for FnOnceTask<Fn, Ok, Err, Fut, (#(I~i,)*)>
             where
-                Fn: FnOnce(#(I~i,)*) -> Fut,
+                Fn: FnOnce(#(I~i,)*) -> Fut + Send,
                 Ok: IntoAny,
                 Err: 'a,
                 Fut: Future<Output = Result<Ok, Err>> + Send + 'a,
@@ -110,6 +116,18 @@ seq!(N in 0..=12 {
     task_impl!(N);
 });
 
+mod fields;
 mod infallible;
-
+mod local;
+mod stream;
+mod sync;
+#[cfg(feature = "tower")]
+mod tower;
+
+#[cfg(feature = "tower")]
+pub use self::tower::ServiceTask;
+pub use fields::*;
 pub use infallible::*;
+pub use local::*;
+pub use stream::*;
+pub use sync::*;
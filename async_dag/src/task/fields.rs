@@ -0,0 +1,85 @@
+use super::IntoInfallibleTask;
+use super::IntoTryTask;
+use super::TryTask;
+use crate::any::IntoAny;
+use crate::tuple::Tuple;
+use futures::future::BoxFuture;
+use std::any::type_name;
+use std::convert::Infallible;
+use std::marker::PhantomData;
+
+/// A struct whose fields are a task's inputs, usually implemented by `#[derive(Task)]`.
+///
+/// Add it to a graph by [`TryGraph::add_struct_task`](crate::TryGraph::add_struct_task).
+pub trait FieldsTask: Sized + Send + 'static {
+    /// Tuple of the field types.
+    type Inputs: Tuple;
+
+    /// Successful output.
+    type Ok: IntoAny;
+
+    /// Error output.
+    type Err: 'static;
+
+    /// The field names.
+    const INPUT_NAMES: &'static [&'static str];
+
+    /// Builds the struct from its fields.
+    fn from_inputs(inputs: Self::Inputs) -> Self;
+
+    /// Runs the task.
+    fn call(self) -> BoxFuture<'static, Result<Self::Ok, Self::Err>>;
+}
+
+/// A [`TryTask`] that builds a [`FieldsTask`] from its inputs and runs it.
+pub struct StructTask<T> {
+    task: PhantomData<fn() -> T>,
+}
+
+impl<T> StructTask<T> {
+    /// Creates the task.
+    pub fn new() -> Self {
+        Self { task: PhantomData }
+    }
+}
+
+impl<T> Default for StructTask<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> std::fmt::Debug for StructTask<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!("StructTask<{}>", type_name::<T>()))
+    }
+}
+
+impl<'a, T: FieldsTask> TryTask<'a> for StructTask<T> {
+    type Inputs = T::Inputs;
+    type Ok = T::Ok;
+    type Err = T::Err;
+    type Future = BoxFuture<'static, Result<T::Ok, T::Err>>;
+
+    fn run(self, inputs: Self::Inputs) -> Self::Future {
+        T::from_inputs(inputs).call()
+    }
+}
+
+impl<'a, T: FieldsTask> IntoTryTask<'a, T::Inputs, T::Ok, T::Err> for StructTask<T> {
+    type Task = Self;
+
+    fn into_task(self) -> Self::Task {
+        self
+    }
+}
+
+impl<'a, T: FieldsTask<Err = Infallible>> IntoInfallibleTask<'a, T::Inputs, T::Ok>
+    for StructTask<T>
+{
+    type Task = Self;
+
+    fn into_task(self) -> Self::Task {
+        self
+    }
+}
@@ -46,12 +46,18 @@ impl<Fn, Ok, Fut, Args> std::fmt::Debug for InfallibleFnOnceTask<Fn, Ok, Fut, Ar
     }
 }
 
+impl<Fn: Clone, Ok, Fut, Args> Clone for InfallibleFnOnceTask<Fn, Ok, Fut, Args> {
+    fn clone(&self) -> Self {
+        InfallibleFnOnceTask::new(self.function.clone())
+    }
+}
+
 macro_rules! task_impl {
     ($N:literal) => {
         seq!(i in 0..$N {
             impl<'a, Fn, Ok, Fut, #(I~i,)*> IntoInfallibleTask<'a, (#(I~i,)*), Ok> for Fn
             where
-                Fn: FnOnce(#(I~i,)*) -> Fut + 'a,
+                Fn: FnOnce(#(I~i,)*) -> Fut + Send + 'a,
                 Ok: IntoAny,
                 Fut: Future<Output = Ok> + Send + 'a,
                 #(
@@ -67,7 +73,7 @@ macro_rules! task_impl {
 
             impl<'a, Fn, Ok, Fut, #(I~i,)*> TryTask<'a> for InfallibleFnOnceTask<Fn, Ok, Fut, (#(I~i,)*)>
             where
-                Fn: FnOnce(#(I~i,)*) -> Fut,
+                Fn: FnOnce(#(I~i,)*) -> Fut + Send,
                 Ok: IntoAny,
                 Fut: Future<Output = Ok> + Send + 'a,
                 #(
@@ -0,0 +1,174 @@
+use super::TryTask;
+use crate::any::IntoAny;
+use futures::future::FutureExt;
+use futures::future::Map;
+use send_wrapper::SendWrapper;
+use seq_macro::seq;
+use std::any::type_name;
+use std::convert::Infallible;
+use std::future::Future;
+use std::marker::PhantomData;
+
+/// Conversion to a [`TryTask`] whose function and future don't have to be [`Send`].
+///
+/// The function and future are wrapped in [`SendWrapper`]s,
+/// so such tasks are only added to a [`LocalTryGraph`](crate::LocalTryGraph), which can't leave the thread.
+pub trait IntoLocalTryTask<'a, Args, Ok, Err> {
+    /// The [`TryTask`] type.
+    type Task: TryTask<'a, Ok = Ok, Err = Err> + 'a;
+
+    /// The conversion.
+    fn into_task(self) -> Self::Task;
+}
+
+/// Conversion to a [`Infallible`] [`TryTask`] whose function and future don't have to be [`Send`].
+///
+/// See [`IntoLocalTryTask`].
+pub trait IntoLocalInfallibleTask<'a, Args, Ok> {
+    /// The [`TryTask`] type.
+    type Task: TryTask<'a, Ok = Ok, Err = Infallible> + 'a;
+
+    /// The conversion.
+    fn into_task(self) -> Self::Task;
+}
+
+/// A thread local [`TryTask`] for types that implement [`FnOnce`].
+pub struct LocalFnOnceTask<Fn, Ok, Err, Fut, Args> {
+    function: SendWrapper<Fn>,
+    ok: PhantomData<Ok>,
+    err: PhantomData<fn() -> Err>,
+    fut: PhantomData<fn() -> Fut>,
+    args: PhantomData<Args>,
+}
+
+impl<Fn, Ok, Err, Fut, Args> LocalFnOnceTask<Fn, Ok, Err, Fut, Args> {
+    fn new(function: Fn) -> Self {
+        LocalFnOnceTask {
+            function: SendWrapper::new(function),
+            ok: Default::default(),
+            err: Default::default(),
+            fut: Default::default(),
+            args: Default::default(),
+        }
+    }
+}
+
+impl<Fn, Ok, Err, Fut, Args> std::fmt::Debug for LocalFnOnceTask<Fn, Ok, Err, Fut, Args> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!(
+            "LocalFnOnceTask{} -> impl Future<Output = Result<{}, {}> {{ ... }}",
+            type_name::<Args>(),
+            type_name::<Ok>(),
+            type_name::<Err>(),
+        ))
+    }
+}
+
+/// A thread local [`Infallible`] [`TryTask`] for types that implement [`FnOnce`].
+pub struct LocalInfallibleFnOnceTask<Fn, Ok, Fut, Args> {
+    function: SendWrapper<Fn>,
+    ok: PhantomData<Ok>,
+    fut: PhantomData<fn() -> Fut>,
+    args: PhantomData<Args>,
+}
+
+impl<Fn, Ok, Fut, Args> LocalInfallibleFnOnceTask<Fn, Ok, Fut, Args> {
+    fn new(function: Fn) -> Self {
+        LocalInfallibleFnOnceTask {
+            function: SendWrapper::new(function),
+            ok: Default::default(),
+            fut: Default::default(),
+            args: Default::default(),
+        }
+    }
+}
+
+impl<Fn, Ok, Fut, Args> std::fmt::Debug for LocalInfallibleFnOnceTask<Fn, Ok, Fut, Args> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!(
+            "LocalInfallibleFnOnceTask{} -> impl Future<Output = {}> {{ ... }}",
+            type_name::<Args>(),
+            type_name::<Ok>(),
+        ))
+    }
+}
+
+macro_rules! task_impl {
+    ($N:literal) => {
+        seq!(i in 0..$N {
+            impl<'a, Fn, Ok, Err, Fut, #(I~i,)*> IntoLocalTryTask<'a, (#(I~i,)*), Ok, Err> for Fn
+            where
+                Fn: FnOnce(#(I~i,)*) -> Fut + 'a,
+                Ok: IntoAny,
+                Err: 'a,
+                Fut: Future<Output = Result<Ok, Err>> + 'a,
+                #(
+                    I~i: IntoAny,
+                )*
+            {
+                type Task = LocalFnOnceTask<Fn, Ok, Err, Fut, (#(I~i,)*)>;
+
+                fn into_task(self) -> Self::Task {
+                    LocalFnOnceTask::new(self)
+                }
+            }
+
+            impl<'a, Fn, Ok, Err, Fut, #(I~i,)*> TryTask<'a> for LocalFnOnceTask<Fn, Ok, Err, Fut, (#(I~i,)*)>
+            where
+                Fn: FnOnce(#(I~i,)*) -> Fut,
+                Ok: IntoAny,
+                Err: 'a,
+                Fut: Future<Output = Result<Ok, Err>> + 'a,
+                #(
+                    I~i: IntoAny,
+                )*
+            {
+                type Inputs = (#(I~i,)*);
+                type Ok = Ok;
+                type Err = Err;
+                type Future = SendWrapper<Fut>;
+                fn run(self, (#(v~i,)*): Self::Inputs) -> Self::Future {
+                    SendWrapper::new((self.function.take())(#(v~i,)*))
+                }
+            }
+
+            impl<'a, Fn, Ok, Fut, #(I~i,)*> IntoLocalInfallibleTask<'a, (#(I~i,)*), Ok> for Fn
+            where
+                Fn: FnOnce(#(I~i,)*) -> Fut + 'a,
+                Ok: IntoAny,
+                Fut: Future<Output = Ok> + 'a,
+                #(
+                    I~i: IntoAny,
+                )*
+            {
+                type Task = LocalInfallibleFnOnceTask<Fn, Ok, Fut, (#(I~i,)*)>;
+
+                fn into_task(self) -> Self::Task {
+                    LocalInfallibleFnOnceTask::new(self)
+                }
+            }
+
+            impl<'a, Fn, Ok, Fut, #(I~i,)*> TryTask<'a> for LocalInfallibleFnOnceTask<Fn, Ok, Fut, (#(I~i,)*)>
+            where
+                Fn: FnOnce(#(I~i,)*) -> Fut,
+                Ok: IntoAny,
+                Fut: Future<Output = Ok> + 'a,
+                #(
+                    I~i: IntoAny,
+                )*
+            {
+                type Inputs = (#(I~i,)*);
+                type Ok = Ok;
+                type Err = Infallible;
+                type Future = Map<SendWrapper<Fut>, fn(Ok) -> Result<Ok, Infallible>>;
+                fn run(self, (#(v~i,)*): Self::Inputs) -> Self::Future {
+                    SendWrapper::new((self.function.take())(#(v~i,)*)).map(Ok)
+                }
+            }
+        });
+    };
+}
+
+seq!(N in 0..=12 {
+    task_impl!(N);
+});
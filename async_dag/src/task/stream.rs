@@ -0,0 +1,291 @@
+use super::IntoInfallibleTask;
+use super::IntoTryTask;
+use super::TryTask;
+use crate::any::IntoAny;
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use futures::FutureExt;
+use futures::Stream;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use std::any::type_name;
+use std::convert::Infallible;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+/// A stream of items as a node output.
+///
+/// Clones share the same stream, which can only be consumed once,
+/// so a node outputting an item stream must have a single [`ForEachTask`] or [`TryForEachTask`] child.
+/// A second consumer panics when it runs.
+pub struct ItemStream<T> {
+    stream: Arc<Mutex<Option<BoxStream<'static, T>>>>,
+}
+
+impl<T> ItemStream<T> {
+    /// Wraps `stream`.
+    pub fn new(stream: impl Stream<Item = T> + Send + 'static) -> Self {
+        Self {
+            stream: Arc::new(Mutex::new(Some(stream.boxed()))),
+        }
+    }
+
+    /// Takes the stream out.
+    ///
+    /// **Panics** if the stream has been taken.
+    pub fn take(&self) -> BoxStream<'static, T> {
+        self.stream
+            .lock()
+            .unwrap()
+            .take()
+            .expect("Item stream is already consumed")
+    }
+}
+
+impl<T> Clone for ItemStream<T> {
+    fn clone(&self) -> Self {
+        Self {
+            stream: self.stream.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for ItemStream<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!("ItemStream<{}>", type_name::<T>()))
+    }
+}
+
+/// How many item futures a [`ForEachTask`] or [`TryForEachTask`] runs concurrently by default.
+pub const DEFAULT_FOR_EACH_LIMIT: usize = 16;
+
+/// A [`TryTask`] that calls a function once per item of its [`ItemStream`] input,
+/// running the returned futures concurrently as items arrive, up to a limit,
+/// and gathers their outputs into a [`Vec`] in item order.
+///
+/// **Panics** when run if the stream was already consumed by another child.
+pub struct ForEachTask<F, T, Fut> {
+    function: F,
+    limit: usize,
+    phantom: PhantomData<fn(T) -> Fut>,
+}
+
+impl<F, T, Fut> ForEachTask<F, T, Fut> {
+    /// Wraps `function`, running up to [`DEFAULT_FOR_EACH_LIMIT`] item futures at once.
+    pub fn new(function: F) -> Self {
+        Self {
+            function,
+            limit: DEFAULT_FOR_EACH_LIMIT,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Runs up to `limit` item futures at once.
+    ///
+    /// **Panics** if `limit` is zero.
+    pub fn limit(mut self, limit: usize) -> Self {
+        assert!(limit > 0, "For each limit must be positive");
+        self.limit = limit;
+        self
+    }
+}
+
+impl<F, T, Fut> std::fmt::Debug for ForEachTask<F, T, Fut> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!("ForEachTask(ItemStream<{}>)", type_name::<T>()))
+    }
+}
+
+/// A fallible [`ForEachTask`], which fails on the first failed item.
+pub struct TryForEachTask<F, T, Fut> {
+    function: F,
+    limit: usize,
+    phantom: PhantomData<fn(T) -> Fut>,
+}
+
+impl<F, T, Fut> TryForEachTask<F, T, Fut> {
+    /// Wraps `function`, running up to [`DEFAULT_FOR_EACH_LIMIT`] item futures at once.
+    pub fn new(function: F) -> Self {
+        Self {
+            function,
+            limit: DEFAULT_FOR_EACH_LIMIT,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Runs up to `limit` item futures at once.
+    ///
+    /// **Panics** if `limit` is zero.
+    pub fn limit(mut self, limit: usize) -> Self {
+        assert!(limit > 0, "For each limit must be positive");
+        self.limit = limit;
+        self
+    }
+}
+
+impl<F, T, Fut> std::fmt::Debug for TryForEachTask<F, T, Fut> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!("TryForEachTask(ItemStream<{}>)", type_name::<T>()))
+    }
+}
+
+impl<'a, F, T, Fut, Ok> TryTask<'a> for ForEachTask<F, T, Fut>
+where
+    F: FnMut(T) -> Fut + Send + 'a,
+    T: IntoAny,
+    Fut: Future<Output = Ok> + Send + 'a,
+    Ok: IntoAny + Clone,
+{
+    type Inputs = (ItemStream<T>,);
+    type Ok = Vec<Ok>;
+    type Err = Infallible;
+    type Future = BoxFuture<'a, Result<Vec<Ok>, Infallible>>;
+
+    fn run(self, (stream,): Self::Inputs) -> Self::Future {
+        stream
+            .take()
+            .map(self.function)
+            .buffered(self.limit)
+            .collect()
+            .map(Ok)
+            .boxed()
+    }
+}
+
+impl<'a, F, T, Fut, Ok> IntoInfallibleTask<'a, (ItemStream<T>,), Vec<Ok>> for ForEachTask<F, T, Fut>
+where
+    F: FnMut(T) -> Fut + Send + 'a,
+    T: IntoAny,
+    Fut: Future<Output = Ok> + Send + 'a,
+    Ok: IntoAny + Clone,
+{
+    type Task = Self;
+
+    fn into_task(self) -> Self::Task {
+        self
+    }
+}
+
+impl<'a, F, T, Fut, Ok, Err> TryTask<'a> for TryForEachTask<F, T, Fut>
+where
+    F: FnMut(T) -> Fut + Send + 'a,
+    T: IntoAny,
+    Fut: Future<Output = Result<Ok, Err>> + Send + 'a,
+    Ok: IntoAny + Clone,
+    Err: Send + 'a,
+{
+    type Inputs = (ItemStream<T>,);
+    type Ok = Vec<Ok>;
+    type Err = Err;
+    type Future = BoxFuture<'a, Result<Vec<Ok>, Err>>;
+
+    fn run(mut self, (stream,): Self::Inputs) -> Self::Future {
+        stream
+            .take()
+            .map(move |item| Ok((self.function)(item)))
+            .try_buffered(self.limit)
+            .try_collect()
+            .boxed()
+    }
+}
+
+impl<'a, F, T, Fut, Ok, Err> IntoTryTask<'a, (ItemStream<T>,), Vec<Ok>, Err>
+    for TryForEachTask<F, T, Fut>
+where
+    F: FnMut(T) -> Fut + Send + 'a,
+    T: IntoAny,
+    Fut: Future<Output = Result<Ok, Err>> + Send + 'a,
+    Ok: IntoAny + Clone,
+    Err: Send + 'a,
+{
+    type Task = Self;
+
+    fn into_task(self) -> Self::Task {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Graph;
+    use crate::TryGraph;
+    use futures::executor::block_on;
+    use futures::stream;
+
+    #[test]
+    fn test_for_each() {
+        let mut graph = Graph::new();
+        let source = graph.add_task(|| async { ItemStream::new(stream::iter(1..=3)) });
+        let doubled = graph
+            .add_child_task(source, ForEachTask::new(|v: i32| async move { v * 2 }), 0)
+            .unwrap();
+        let sum = graph
+            .add_child_task(
+                doubled,
+                |v: Vec<i32>| async move { v.iter().sum::<i32>() },
+                0,
+            )
+            .unwrap();
+        block_on(graph.run());
+        assert_eq!(graph.get_value::<Vec<i32>>(doubled).unwrap(), [2, 4, 6]);
+        assert_eq!(graph.get_value::<i32>(sum).unwrap(), 12);
+    }
+
+    #[test]
+    fn test_try_for_each() {
+        let mut graph = TryGraph::<i32>::new();
+        let source = graph.add_try_task(|| async { Ok(ItemStream::new(stream::iter(1..=3))) });
+        let _ = graph
+            .add_child_try_task(
+                source,
+                TryForEachTask::new(|v: i32| async move {
+                    if v == 2 {
+                        Err(v)
+                    } else {
+                        Ok(v)
+                    }
+                }),
+                0,
+            )
+            .unwrap();
+        assert_eq!(block_on(graph.try_run()).unwrap_err(), 2);
+    }
+
+    #[test]
+    fn test_for_each_limit() {
+        use crate::test_util::yield_now;
+        use std::sync::atomic::AtomicUsize;
+        use std::sync::atomic::Ordering;
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let mut graph = Graph::new();
+        let source = graph.add_task(|| async { ItemStream::new(stream::iter(0..10)) });
+        let task = ForEachTask::new({
+            let running = running.clone();
+            let peak = peak.clone();
+            move |v: i32| {
+                let running = running.clone();
+                let peak = peak.clone();
+                async move {
+                    let _ = peak
+                        .fetch_max(running.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+                    yield_now().await;
+                    let _ = running.fetch_sub(1, Ordering::SeqCst);
+                    v
+                }
+            }
+        })
+        .limit(3);
+        let all = graph.add_child_task(source, task, 0).unwrap();
+        block_on(graph.run());
+        assert_eq!(
+            graph.get_value::<Vec<i32>>(all).unwrap(),
+            (0..10).collect::<Vec<_>>()
+        );
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,202 @@
+use super::TryTask;
+use crate::any::IntoAny;
+use seq_macro::seq;
+use std::any::type_name;
+use std::convert::Infallible;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+
+/// Conversion to a [`TryTask`] from a synchronous function.
+///
+/// The function is called when the output future is first polled, which is then immediately ready.
+pub trait IntoSyncTryTask<'a, Args, Ok, Err> {
+    /// The [`TryTask`] type.
+    type Task: TryTask<'a, Ok = Ok, Err = Err> + 'a;
+
+    /// The conversion.
+    fn into_task(self) -> Self::Task;
+}
+
+/// Conversion to a [`Infallible`] [`TryTask`] from a synchronous function.
+///
+/// See [`IntoSyncTryTask`].
+pub trait IntoSyncInfallibleTask<'a, Args, Ok> {
+    /// The [`TryTask`] type.
+    type Task: TryTask<'a, Ok = Ok, Err = Infallible> + 'a;
+
+    /// The conversion.
+    fn into_task(self) -> Self::Task;
+}
+
+/// A future that calls a synchronous function when first polled.
+pub struct SyncFuture<'a, T>(Option<Box<dyn FnOnce() -> T + Send + 'a>>);
+
+impl<'a, T> SyncFuture<'a, T> {
+    fn new(function: impl FnOnce() -> T + Send + 'a) -> Self {
+        Self(Some(Box::new(function)))
+    }
+}
+
+impl<'a, T> Future for SyncFuture<'a, T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<T> {
+        let function = self.0.take().expect("SyncFuture polled after completion");
+        Poll::Ready(function())
+    }
+}
+
+impl<'a, T> std::fmt::Debug for SyncFuture<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(&format!("SyncFuture<{}>", type_name::<T>()))
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [`TryTask`] for synchronous functions that return a [`Result`].
+pub struct SyncFnOnceTask<Fn, Ok, Err, Args> {
+    function: Fn,
+    ok: PhantomData<Ok>,
+    err: PhantomData<fn() -> Err>,
+    args: PhantomData<Args>,
+}
+
+impl<Fn, Ok, Err, Args> SyncFnOnceTask<Fn, Ok, Err, Args> {
+    fn new(function: Fn) -> Self {
+        SyncFnOnceTask {
+            function,
+            ok: Default::default(),
+            err: Default::default(),
+            args: Default::default(),
+        }
+    }
+}
+
+impl<Fn, Ok, Err, Args> std::fmt::Debug for SyncFnOnceTask<Fn, Ok, Err, Args> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!(
+            "SyncFnOnceTask{} -> Result<{}, {}> {{ ... }}",
+            type_name::<Args>(),
+            type_name::<Ok>(),
+            type_name::<Err>(),
+        ))
+    }
+}
+
+impl<Fn: Clone, Ok, Err, Args> Clone for SyncFnOnceTask<Fn, Ok, Err, Args> {
+    fn clone(&self) -> Self {
+        SyncFnOnceTask::new(self.function.clone())
+    }
+}
+
+/// An [`Infallible`] [`TryTask`] for synchronous functions.
+pub struct SyncInfallibleFnOnceTask<Fn, Ok, Args> {
+    function: Fn,
+    ok: PhantomData<Ok>,
+    args: PhantomData<Args>,
+}
+
+impl<Fn, Ok, Args> SyncInfallibleFnOnceTask<Fn, Ok, Args> {
+    fn new(function: Fn) -> Self {
+        SyncInfallibleFnOnceTask {
+            function,
+            ok: Default::default(),
+            args: Default::default(),
+        }
+    }
+}
+
+impl<Fn, Ok, Args> std::fmt::Debug for SyncInfallibleFnOnceTask<Fn, Ok, Args> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!(
+            "SyncInfallibleFnOnceTask{} -> {} {{ ... }}",
+            type_name::<Args>(),
+            type_name::<Ok>(),
+        ))
+    }
+}
+
+impl<Fn: Clone, Ok, Args> Clone for SyncInfallibleFnOnceTask<Fn, Ok, Args> {
+    fn clone(&self) -> Self {
+        SyncInfallibleFnOnceTask::new(self.function.clone())
+    }
+}
+
+macro_rules! task_impl {
+    ($N:literal) => {
+        seq!(i in 0..$N {
+            impl<'a, Fn, Ok, Err, #(I~i,)*> IntoSyncTryTask<'a, (#(I~i,)*), Ok, Err> for Fn
+            where
+                Fn: FnOnce(#(I~i,)*) -> Result<Ok, Err> + Send + 'a,
+                Ok: IntoAny,
+                Err: Send + 'a,
+                #(
+                    I~i: IntoAny,
+                )*
+            {
+                type Task = SyncFnOnceTask<Fn, Ok, Err, (#(I~i,)*)>;
+
+                fn into_task(self) -> Self::Task {
+                    SyncFnOnceTask::new(self)
+                }
+            }
+
+            impl<'a, Fn, Ok, Err, #(I~i,)*> TryTask<'a> for SyncFnOnceTask<Fn, Ok, Err, (#(I~i,)*)>
+            where
+                Fn: FnOnce(#(I~i,)*) -> Result<Ok, Err> + Send + 'a,
+                Ok: IntoAny,
+                Err: Send + 'a,
+                #(
+                    I~i: IntoAny,
+                )*
+            {
+                type Inputs = (#(I~i,)*);
+                type Ok = Ok;
+                type Err = Err;
+                type Future = SyncFuture<'a, Result<Ok, Err>>;
+                fn run(self, (#(v~i,)*): Self::Inputs) -> Self::Future {
+                    SyncFuture::new(move || (self.function)(#(v~i,)*))
+                }
+            }
+
+            impl<'a, Fn, Ok, #(I~i,)*> IntoSyncInfallibleTask<'a, (#(I~i,)*), Ok> for Fn
+            where
+                Fn: FnOnce(#(I~i,)*) -> Ok + Send + 'a,
+                Ok: IntoAny,
+                #(
+                    I~i: IntoAny,
+                )*
+            {
+                type Task = SyncInfallibleFnOnceTask<Fn, Ok, (#(I~i,)*)>;
+
+                fn into_task(self) -> Self::Task {
+                    SyncInfallibleFnOnceTask::new(self)
+                }
+            }
+
+            impl<'a, Fn, Ok, #(I~i,)*> TryTask<'a> for SyncInfallibleFnOnceTask<Fn, Ok, (#(I~i,)*)>
+            where
+                Fn: FnOnce(#(I~i,)*) -> Ok + Send + 'a,
+                Ok: IntoAny,
+                #(
+                    I~i: IntoAny,
+                )*
+            {
+                type Inputs = (#(I~i,)*);
+                type Ok = Ok;
+                type Err = Infallible;
+                type Future = SyncFuture<'a, Result<Ok, Infallible>>;
+                fn run(self, (#(v~i,)*): Self::Inputs) -> Self::Future {
+                    SyncFuture::new(move || Ok((self.function)(#(v~i,)*)))
+                }
+            }
+        });
+    };
+}
+
+seq!(N in 0..=12 {
+    task_impl!(N);
+});
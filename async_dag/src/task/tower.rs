@@ -0,0 +1,94 @@
+use super::IntoTryTask;
+use super::TryTask;
+use crate::any::IntoAny;
+use futures::future::poll_fn;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use std::any::type_name;
+use std::marker::PhantomData;
+use tower::Service;
+
+/// A [`TryTask`] that makes one call to a [`tower::Service`] with its only input.
+///
+/// Middleware-wrapped services (retry, rate limit, load shedding etc.) can be added to a graph as tasks this way.
+pub struct ServiceTask<S, Req> {
+    service: S,
+    req: PhantomData<fn(Req)>,
+}
+
+impl<S, Req> ServiceTask<S, Req> {
+    /// Wraps `service`.
+    pub fn new(service: S) -> Self {
+        ServiceTask {
+            service,
+            req: PhantomData,
+        }
+    }
+}
+
+impl<S, Req> std::fmt::Debug for ServiceTask<S, Req> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!(
+            "ServiceTask<{}>({})",
+            type_name::<S>(),
+            type_name::<Req>(),
+        ))
+    }
+}
+
+impl<'a, S, Req> TryTask<'a> for ServiceTask<S, Req>
+where
+    S: Service<Req> + Send + 'a,
+    S::Response: IntoAny,
+    S::Error: 'a,
+    S::Future: Send + 'a,
+    Req: IntoAny,
+{
+    type Inputs = (Req,);
+    type Ok = S::Response;
+    type Err = S::Error;
+    type Future = BoxFuture<'a, Result<S::Response, S::Error>>;
+
+    fn run(mut self, (req,): Self::Inputs) -> Self::Future {
+        async move {
+            poll_fn(|cx| self.service.poll_ready(cx)).await?;
+            self.service.call(req).await
+        }
+        .boxed()
+    }
+}
+
+impl<'a, S, Req> IntoTryTask<'a, (Req,), S::Response, S::Error> for ServiceTask<S, Req>
+where
+    S: Service<Req> + Send + 'a,
+    S::Response: IntoAny,
+    S::Error: 'a,
+    S::Future: Send + 'a,
+    Req: IntoAny,
+{
+    type Task = Self;
+
+    fn into_task(self) -> Self::Task {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ServiceTask;
+    use crate::TryGraph;
+    use futures::executor::block_on;
+    use tower::service_fn;
+
+    #[test]
+    fn test_service_task() {
+        let mut graph = TryGraph::<()>::new();
+        let input = graph.add_try_task(|| async { Ok(1) });
+        let double = service_fn(|v: i32| async move { Ok::<_, ()>(v * 2) });
+        let output = graph
+            .add_child_try_task(input, ServiceTask::new(double), 0)
+            .unwrap();
+        block_on(graph.try_run()).unwrap();
+        assert_eq!(graph.get_value::<i32>(output).unwrap(), 2);
+    }
+}
@@ -0,0 +1,274 @@
+//! Utilities for testing graphs: virtual time, run tracing and random graphs.
+
+mod random;
+
+use crate::Clock;
+use crate::NodeIndex;
+use crate::TaskFuture;
+use crate::TaskLayer;
+use futures::future::BoxFuture;
+use futures::task::waker;
+use futures::task::ArcWake;
+use futures::FutureExt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Waker;
+use std::time::Duration;
+use std::time::Instant;
+
+pub use random::*;
+
+#[derive(Debug)]
+struct ClockState {
+    start: Instant,
+    now: Instant,
+    sleepers: Vec<(Instant, Waker)>,
+}
+
+/// A [`Clock`] whose time only moves when told to.
+///
+/// Pass it to [`RunOptions::clock`](crate::RunOptions::clock) and drive the run with [`block_on`].
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    state: Arc<Mutex<ClockState>>,
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockClock {
+    /// Creates a clock stopped at the current time.
+    pub fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            state: Arc::new(Mutex::new(ClockState {
+                start: now,
+                now,
+                sleepers: vec![],
+            })),
+        }
+    }
+
+    /// How much time has passed since creation.
+    pub fn elapsed(&self) -> Duration {
+        let state = self.state.lock().unwrap();
+        state.now - state.start
+    }
+
+    /// Moves time forward by `duration`, waking due sleeps.
+    pub fn advance(&self, duration: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.now += duration;
+        let now = state.now;
+        state.sleepers.retain(|(deadline, waker)| {
+            if *deadline <= now {
+                waker.wake_by_ref();
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    /// Moves time forward to the earliest pending sleep, returning `false` if there's none.
+    pub fn advance_to_next(&self) -> bool {
+        let next = {
+            let state = self.state.lock().unwrap();
+            match state.sleepers.iter().map(|(deadline, _)| *deadline).min() {
+                Some(deadline) => deadline.saturating_duration_since(state.now),
+                None => return false,
+            }
+        };
+        self.advance(next);
+        true
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.state.lock().unwrap().now
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        MockSleep {
+            state: self.state.clone(),
+            deadline: self.now() + duration,
+        }
+        .boxed()
+    }
+}
+
+struct MockSleep {
+    state: Arc<Mutex<ClockState>>,
+    deadline: Instant,
+}
+
+impl Future for MockSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.now >= self.deadline {
+            Poll::Ready(())
+        } else {
+            state.sleepers.push((self.deadline, cx.waker().clone()));
+            Poll::Pending
+        }
+    }
+}
+
+struct Flag(AtomicBool);
+
+impl ArcWake for Flag {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Runs `future` to completion on the current thread,
+/// advancing `clock` to the next sleep whenever the future can't progress otherwise.
+///
+/// So sleeps complete instantly in real time, in the order of their deadlines.
+///
+/// **Panics** if the future can't progress and nothing is sleeping on `clock`.
+pub fn block_on<F: Future>(clock: &MockClock, future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let flag = Arc::new(Flag(AtomicBool::new(false)));
+    let waker = waker(flag.clone());
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        if !flag.0.swap(false, Ordering::SeqCst) {
+            assert!(clock.advance_to_next(), "Future is stalled");
+            let _ = flag.0.swap(false, Ordering::SeqCst);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct TraceState {
+    // Sequence numbers of task starts and completions.
+    started: Vec<(NodeIndex, usize)>,
+    completed: Vec<(NodeIndex, usize)>,
+    sequence: usize,
+}
+
+/// A [`TaskLayer`] that records which nodes ran, in what order.
+///
+/// Add a clone to the graph by [`TryGraph::layer`](crate::TryGraph::layer) and inspect the original.
+#[derive(Debug, Clone, Default)]
+pub struct Trace {
+    state: Arc<Mutex<TraceState>>,
+}
+
+impl Trace {
+    /// Creates an empty trace.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `node` has started running.
+    pub fn ran(&self, node: NodeIndex) -> bool {
+        self.sequence(node, |state| &state.started).is_some()
+    }
+
+    /// Nodes that have started, in start order.
+    pub fn started(&self) -> Vec<NodeIndex> {
+        let state = self.state.lock().unwrap();
+        state.started.iter().map(|(node, _)| *node).collect()
+    }
+
+    /// **Panics** unless `before` completed before `after` started.
+    pub fn assert_before(&self, before: NodeIndex, after: NodeIndex) {
+        let completed = self.sequence(before, |state| &state.completed);
+        let started = self.sequence(after, |state| &state.started);
+        match (completed, started) {
+            (Some(completed), Some(started)) if completed < started => {}
+            _ => panic!(
+                "Expecting node {} to complete before node {} starts",
+                before.index(),
+                after.index()
+            ),
+        }
+    }
+
+    fn sequence(
+        &self,
+        node: NodeIndex,
+        events: impl Fn(&TraceState) -> &Vec<(NodeIndex, usize)>,
+    ) -> Option<usize> {
+        let state = self.state.lock().unwrap();
+        events(&state)
+            .iter()
+            .find(|(index, _)| *index == node)
+            .map(|(_, sequence)| *sequence)
+    }
+
+    fn record(&self, node: NodeIndex, completed: bool) {
+        let mut state = self.state.lock().unwrap();
+        let sequence = state.sequence;
+        state.sequence += 1;
+        let events = if completed {
+            &mut state.completed
+        } else {
+            &mut state.started
+        };
+        events.push((node, sequence));
+    }
+}
+
+impl<'a, Err: 'a> TaskLayer<'a, Err> for Trace {
+    fn layer(&self, node: NodeIndex, future: TaskFuture<'a, Err>) -> TaskFuture<'a, Err> {
+        let trace = self.clone();
+        async move {
+            trace.record(node, false);
+            let output = future.await;
+            trace.record(node, true);
+            output
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Graph;
+    use crate::RateLimit;
+    use crate::RunOptions;
+
+    #[test]
+    fn test_mock_clock() {
+        let clock = MockClock::new();
+        let trace = Trace::new();
+        let mut graph = Graph::new();
+        let nodes = (0..3)
+            .map(|_| {
+                let node = graph.add_task(|| async {});
+                graph.set_group(node, "api_calls");
+                node
+            })
+            .collect::<Vec<_>>();
+        let root = graph.add_child_task(nodes[0], |_: ()| async {}, 0).unwrap();
+        graph.set_rate_limit("api_calls", RateLimit::per_second(1));
+        graph.layer(trace.clone());
+
+        let options = RunOptions::new().clock(clock.clone());
+        block_on(&clock, graph.run_with(&options));
+        assert_eq!(clock.elapsed(), Duration::from_secs(2));
+        assert!(trace.ran(root));
+        assert_eq!(trace.started(), [nodes[0], root, nodes[1], nodes[2]]);
+        trace.assert_before(nodes[0], root);
+    }
+}
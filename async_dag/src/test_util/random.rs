@@ -0,0 +1,212 @@
+use crate::NodeIndex;
+use crate::TryGraph;
+use futures::future::poll_fn;
+use std::task::Poll;
+
+/// The maximum number of inputs of a generated node.
+pub const MAX_INPUTS: usize = 4;
+
+// SplitMix64, good enough for shaping graphs without pulling in a dependency.
+#[derive(Debug)]
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn chance(&mut self, probability: f64) -> bool {
+        ((self.next() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+}
+
+/// A generator of random, well typed graphs.
+///
+/// Every node outputs a `u64`: its index plus the wrapping sum of its inputs, which come from earlier nodes.
+/// Nodes yield to the executor a random number of times before completing,
+/// so completion order varies between nodes.
+#[derive(Debug, Clone)]
+pub struct RandomDag {
+    seed: u64,
+    nodes: usize,
+    max_inputs: usize,
+    edge_probability: f64,
+    failure_probability: f64,
+}
+
+/// A generated graph and what running it should produce.
+#[derive(Debug)]
+pub struct GeneratedDag {
+    /// The graph, failing with the [`NodeIndex`] of a failed node.
+    pub graph: TryGraph<'static, NodeIndex>,
+    /// The expected output of every node, if no node fails.
+    pub expected: Vec<u64>,
+    /// Nodes that fail when run.
+    pub failing: Vec<NodeIndex>,
+}
+
+impl RandomDag {
+    /// A generator seeded by `seed`, making 16 nodes with up to 2 inputs each and no failures.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            nodes: 16,
+            max_inputs: 2,
+            edge_probability: 0.5,
+            failure_probability: 0.0,
+        }
+    }
+
+    /// Sets the number of nodes.
+    pub fn nodes(mut self, nodes: usize) -> Self {
+        self.nodes = nodes;
+        self
+    }
+
+    /// Sets the maximum number of inputs of a node.
+    ///
+    /// **Panics** if `max_inputs` exceeds [`MAX_INPUTS`].
+    pub fn max_inputs(mut self, max_inputs: usize) -> Self {
+        assert!(
+            max_inputs <= MAX_INPUTS,
+            "At most {} inputs are supported",
+            MAX_INPUTS
+        );
+        self.max_inputs = max_inputs;
+        self
+    }
+
+    /// Sets the probability of every potential input becoming an actual one,
+    /// so lower probabilities make wider and shallower graphs.
+    pub fn edge_probability(mut self, probability: f64) -> Self {
+        self.edge_probability = probability;
+        self
+    }
+
+    /// Sets the probability of a node failing.
+    pub fn failure_probability(mut self, probability: f64) -> Self {
+        self.failure_probability = probability;
+        self
+    }
+
+    /// Generates a graph.
+    pub fn build(&self) -> GeneratedDag {
+        let mut rng = Rng(self.seed);
+        let mut graph = TryGraph::new();
+        let mut expected = vec![];
+        let mut failing = vec![];
+        for index in 0..self.nodes {
+            let parents = if index == 0 {
+                vec![]
+            } else {
+                let mut parents = vec![];
+                for _ in 0..self.max_inputs {
+                    if rng.chance(self.edge_probability) {
+                        parents.push(rng.below(index));
+                    }
+                }
+                parents
+            };
+            let node = NodeIndex::new(index);
+            let fails = rng.chance(self.failure_probability);
+            if fails {
+                failing.push(node);
+            }
+            let yields = rng.below(4);
+            let value = parents.iter().fold(index as u64, |sum, parent| {
+                sum.wrapping_add(expected[*parent])
+            });
+            expected.push(value);
+
+            let output = move |sum: u64| async move {
+                for _ in 0..yields {
+                    yield_now().await;
+                }
+                if fails {
+                    Err(node)
+                } else {
+                    Ok(sum.wrapping_add(index as u64))
+                }
+            };
+            let added = match parents.len() {
+                0 => graph.add_try_task(move || output(0)),
+                1 => graph.add_try_task(move |a: u64| output(a)),
+                2 => graph.add_try_task(move |a: u64, b: u64| output(a.wrapping_add(b))),
+                3 => graph.add_try_task(move |a: u64, b: u64, c: u64| {
+                    output(a.wrapping_add(b).wrapping_add(c))
+                }),
+                _ => graph.add_try_task(move |a: u64, b: u64, c: u64, d: u64| {
+                    output(a.wrapping_add(b).wrapping_add(c).wrapping_add(d))
+                }),
+            };
+            debug_assert_eq!(added, node);
+            for (input, parent) in parents.into_iter().enumerate() {
+                // Inputs are `u64`s from earlier nodes, so this can't fail.
+                graph
+                    .update_dependency(NodeIndex::new(parent), node, input as _)
+                    .unwrap();
+            }
+        }
+        GeneratedDag {
+            graph,
+            expected,
+            failing,
+        }
+    }
+}
+
+/// Yields to the executor once, letting the other futures of a run make progress.
+pub async fn yield_now() {
+    let mut yielded = false;
+    poll_fn(|cx| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_random_dag() {
+        for seed in 0..32 {
+            let GeneratedDag {
+                mut graph,
+                expected,
+                failing,
+            } = RandomDag::new(seed)
+                .nodes(24)
+                .max_inputs(MAX_INPUTS)
+                .failure_probability(if seed % 2 == 0 { 0.0 } else { 0.1 })
+                .build();
+            match block_on(graph.try_run()) {
+                Ok(()) => {
+                    assert!(failing.is_empty());
+                    for (index, value) in expected.into_iter().enumerate() {
+                        assert_eq!(
+                            graph.get_value::<u64>(NodeIndex::new(index)).unwrap(),
+                            value
+                        );
+                    }
+                }
+                Err(node) => assert!(failing.contains(&node)),
+            }
+        }
+    }
+}
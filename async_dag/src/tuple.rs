@@ -5,10 +5,10 @@ use crate::any::TypeInfo;
 use seq_macro::seq;
 use std::any::{type_name, Any, TypeId};
 
-/// Type used for indexing a [`TupleOption`].
+/// Type used for indexing a `TupleOption`.
 pub type TupleIndex = u8;
 
-/// The error that can happen when inserting to a [`TupleOption`].
+/// The error that can happen when inserting to a `TupleOption`.
 #[derive(Debug)]
 pub struct InsertError {
     /// The error kind.
@@ -35,17 +35,17 @@ impl std::fmt::Display for InsertError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("InsertError")
             .field("kind", &self.kind)
-            .field("value", &self.value.type_id())
+            .field("value", &(*self.value).type_id())
             .finish()
     }
 }
 
 impl std::error::Error for InsertError {}
 
-/// The result of inserting to a [`TupleOption`].
+/// The result of inserting to a `TupleOption`.
 pub type InsertResult = Result<(), InsertError>;
 
-/// The error that can happen when taking from [`TupleOption`].
+/// The error that can happen when taking from a `TupleOption`.
 #[derive(Debug)]
 pub struct TakeError {
     /// The first missing input's index.
@@ -62,8 +62,8 @@ impl std::fmt::Display for TakeError {
 
 impl std::error::Error for TakeError {}
 
-/// Implemented for all [`Sized`] + `'static` tuple of [`Option`]s.
-pub trait TupleOption<T: Tuple>: Default {
+/// Implemented for all [`Sized`] + `'static` + [`Send`] tuple of [`Option`]s.
+pub trait TupleOption<T: Tuple>: Default + Send {
     /// Returns index of the first element that is [`None`].
     fn first_none(&self) -> Option<TupleIndex>;
 
@@ -78,7 +78,7 @@ pub trait TupleOption<T: Tuple>: Default {
     fn take(&mut self) -> Result<T, TakeError>;
 }
 
-/// Implemented for all [`Sized`] + `'static` tuples.
+/// Implemented for all [`Sized`] + `'static` + [`Send`] tuples.
 pub trait Tuple: Sized {
     /// The corresponding tuple of [`Option`]s.
     type Option: TupleOption<Self>;
@@ -95,7 +95,7 @@ pub trait Tuple: Sized {
 macro_rules! tupl_impl {
     ($N:literal) => {
         seq!(i in 0..$N {
-            impl<#(T~i: Any,)*> TupleOption<(#(T~i,)*)> for (#(Option<T~i>,)*) {
+            impl<#(T~i: Any + Send,)*> TupleOption<(#(T~i,)*)> for (#(Option<T~i>,)*) {
                 fn first_none(&self) -> Option<TupleIndex> {
                     #(
                         if self.i.is_none() {
@@ -140,7 +140,7 @@ macro_rules! tupl_impl {
         });
 
         seq!(i in 0..$N {
-            impl<#(T~i: Any,)*> Tuple for (#(T~i,)*) {
+            impl<#(T~i: Any + Send,)*> Tuple for (#(T~i,)*) {
                 type Option = (#(Option<T~i>,)*);
 
                 const LEN: TupleIndex = $N;
@@ -165,6 +165,48 @@ seq!(N in 0..=12 {
     )*
 });
 
+/// A tuple index known at compile time.
+///
+/// Methods taking an `In<I>` only accept tasks with more than `I` inputs:
+///
+/// ```compile_fail
+/// use async_dag::{Graph, In};
+///
+/// let mut graph = Graph::new();
+/// let parent = graph.add_task(|| async { 1 });
+/// let _ = graph.add_child_task_at(parent, |_: i32, _: i32| async {}, In::<2>);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct In<const I: TupleIndex>;
+
+impl<const I: TupleIndex> From<In<I>> for TupleIndex {
+    fn from(_: In<I>) -> Self {
+        I
+    }
+}
+
+/// Implemented by [`In`] for every index that is in range of the tuple `T`.
+pub trait InputIndex<T: Tuple> {
+    /// The index.
+    const INDEX: TupleIndex;
+}
+
+macro_rules! input_index_impl {
+    ($N:literal, $I:literal) => {
+        seq!(i in 0..$N {
+            impl<#(T~i: Any + Send,)*> InputIndex<(#(T~i,)*)> for In<$I> {
+                const INDEX: TupleIndex = $I;
+            }
+        });
+    };
+}
+
+seq!(N in 1..=12 {
+    seq!(I in 0..N {
+        input_index_impl!(N, I);
+    });
+});
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,7 +214,7 @@ mod tests {
     #[test]
     fn test_mismatch_type_name() {
         let mut option: (Option<i32>,) = (None,);
-        let error = option.insert(0, Box::new(0.0f32)).unwrap_err();
+        let error = option.insert(0, DynAny::new(0.0f32)).unwrap_err();
         let expected_name = match error.kind {
             InsertErrorKind::TypeMismatch { expected_name, .. } => expected_name,
             _ => panic!("Expecting TypeMismatch"),
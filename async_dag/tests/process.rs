@@ -0,0 +1,70 @@
+use async_dag::process::WorkerError;
+use async_dag::process::WorkerOptions;
+use async_dag::process::WorkerPool;
+use async_dag::TryGraph;
+use futures::executor::block_on;
+use std::process::Command;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn worker() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_async_dag_test_worker"))
+}
+
+#[test]
+fn test_worker_pool() {
+    let pool = Arc::new(WorkerPool::new(2, worker));
+    let mut graph = TryGraph::<WorkerError>::new();
+    let nodes = (0..4u8)
+        .map(|i| {
+            let pool = pool.clone();
+            graph.add_try_task(move || pool.call(vec![i; 3]))
+        })
+        .collect::<Vec<_>>();
+    block_on(graph.try_run()).unwrap();
+    for (i, node) in nodes.into_iter().enumerate() {
+        assert_eq!(graph.get_value::<Vec<u8>>(node).unwrap(), vec![i as u8; 3]);
+    }
+}
+
+#[test]
+fn test_restart() {
+    let pool = WorkerPool::new(1, worker);
+    assert_eq!(block_on(pool.call(vec![1; 4])).unwrap(), vec![1; 4]);
+    assert!(matches!(
+        block_on(pool.call(b"exit".to_vec())),
+        Err(WorkerError::Crashed(_))
+    ));
+    assert_eq!(block_on(pool.call(vec![3; 4])).unwrap(), vec![3; 4]);
+    assert_eq!(pool.restarts(), 1);
+}
+
+#[test]
+fn test_max_frame() {
+    let pool = WorkerPool::with_options(1, worker, WorkerOptions::new().max_frame(8));
+    // Too large requests aren't sent.
+    assert!(matches!(
+        block_on(pool.call(vec![1; 9])),
+        Err(WorkerError::TooLarge(9))
+    ));
+    assert_eq!(pool.restarts(), 0);
+    assert!(matches!(
+        block_on(pool.call(b"huge".to_vec())),
+        Err(WorkerError::TooLarge(u64::MAX))
+    ));
+    assert_eq!(pool.restarts(), 1);
+    assert_eq!(block_on(pool.call(vec![2; 8])).unwrap(), vec![2; 8]);
+}
+
+#[test]
+fn test_timeout() {
+    let options = WorkerOptions::new().timeout(Duration::from_millis(100));
+    let pool = WorkerPool::with_options(1, worker, options);
+    assert_eq!(block_on(pool.call(vec![1; 4])).unwrap(), vec![1; 4]);
+    assert!(matches!(
+        block_on(pool.call(b"hang".to_vec())),
+        Err(WorkerError::TimedOut)
+    ));
+    assert_eq!(pool.restarts(), 1);
+    assert_eq!(block_on(pool.call(vec![2; 4])).unwrap(), vec![2; 4]);
+}
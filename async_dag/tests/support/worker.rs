@@ -0,0 +1,33 @@
+//! A worker for the `process` tests, echoing requests except for a few commands.
+
+use std::io::Read;
+use std::io::Write;
+use std::time::Duration;
+
+fn main() {
+    let mut stdin = std::io::stdin().lock();
+    let mut stdout = std::io::stdout().lock();
+    loop {
+        let mut len = [0; 8];
+        if stdin.read_exact(&mut len).is_err() {
+            return;
+        }
+        let mut request = vec![0; u64::from_le_bytes(len) as usize];
+        stdin.read_exact(&mut request).unwrap();
+        match &request[..] {
+            b"exit" => std::process::exit(1),
+            b"hang" => std::thread::sleep(Duration::from_secs(60)),
+            b"huge" => {
+                // Announces a reply no one should allocate.
+                stdout.write_all(&u64::MAX.to_le_bytes()).unwrap();
+                stdout.flush().unwrap();
+                std::thread::sleep(Duration::from_secs(60));
+            }
+            _ => {
+                stdout.write_all(&len).unwrap();
+                stdout.write_all(&request).unwrap();
+                stdout.flush().unwrap();
+            }
+        }
+    }
+}
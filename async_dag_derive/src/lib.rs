@@ -0,0 +1,118 @@
+//! Derive macros for `async_dag`.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::parse_macro_input;
+use syn::parse_quote;
+use syn::Data;
+use syn::DeriveInput;
+use syn::Fields;
+use syn::Type;
+
+/// Derives `async_dag::FieldsTask` for a struct whose named fields are the task's inputs.
+///
+/// The struct must have an inherent `async fn run(self) -> Result<Ok, Err>` whose future is `Send`,
+/// and declare the output types by `#[task(ok = Ok, err = Err)]`, where `err` defaults to `Infallible`.
+///
+/// Inputs are named after the fields, see `TryGraph::add_struct_task`.
+#[proc_macro_derive(Task, attributes(task))]
+pub fn derive_task(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            Fields::Unit => return expand_fields(&input, &[], &[]),
+            Fields::Unnamed(_) => {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "Task can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "Task can only be derived for structs",
+            ))
+        }
+    };
+    let idents = fields
+        .iter()
+        .map(|field| field.ident.clone().unwrap())
+        .collect::<Vec<_>>();
+    let types = fields
+        .iter()
+        .map(|field| field.ty.clone())
+        .collect::<Vec<_>>();
+    expand_fields(&input, &idents, &types)
+}
+
+fn expand_fields(
+    input: &DeriveInput,
+    idents: &[syn::Ident],
+    types: &[Type],
+) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "Task can't be derived for generic structs",
+        ));
+    }
+
+    let mut ok: Option<Type> = None;
+    let mut err: Type = parse_quote!(::std::convert::Infallible);
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("task"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("ok") {
+                ok = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("err") {
+                err = meta.value()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("expecting `ok` or `err`"))
+            }
+        })?;
+    }
+    let ok =
+        ok.ok_or_else(|| syn::Error::new_spanned(name, "missing `#[task(ok = Type)]` attribute"))?;
+    let names = idents.iter().map(|ident| ident.to_string());
+
+    Ok(quote! {
+        impl ::async_dag::FieldsTask for #name {
+            type Inputs = (#(#types,)*);
+            type Ok = #ok;
+            type Err = #err;
+            const INPUT_NAMES: &'static [&'static str] = &[#(#names),*];
+
+            fn from_inputs((#(#idents,)*): Self::Inputs) -> Self {
+                Self { #(#idents),* }
+            }
+
+            fn call(
+                self,
+            ) -> ::std::pin::Pin<
+                ::std::boxed::Box<
+                    dyn ::std::future::Future<Output = ::std::result::Result<#ok, #err>>
+                        + ::std::marker::Send
+                        + 'static,
+                >,
+            > {
+                ::std::boxed::Box::pin(Self::run(self))
+            }
+        }
+    })
+}
@@ -12,9 +12,17 @@ fn run(command: &mut Command) -> Result<()> {
 
 fn main() -> Result<()> {
     run(Command::new("cargo").args(["fmt", "--check"]))?;
-    run(Command::new("cargo").args(["clippy", "--", "-D", "warnings"]))?;
+    run(Command::new("cargo").args(["clippy", "--all-features", "--", "-D", "warnings"]))?;
     run(Command::new("cargo").args(["doc"]))?;
-    run(Command::new("cargo").args(["test"]))?;
+    run(Command::new("cargo").args(["test", "--all-features"]))?;
+    run(Command::new("cargo").args([
+        "check",
+        "-p",
+        "async_dag",
+        "--examples",
+        "--target",
+        "wasm32-unknown-unknown",
+    ]))?;
     run(Command::new("cargo")
         .args(["sync-readme", "-c"])
         .current_dir("async_dag"))?;